@@ -0,0 +1,485 @@
+//! Automatic audit logging for Aether Vault operations.
+//!
+//! Every capability operation performed through the client produces an
+//! [`AuditEvent`] which is handed to the configured [`AuditSink`].
+
+use crate::capability::{Action, Capability, Domain};
+use crate::config::LogFormat;
+use crate::error::{Result, VaultError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+/// Audit event severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditLevel {
+    /// Routine operation
+    Info,
+    /// Operation that deserves attention
+    Warning,
+    /// Security-relevant failure
+    Critical,
+}
+
+/// Outcome of an audited operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    /// Operation succeeded
+    Success,
+    /// Operation failed with the given error code
+    Failure(String),
+}
+
+/// Single audit record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// Event timestamp
+    pub timestamp: DateTime<Utc>,
+
+    /// Event severity
+    pub level: AuditLevel,
+
+    /// Operation name (request, access, revoke, ...)
+    pub operation: String,
+
+    /// Capability involved, if any
+    pub capability_id: Option<Uuid>,
+
+    /// Capability domain
+    pub domain: Option<Domain>,
+
+    /// Capability action
+    pub action: Option<Action>,
+
+    /// Target resource
+    pub target: Option<String>,
+
+    /// Subject identity
+    pub subject: Option<String>,
+
+    /// Enterprise namespace the operation ran in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+
+    /// Caller-supplied reason for the operation
+    pub reason: Option<String>,
+
+    /// Operation outcome
+    pub outcome: AuditOutcome,
+
+    /// Request id of the transport call behind the event, if it reached one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<Uuid>,
+}
+
+impl AuditEvent {
+    /// Create a new audit event for an operation
+    pub fn new(operation: &str) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            level: AuditLevel::Info,
+            operation: operation.to_string(),
+            capability_id: None,
+            domain: None,
+            action: None,
+            target: None,
+            subject: None,
+            namespace: None,
+            reason: None,
+            outcome: AuditOutcome::Success,
+            request_id: None,
+        }
+    }
+
+    /// Create a new audit event describing a capability operation
+    pub fn for_capability(operation: &str, capability: &Capability) -> Self {
+        let mut event = Self::new(operation);
+        event.capability_id = Some(capability.id);
+        event.domain = Some(capability.domain.clone());
+        event.action = Some(capability.action.clone());
+        event.target = Some(capability.target.clone());
+        event.subject = Some(capability.subject.clone());
+        event
+    }
+
+    /// Attach a reason to the event
+    pub fn with_reason(mut self, reason: Option<&str>) -> Self {
+        self.reason = reason.map(|r| r.to_string());
+        self
+    }
+
+    /// Tag the event with the request id its transport call was sent with
+    pub fn with_request_id(mut self, request_id: Uuid) -> Self {
+        self.request_id = Some(request_id);
+        self
+    }
+
+    /// Record the outcome of the operation
+    ///
+    /// A failure's own request id, if any, replaces one set earlier.
+    pub fn with_outcome<T>(mut self, result: &Result<T>) -> Self {
+        if let Err(e) = result {
+            self.level = AuditLevel::Warning;
+            self.outcome = AuditOutcome::Failure(e.error_code().to_string());
+            self.request_id = e.request_id().or(self.request_id);
+        }
+        self
+    }
+}
+
+/// Destination for audit events
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Record an audit event
+    async fn record(&self, event: AuditEvent);
+}
+
+/// Sink writing one line per event to stdout
+#[derive(Debug, Clone)]
+pub struct StdoutSink {
+    format: LogFormat,
+}
+
+/// Sink discarding every event
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullSink;
+
+impl StdoutSink {
+    /// Create a new stdout sink
+    pub fn new(format: LogFormat) -> Self {
+        Self { format }
+    }
+
+    /// Render an event as a single line in the configured format
+    pub fn render(&self, event: &AuditEvent) -> String {
+        match self.format {
+            LogFormat::Json => serde_json::to_string(event)
+                .unwrap_or_else(|e| format!("{{\"error\":\"unserializable audit event: {}\"}}", e)),
+            LogFormat::Text => {
+                let mut line = format!(
+                    "{} {:?} {}",
+                    event.timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                    event.level,
+                    event.operation
+                );
+                let fields = [
+                    ("capability", event.capability_id.map(|id| id.to_string())),
+                    ("domain", event.domain.as_ref().map(|d| d.to_string())),
+                    ("action", event.action.as_ref().map(|a| a.to_string())),
+                    ("target", event.target.clone()),
+                    ("subject", event.subject.clone()),
+                    ("namespace", event.namespace.clone()),
+                    ("reason", event.reason.clone()),
+                    ("request_id", event.request_id.map(|id| id.to_string())),
+                ];
+                for (name, value) in fields {
+                    if let Some(value) = value {
+                        line.push_str(&format!(" {}={:?}", name, value));
+                    }
+                }
+                match &event.outcome {
+                    AuditOutcome::Success => line.push_str(" outcome=success"),
+                    AuditOutcome::Failure(code) => line.push_str(&format!(" outcome=failure({})", code)),
+                }
+                line
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for StdoutSink {
+    async fn record(&self, event: AuditEvent) {
+        println!("{}", self.render(&event));
+    }
+}
+
+#[async_trait]
+impl AuditSink for NullSink {
+    async fn record(&self, _event: AuditEvent) {}
+}
+
+/// Sink appending newline-delimited JSON events to a file
+///
+/// Events are queued on a bounded channel and written by a background
+/// thread, so recording never waits on disk I/O. When the queue is full the
+/// event is dropped and counted; see [`FileSink::dropped_events`]. The file
+/// is rotated to `<path>.1`, `<path>.2`, ... once it would grow past the
+/// configured size.
+#[derive(Debug)]
+pub struct FileSink {
+    sender: mpsc::Sender<FileCommand>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Builder for [`FileSink`]
+#[derive(Debug, Clone)]
+pub struct FileSinkBuilder {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    fsync: bool,
+    buffer_size: usize,
+}
+
+/// Message handled by the file writer thread
+#[derive(Debug)]
+enum FileCommand {
+    Write(AuditEvent),
+    Flush(oneshot::Sender<()>),
+}
+
+/// File currently being appended to
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    max_files: usize,
+    fsync: bool,
+}
+
+impl FileSink {
+    /// Start building a sink writing to `path`
+    pub fn builder<P: AsRef<Path>>(path: P) -> FileSinkBuilder {
+        FileSinkBuilder {
+            path: path.as_ref().to_path_buf(),
+            max_bytes: 10 * 1024 * 1024,
+            max_files: 5,
+            fsync: false,
+            buffer_size: 1024,
+        }
+    }
+
+    /// Number of events dropped because the write buffer was full
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Wait until every event queued so far has been written
+    pub async fn flush(&self) {
+        let (done, written) = oneshot::channel();
+        if self.sender.send(FileCommand::Flush(done)).await.is_ok() {
+            let _ = written.await;
+        }
+    }
+}
+
+impl FileSinkBuilder {
+    /// Rotate once the file would grow past this many bytes
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Number of rotated files to keep
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    /// Sync the file to disk after every event
+    pub fn fsync(mut self, fsync: bool) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
+    /// Number of events that may be queued before new ones are dropped
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    /// Open the file and start the writer thread
+    pub fn build(self) -> Result<FileSink> {
+        if self.max_bytes == 0 {
+            return Err(VaultError::Validation("audit file max_bytes must be positive".to_string()));
+        }
+        if self.max_files == 0 {
+            return Err(VaultError::Validation("audit file max_files must be positive".to_string()));
+        }
+        if self.buffer_size == 0 {
+            return Err(VaultError::Validation("audit file buffer_size must be positive".to_string()));
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let mut writer = RotatingFile {
+            size: file.metadata()?.len(),
+            path: self.path,
+            file,
+            max_bytes: self.max_bytes,
+            max_files: self.max_files,
+            fsync: self.fsync,
+        };
+
+        let (sender, mut receiver) = mpsc::channel(self.buffer_size);
+        std::thread::Builder::new()
+            .name("aether-vault-audit".to_string())
+            .spawn(move || {
+                while let Some(command) = receiver.blocking_recv() {
+                    match command {
+                        FileCommand::Write(event) => {
+                            if let Err(e) = writer.write(&event) {
+                                tracing::error!("Failed to write audit event to {}: {}", writer.path.display(), e);
+                            }
+                        }
+                        FileCommand::Flush(done) => {
+                            if let Err(e) = writer.file.flush() {
+                                tracing::error!("Failed to flush audit file {}: {}", writer.path.display(), e);
+                            }
+                            let _ = done.send(());
+                        }
+                    }
+                }
+            })?;
+
+        Ok(FileSink {
+            sender,
+            dropped: Arc::new(AtomicU64::new(0)),
+        })
+    }
+}
+
+impl RotatingFile {
+    /// Append one event, rotating first if it would not fit
+    fn write(&mut self, event: &AuditEvent) -> Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        self.file.write_all(&line)?;
+        if self.fsync {
+            self.file.sync_data()?;
+        }
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shift `<path>.N` to `<path>.N+1`, dropping the oldest, and reopen
+    fn rotate(&mut self) -> Result<()> {
+        let _ = std::fs::remove_file(self.rotated_path(self.max_files));
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                std::fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated_path(1))?;
+
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileSink {
+    async fn record(&self, event: AuditEvent) {
+        match self.sender.try_send(FileCommand::Write(event)) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                tracing::warn!("Audit buffer full, dropped event ({} dropped so far)", dropped);
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                tracing::error!("Audit writer stopped, dropped event");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_outcome() {
+        let ok: Result<()> = Ok(());
+        let request_id = Uuid::new_v4();
+        let event = AuditEvent::new("access").with_request_id(request_id).with_outcome(&ok);
+        assert_eq!(event.outcome, AuditOutcome::Success);
+        assert_eq!(event.request_id, Some(request_id));
+
+        let err: Result<()> = Err(VaultError::AccessDenied("test".to_string()));
+        let event = AuditEvent::new("access").with_outcome(&err);
+        assert_eq!(event.outcome, AuditOutcome::Failure("ACCESS_DENIED".to_string()));
+        assert_eq!(event.level, AuditLevel::Warning);
+    }
+
+    #[test]
+    fn test_stdout_sink_formats() {
+        let capability = Capability::new(
+            Domain::Database,
+            Action::Read,
+            "users".to_string(),
+            crate::capability::CapabilityContext::default(),
+            std::time::Duration::from_secs(60),
+            "vault".to_string(),
+            "svc".to_string(),
+        );
+        let event = AuditEvent::for_capability("access", &capability).with_reason(Some("nightly report"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&StdoutSink::new(LogFormat::Json).render(&event)).unwrap();
+        assert_eq!(json["operation"], "access");
+        assert_eq!(json["target"], "users");
+
+        let text = StdoutSink::new(LogFormat::Text).render(&event);
+        assert!(text.contains(" Info access "));
+        assert!(text.contains("domain=\"database\""));
+        assert!(text.contains("reason=\"nightly report\""));
+        assert!(text.ends_with("outcome=success"));
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let sink = FileSink::builder(&path).max_bytes(512).max_files(2).build().unwrap();
+
+        for _ in 0..20 {
+            sink.record(AuditEvent::new("access").with_reason(Some("rotation test"))).await;
+        }
+        sink.flush().await;
+
+        assert!(path.exists());
+        assert!(dir.path().join("audit.log.1").exists());
+        assert!(dir.path().join("audit.log.2").exists());
+        assert!(!dir.path().join("audit.log.3").exists());
+        assert_eq!(sink.dropped_events(), 0);
+
+        let current = std::fs::read_to_string(&path).unwrap();
+        assert!(current.len() <= 512);
+        for line in current.lines() {
+            let event: AuditEvent = serde_json::from_str(line).unwrap();
+            assert_eq!(event.operation, "access");
+        }
+    }
+
+    #[test]
+    fn test_file_sink_rejects_invalid_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        assert!(FileSink::builder(&path).max_files(0).build().is_err());
+        assert!(FileSink::builder(&path).buffer_size(0).build().is_err());
+    }
+}
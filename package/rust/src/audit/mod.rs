@@ -1,3 +1,3 @@
 pub mod audit;
 
-pub use audit::{Auditor, AuditEvent, AuditLevel, AuditLogger};
\ No newline at end of file
+pub use audit::{AuditEvent, AuditLevel, AuditOutcome, AuditSink, FileSink, FileSinkBuilder, NullSink, StdoutSink};
@@ -0,0 +1,451 @@
+//! In-memory caches for Aether Vault.
+//!
+//! Nothing here is ever written to disk; entries live only as long as the
+//! client holding them.
+
+use crate::capability::{Capability, CapabilityRequest};
+use crate::clock::{Clock, SystemClock};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+/// Capability cache with optional LRU bound
+///
+/// Inserting counts as a use. When `max_size` is set and the cache is full,
+/// expired entries are dropped first, then the least recently used one.
+/// Eviction is local only: evicted capabilities are not revoked.
+///
+/// The identity a capability was requested under, the request that issued
+/// it, and the ids of capabilities it replaced are kept only while the
+/// capability itself is cached.
+#[derive(Debug, Clone, Default)]
+pub struct CapabilityCache {
+    /// Cached capabilities with their last-use tick
+    entries: HashMap<Uuid, (Capability, u64)>,
+
+    /// Tags of the identities cached capabilities were requested under
+    owners: HashMap<Uuid, String>,
+
+    /// Requests that issued cached capabilities
+    origins: HashMap<Uuid, CapabilityRequest>,
+
+    /// Ids of replaced capabilities, mapped to the cached capability replacing them
+    replaced_by: HashMap<Uuid, Uuid>,
+
+    /// Capability ids ordered by last use
+    recency: BTreeMap<u64, Uuid>,
+
+    /// Next use tick
+    tick: u64,
+
+    /// Maximum number of entries, if bounded
+    max_size: Option<usize>,
+}
+
+impl CapabilityCache {
+    /// Create a cache holding at most `max_size` capabilities (`None` for unbounded)
+    pub fn new(max_size: Option<usize>) -> Self {
+        Self {
+            max_size,
+            ..Self::default()
+        }
+    }
+
+    /// Insert or replace a capability, marking it most recently used
+    ///
+    /// Returns the capabilities evicted to make room.
+    pub fn insert(&mut self, capability: Capability) -> Vec<Capability> {
+        self.take(&capability.id);
+
+        let mut evicted = Vec::new();
+        if let Some(max_size) = self.max_size {
+            if self.entries.len() >= max_size {
+                evicted = self.remove_expired(Utc::now());
+            }
+            while self.entries.len() >= max_size {
+                let Some((_, id)) = self.recency.pop_first() else { break };
+                if let Some((capability, _)) = self.entries.remove(&id) {
+                    self.forget(&id);
+                    evicted.push(capability);
+                }
+            }
+        }
+
+        let tick = self.next_tick();
+        self.recency.insert(tick, capability.id);
+        self.entries.insert(capability.id, (capability, tick));
+        evicted
+    }
+
+    /// Get a cached capability without marking it used
+    pub fn get(&self, id: &Uuid) -> Option<&Capability> {
+        self.entries.get(id).map(|(capability, _)| capability)
+    }
+
+    /// Tag a cached capability with the identity it was requested under
+    ///
+    /// Ignored when the capability is not cached. Re-inserting an entry
+    /// keeps its tag.
+    pub fn set_owner(&mut self, id: Uuid, owner: String) {
+        if self.entries.contains_key(&id) {
+            self.owners.insert(id, owner);
+        }
+    }
+
+    /// Cached capabilities tagged with `owner`
+    pub fn owned_by<'a>(&'a self, owner: &'a str) -> impl Iterator<Item = &'a Capability> + 'a {
+        self.values().filter(move |capability| self.owners.get(&capability.id).map(String::as_str) == Some(owner))
+    }
+
+    /// Move every capability tagged `from` over to `to`
+    ///
+    /// For an identity whose token was rotated.
+    pub fn reassign(&mut self, from: &str, to: &str) {
+        for owner in self.owners.values_mut().filter(|owner| owner.as_str() == from) {
+            *owner = to.to_string();
+        }
+    }
+
+    /// Record the request that issued a cached capability
+    ///
+    /// Ignored when the capability is not cached.
+    pub fn set_origin(&mut self, id: Uuid, request: CapabilityRequest) {
+        if self.entries.contains_key(&id) {
+            self.origins.insert(id, request);
+        }
+    }
+
+    /// Request that issued a cached capability, if recorded
+    pub fn origin(&self, id: &Uuid) -> Option<&CapabilityRequest> {
+        self.origins.get(id)
+    }
+
+    /// Drop `old` in favour of the cached capability `new`
+    ///
+    /// Later lookups of `old`, or of anything `old` itself replaced, resolve
+    /// to `new` through [`CapabilityCache::replacement`].
+    pub fn replace(&mut self, old: &Uuid, new: Uuid) {
+        if !self.entries.contains_key(&new) {
+            return;
+        }
+        let mut aliases: Vec<Uuid> = self.replaced_by.iter()
+            .filter(|(_, to)| *to == old)
+            .map(|(from, _)| *from)
+            .collect();
+        aliases.push(*old);
+
+        self.remove(old);
+        for alias in aliases {
+            self.replaced_by.insert(alias, new);
+        }
+    }
+
+    /// Cached capability that replaced `id`, if any
+    pub fn replacement(&self, id: &Uuid) -> Option<&Capability> {
+        self.replaced_by.get(id).and_then(|new| self.get(new))
+    }
+
+    /// Remove a capability
+    pub fn remove(&mut self, id: &Uuid) -> Option<Capability> {
+        let capability = self.take(id)?;
+        self.forget(id);
+        Some(capability)
+    }
+
+    /// Remove a capability's entry, keeping what is recorded about it
+    fn take(&mut self, id: &Uuid) -> Option<Capability> {
+        let (capability, tick) = self.entries.remove(id)?;
+        self.recency.remove(&tick);
+        Some(capability)
+    }
+
+    /// Drop the owner, origin and replacement records of a capability leaving the cache
+    fn forget(&mut self, id: &Uuid) {
+        self.owners.remove(id);
+        self.origins.remove(id);
+        self.replaced_by.retain(|_, to| to != id);
+    }
+
+    /// Drop every capability that expired at or before `now`
+    pub fn remove_expired(&mut self, now: DateTime<Utc>) -> Vec<Capability> {
+        let expired: Vec<Uuid> = self.entries.values()
+            .filter(|(capability, _)| capability.expires_at <= now)
+            .map(|(capability, _)| capability.id)
+            .collect();
+        expired.iter().filter_map(|id| self.remove(id)).collect()
+    }
+
+    /// Iterate over cached capabilities
+    pub fn values(&self) -> impl Iterator<Item = &Capability> {
+        self.entries.values().map(|(capability, _)| capability)
+    }
+
+    /// Number of cached capabilities
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove every capability
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.owners.clear();
+        self.origins.clear();
+        self.replaced_by.clear();
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+/// Short-lived cache of access responses
+///
+/// Entries are keyed by capability id and target, kept as serialized JSON
+/// that is zeroized when dropped, and expire `ttl` after being stored. When
+/// full, the oldest entry is evicted.
+pub struct ResponseCache {
+    /// Payloads with the time they were stored
+    entries: HashMap<(Uuid, String), (DateTime<Utc>, Zeroizing<Vec<u8>>)>,
+
+    /// How long a payload may be served
+    ttl: Duration,
+
+    /// Maximum number of entries
+    max_size: usize,
+
+    /// Time source for storage and freshness
+    clock: Arc<dyn Clock>,
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("entries", &self.entries.len())
+            .field("ttl", &self.ttl)
+            .field("max_size", &self.max_size)
+            .finish()
+    }
+}
+
+impl ResponseCache {
+    /// Create a cache serving payloads for up to `ttl`
+    pub fn new(max_size: usize, ttl: Duration) -> Self {
+        Self::with_clock(max_size, ttl, Arc::new(SystemClock))
+    }
+
+    /// Create a cache that reads the time from `clock`
+    pub fn with_clock(max_size: usize, ttl: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            entries: HashMap::new(),
+            ttl,
+            max_size,
+            clock,
+        }
+    }
+
+    /// Whether a payload stored at `stored_at` may still be served
+    fn is_fresh(&self, stored_at: DateTime<Utc>) -> bool {
+        let ttl = chrono::Duration::from_std(self.ttl).unwrap_or(chrono::Duration::max_value());
+        self.clock.now() < stored_at + ttl
+    }
+
+    /// Cached payload for a capability and target, if still fresh
+    pub fn get(&mut self, capability_id: Uuid, target: &str) -> Option<Zeroizing<Vec<u8>>> {
+        let key = (capability_id, target.to_string());
+        match self.entries.get(&key) {
+            Some((stored_at, payload)) if self.is_fresh(*stored_at) => Some(payload.clone()),
+            Some(_) => {
+                self.entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Store a payload for a capability and target
+    pub fn insert(&mut self, capability_id: Uuid, target: &str, payload: Zeroizing<Vec<u8>>) {
+        let stale: Vec<_> = self.entries.iter()
+            .filter(|(_, (stored_at, _))| !self.is_fresh(*stored_at))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            self.entries.remove(&key);
+        }
+        if self.entries.len() >= self.max_size {
+            let oldest = self.entries.iter()
+                .min_by_key(|(_, (stored_at, _))| *stored_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert((capability_id, target.to_string()), (self.clock.now(), payload));
+    }
+
+    /// Drop every payload fetched with a capability
+    pub fn remove_capability(&mut self, capability_id: Uuid) {
+        self.entries.retain(|(id, _), _| *id != capability_id);
+    }
+
+    /// Number of cached payloads
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove every payload
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::{Action, CapabilityContext, Domain};
+    use std::time::Duration;
+
+    fn capability(target: &str) -> Capability {
+        Capability::new(
+            Domain::Database,
+            Action::Read,
+            target.to_string(),
+            CapabilityContext::default(),
+            Duration::from_secs(300),
+            "vault".to_string(),
+            "client".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut cache = CapabilityCache::new(Some(2));
+        let first = capability("first");
+        let second = capability("second");
+        let third = capability("third");
+
+        assert!(cache.insert(first.clone()).is_empty());
+        assert!(cache.insert(second.clone()).is_empty());
+        // Re-inserting marks an entry used
+        assert!(cache.insert(first.clone()).is_empty());
+
+        let evicted = cache.insert(third.clone());
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].id, second.id);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&first.id).is_some());
+        assert!(cache.get(&third.id).is_some());
+
+        // Replacing an entry never evicts
+        assert!(cache.insert(first.clone()).is_empty());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_expired_evicted_first() {
+        let mut cache = CapabilityCache::new(Some(2));
+        let mut expired = capability("expired");
+        expired.expires_at = Utc::now() - chrono::Duration::seconds(1);
+        let valid = capability("valid");
+
+        cache.insert(expired.clone());
+        cache.insert(valid.clone());
+        let evicted = cache.insert(capability("fresh"));
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].id, expired.id);
+        assert!(cache.get(&valid.id).is_some());
+
+        let mut unbounded = CapabilityCache::new(None);
+        unbounded.insert(expired.clone());
+        unbounded.insert(valid.clone());
+        assert_eq!(unbounded.remove_expired(Utc::now()).len(), 1);
+        assert_eq!(unbounded.len(), 1);
+    }
+
+    #[test]
+    fn test_origins_and_replacements_follow_entries() {
+        let mut cache = CapabilityCache::new(Some(2));
+        let first = capability("first");
+        let request = CapabilityRequest::new(
+            Domain::Database,
+            Action::Read,
+            "first".to_string(),
+            CapabilityContext::default(),
+            Duration::from_secs(300),
+        );
+        cache.insert(first.clone());
+        cache.set_owner(first.id, "alice".to_string());
+        cache.set_origin(first.id, request.clone());
+        cache.set_origin(Uuid::new_v4(), request.clone());
+        assert_eq!(cache.origins.len(), 1);
+
+        // Re-inserting an entry keeps its owner and origin
+        cache.insert(first.clone());
+        assert!(cache.origin(&first.id).is_some());
+        assert_eq!(cache.owned_by("alice").count(), 1);
+        assert_eq!(cache.owned_by("bob").count(), 0);
+        cache.reassign("alice", "bob");
+        assert_eq!(cache.owned_by("bob").count(), 1);
+
+        // Replacements chain, and the replaced entry leaves the cache
+        let second = capability("first");
+        cache.insert(second.clone());
+        cache.replace(&first.id, second.id);
+        assert!(cache.get(&first.id).is_none());
+        assert!(cache.origin(&first.id).is_none());
+        assert_eq!(cache.replacement(&first.id).unwrap().id, second.id);
+
+        let third = capability("first");
+        cache.insert(third.clone());
+        cache.replace(&second.id, third.id);
+        assert_eq!(cache.replacement(&first.id).unwrap().id, third.id);
+        assert_eq!(cache.replacement(&second.id).unwrap().id, third.id);
+
+        // Evicting the replacement drops its records
+        cache.set_origin(third.id, request);
+        cache.insert(capability("other"));
+        cache.insert(capability("another"));
+        assert!(cache.get(&third.id).is_none());
+        assert!(cache.replacement(&first.id).is_none());
+        assert!(cache.owners.is_empty());
+        assert!(cache.origins.is_empty());
+        assert!(cache.replaced_by.is_empty());
+    }
+
+    #[test]
+    fn test_response_cache_expiry_and_eviction() {
+        let id = Uuid::new_v4();
+        let clock = crate::clock::MockClock::new(Utc::now());
+        let mut cache = ResponseCache::with_clock(2, Duration::from_millis(50), Arc::new(clock.clone()));
+        cache.insert(id, "users", Zeroizing::new(b"{}".to_vec()));
+        assert_eq!(cache.get(id, "users").unwrap().as_slice(), b"{}");
+        assert!(cache.get(id, "orders").is_none());
+
+        clock.advance(Duration::from_millis(1));
+        cache.insert(id, "orders", Zeroizing::new(b"[]".to_vec()));
+        clock.advance(Duration::from_millis(1));
+        cache.insert(Uuid::new_v4(), "users", Zeroizing::new(b"1".to_vec()));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(id, "users").is_none());
+
+        cache.remove_capability(id);
+        assert_eq!(cache.len(), 1);
+
+        clock.advance(Duration::from_millis(60));
+        assert!(cache.get(id, "orders").is_none());
+    }
+}
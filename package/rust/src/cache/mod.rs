@@ -0,0 +1,3 @@
+pub mod cache;
+
+pub use cache::{CapabilityCache, ResponseCache};
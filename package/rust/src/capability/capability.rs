@@ -3,16 +3,69 @@
 //! Implements strong typing for capabilities with domain-specific
 //! validation and lifetime management.
 
+use crate::clock::{Clock, SystemClock};
+use crate::portable::{context_allows, day_allowed, in_window, ip_allowed, is_expired, parse_ip_constraint, within_use_limit};
+pub use crate::portable::{Action, Domain};
 use crate::error::{CapabilityError, Result};
-use chrono::{DateTime, Utc};
+use base64::Engine;
+use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::fmt;
+use std::collections::{HashSet, VecDeque};
+use std::net::IpAddr;
 use uuid::Uuid;
 
+/// Wire format version written into new capabilities and requests
+///
+/// Bump when the serialized layout changes, and teach
+/// [`Capability::migrate`] to upgrade the previous version.
+///
+/// - 1: the signature covers the identifying fields only
+/// - 2: the signature also covers the version and the context
+pub const CURRENT_CAPABILITY_VERSION: u16 = 2;
+
+/// Version of payloads predating the `version` field
+fn unversioned() -> u16 {
+    1
+}
+
+/// Serde helper writing a TTL as whole seconds, the wire form used by every endpoint
+///
+/// Use with `#[serde(with = "ttl_seconds")]`. Sub-second parts are dropped.
+/// Deserializing also accepts the `{"secs", "nanos"}` form older clients sent.
+pub mod ttl_seconds {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum TtlRepr {
+        Seconds(u64),
+        Legacy { secs: u64, nanos: u32 },
+    }
+
+    /// Serialize `ttl` as an integer number of seconds
+    pub fn serialize<S: Serializer>(ttl: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(ttl.as_secs())
+    }
+
+    /// Deserialize a TTL from seconds or the legacy `{"secs", "nanos"}` form
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(match TtlRepr::deserialize(deserializer)? {
+            TtlRepr::Seconds(secs) => Duration::from_secs(secs),
+            TtlRepr::Legacy { secs, nanos } => Duration::new(secs, nanos),
+        })
+    }
+}
+
 /// Capability token with strong typing and lifetime management
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// The signature is redacted from `Debug` output.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Capability {
+    /// Wire format version, see [`CURRENT_CAPABILITY_VERSION`]
+    #[serde(default = "unversioned")]
+    pub version: u16,
+    
     /// Unique capability identifier
     pub id: Uuid,
     
@@ -42,10 +95,33 @@ pub struct Capability {
     
     /// Capability signature
     pub signature: Vec<u8>,
+    
+    /// Capability this one was attenuated from
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+}
+
+impl std::fmt::Debug for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Capability")
+            .field("version", &self.version)
+            .field("id", &self.id)
+            .field("domain", &self.domain)
+            .field("action", &self.action)
+            .field("target", &self.target)
+            .field("context", &self.context)
+            .field("issued_at", &self.issued_at)
+            .field("expires_at", &self.expires_at)
+            .field("issuer", &self.issuer)
+            .field("subject", &self.subject)
+            .field("signature", &"***")
+            .field("parent_id", &self.parent_id)
+            .finish()
+    }
 }
 
 /// Capability context constraints
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CapabilityContext {
     /// Allowed environments
     pub environments: Option<HashSet<String>>,
@@ -75,10 +151,42 @@ pub struct TimeWindow {
     pub end: DateTime<Utc>,
     /// Allowed days of week (0=Sunday, 6=Saturday)
     pub days_of_week: Option<Vec<u8>>,
+    /// IANA time zone the days of week are evaluated in (UTC if absent)
+    #[serde(default)]
+    pub tz: Option<String>,
+}
+
+impl TimeWindow {
+    /// Whether `now` falls inside the window
+    ///
+    /// `start` and `end` are absolute instants; the day of week is taken in
+    /// `tz`. A window with an unknown time zone allows nothing.
+    pub fn allows(&self, now: DateTime<Utc>) -> bool {
+        if !in_window(now, self.start, self.end) {
+            return false;
+        }
+
+        if self.days_of_week.is_none() {
+            return true;
+        }
+        let current_day = match &self.tz {
+            Some(tz) => match tz.parse::<chrono_tz::Tz>() {
+                Ok(tz) => now.with_timezone(&tz).weekday(),
+                Err(_) => return false,
+            },
+            None => now.weekday(),
+        };
+        day_allowed(self.days_of_week.as_deref(), current_day.num_days_from_sunday() as u8)
+    }
+
+    /// Time zone name, with UTC standing in for an absent one
+    fn zone(&self) -> &str {
+        self.tz.as_deref().unwrap_or("UTC")
+    }
 }
 
 /// Usage limits
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UsageLimits {
     /// Maximum number of uses
     pub max_uses: Option<u32>,
@@ -86,11 +194,18 @@ pub struct UsageLimits {
     pub uses_per_window: Option<(u32, chrono::Duration)>,
     /// Current usage count
     pub current_uses: u32,
+    /// Timestamps of uses still inside the rate window
+    #[serde(default)]
+    pub window_uses: VecDeque<DateTime<Utc>>,
 }
 
 /// Capability request for creating new capabilities
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CapabilityRequest {
+    /// Wire format version, see [`CURRENT_CAPABILITY_VERSION`]
+    #[serde(default = "unversioned")]
+    pub version: u16,
+    
     /// Domain of access
     pub domain: Domain,
     
@@ -103,95 +218,82 @@ pub struct CapabilityRequest {
     /// Request context
     pub context: CapabilityContext,
     
-    /// Requested TTL
+    /// Requested TTL, sent as `ttl_seconds`
+    #[serde(rename = "ttl_seconds", alias = "ttl", with = "ttl_seconds")]
     pub ttl: std::time::Duration,
     
     /// Justification for access
     pub justification: Option<String>,
+
+    /// Key letting the server dedupe retries of this request
+    ///
+    /// Sent as the `Idempotency-Key` header rather than in the body. The
+    /// client assigns one per logical request when unset.
+    #[serde(skip)]
+    pub idempotency_key: Option<Uuid>,
 }
 
-/// Access domains
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Domain {
-    /// Database access
-    Database,
-    /// TLS certificates
-    Tls,
-    /// SMTP access
-    Smtp,
-    /// IMAP access
-    Imap,
-    /// Docker registry
-    Docker,
-    /// Git repositories
-    Git,
-    /// File system access
-    Filesystem,
-    /// Cloud provider access
-    Cloud,
-    /// API access
-    Api,
-    /// SSH access
-    Ssh,
-    /// Custom domain
-    Custom(String),
-}
-
-impl fmt::Display for Domain {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Domain::Database => write!(f, "database"),
-            Domain::Tls => write!(f, "tls"),
-            Domain::Smtp => write!(f, "smtp"),
-            Domain::Imap => write!(f, "imap"),
-            Domain::Docker => write!(f, "docker"),
-            Domain::Git => write!(f, "git"),
-            Domain::Filesystem => write!(f, "filesystem"),
-            Domain::Cloud => write!(f, "cloud"),
-            Domain::Api => write!(f, "api"),
-            Domain::Ssh => write!(f, "ssh"),
-            Domain::Custom(name) => write!(f, "custom:{}", name),
-        }
-    }
-}
-
-/// Access actions
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Action {
-    /// Read access
-    Read,
-    /// Write access
-    Write,
-    /// Delete access
-    Delete,
-    /// Execute access
-    Execute,
-    /// List access
-    List,
-    /// Admin access
-    Admin,
-    /// Create access
-    Create,
-    /// Update access
-    Update,
-    /// Custom action
-    Custom(String),
-}
-
-impl fmt::Display for Action {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+/// Set of capabilities used together for one logical operation
+///
+/// The composite is valid only while every member is valid.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompositeCapability {
+    /// Member capabilities
+    capabilities: Vec<Capability>,
+}
+
+/// Builder for [`CapabilityRequest`]
+#[derive(Debug, Clone, Default)]
+pub struct CapabilityRequestBuilder {
+    domain: Option<Domain>,
+    action: Option<Action>,
+    target: Option<String>,
+    ttl: Option<std::time::Duration>,
+    context: CapabilityContext,
+    justification: Option<String>,
+}
+
+impl Serialize for Domain {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Domain {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = NameRepr::deserialize(deserializer)?.into_string();
+        Domain::parse(&name).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for Action {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = NameRepr::deserialize(deserializer)?.into_string();
+        Action::parse(&name).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Wire form of a domain or action name
+///
+/// Also accepts the `{"custom": "x"}` form written by earlier releases.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NameRepr {
+    Text(String),
+    Tagged { custom: String },
+}
+
+impl NameRepr {
+    fn into_string(self) -> String {
         match self {
-            Action::Read => write!(f, "read"),
-            Action::Write => write!(f, "write"),
-            Action::Delete => write!(f, "delete"),
-            Action::Execute => write!(f, "execute"),
-            Action::List => write!(f, "list"),
-            Action::Admin => write!(f, "admin"),
-            Action::Create => write!(f, "create"),
-            Action::Update => write!(f, "update"),
-            Action::Custom(name) => write!(f, "custom:{}", name),
+            NameRepr::Text(name) => name,
+            NameRepr::Tagged { custom } => format!("custom:{}", custom),
         }
     }
 }
@@ -209,6 +311,7 @@ impl Capability {
     ) -> Self {
         let now = Utc::now();
         Self {
+            version: CURRENT_CAPABILITY_VERSION,
             id: Uuid::new_v4(),
             domain,
             action,
@@ -219,43 +322,56 @@ impl Capability {
             issuer,
             subject,
             signature: Vec::new(), // To be filled by signing
+            parent_id: None,
         }
     }
 
     /// Check if capability is currently valid
     pub fn is_valid(&self) -> bool {
-        let now = Utc::now();
-        
+        self.is_valid_at(SystemClock.now())
+    }
+
+    /// Check if capability is valid at `now`
+    pub fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        self.check_valid_at(now).is_ok()
+    }
+
+    /// Check the capability is valid, saying why when it is not
+    ///
+    /// Makes the checks of [`Capability::is_valid`], failing with
+    /// `CapabilityError::Expired`, or `CapabilityError::ScopeMismatch` for
+    /// the time window or an exhausted usage limit.
+    pub fn check_valid(&self) -> Result<()> {
+        self.check_valid_at(SystemClock.now())
+    }
+
+    /// Check the capability is valid at `now`, saying why when it is not
+    pub fn check_valid_at(&self, now: DateTime<Utc>) -> Result<()> {
         // Check expiration
-        if now > self.expires_at {
-            return false;
+        if is_expired(now, self.expires_at) {
+            return Err(CapabilityError::Expired(self.expires_at).into());
         }
 
         // Check time window
         if let Some(time_window) = &self.context.time_window {
-            if now < time_window.start || now > time_window.end {
-                return false;
-            }
-            
-            // Check day of week
-            if let Some(allowed_days) = &time_window.days_of_week {
-                let current_day = now.weekday().num_days_from_sunday() as u8;
-                if !allowed_days.contains(&current_day) {
-                    return false;
-                }
+            if !time_window.allows(now) {
+                return Err(CapabilityError::ScopeMismatch(format!(
+                    "{} is outside the allowed time window",
+                    now
+                )).into());
             }
         }
 
         // Check usage limits
         if let Some(usage_limits) = &self.context.usage_limits {
-            if let Some(max_uses) = usage_limits.max_uses {
-                if usage_limits.current_uses >= max_uses {
-                    return false;
-                }
+            if !within_use_limit(usage_limits.current_uses, usage_limits.max_uses) {
+                return Err(CapabilityError::ScopeMismatch(
+                    "Usage limit exceeded".to_string(),
+                ).into());
             }
         }
 
-        true
+        Ok(())
     }
 
     /// Check if capability is valid for specific context
@@ -264,33 +380,190 @@ impl Capability {
             return false;
         }
 
-        // Check environment
-        if let Some(allowed_envs) = &self.context.environments {
-            if !allowed_envs.contains(environment) {
-                return false;
+        let allowed = |set: &Option<HashSet<String>>, value: &str| {
+            context_allows(set.as_ref().map(|set| set.iter().map(String::as_str)), value)
+        };
+        allowed(&self.context.environments, environment)
+            && allowed(&self.context.services, service)
+            && allowed(&self.context.namespaces, namespace)
+    }
+
+    /// Validate the capability entirely offline
+    ///
+    /// Verifies the signature against `public_key`, then expiry, the time
+    /// window and every context constraint for a caller in `env`, `service`
+    /// and `namespace` connecting from `client_ip`. Unlike
+    /// [`Capability::is_valid_for_context`], the error says which check failed:
+    /// `CryptoError::SignatureVerificationFailed`, `CapabilityError::Expired`,
+    /// or `CapabilityError::ScopeMismatch` naming the constraint.
+    ///
+    /// Usage limits are not checked: the use counters travel unsigned with
+    /// the capability, so only the server can enforce them.
+    pub fn validate_offline(
+        &self,
+        public_key: &[u8],
+        env: &str,
+        service: &str,
+        namespace: &str,
+        client_ip: IpAddr,
+    ) -> Result<()> {
+        self.validate_offline_at(public_key, env, service, namespace, client_ip, SystemClock.now())
+    }
+
+    /// Validate the capability offline as of `now`
+    pub fn validate_offline_at(
+        &self,
+        public_key: &[u8],
+        env: &str,
+        service: &str,
+        namespace: &str,
+        client_ip: IpAddr,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        self.validate_signature(public_key)?;
+
+        if is_expired(now, self.expires_at) {
+            return Err(CapabilityError::Expired(self.expires_at).into());
+        }
+
+        if let Some(time_window) = &self.context.time_window {
+            if !time_window.allows(now) {
+                return Err(CapabilityError::ScopeMismatch(format!(
+                    "{} is outside the allowed time window",
+                    now
+                )).into());
             }
         }
 
-        // Check service
-        if let Some(allowed_services) = &self.context.services {
-            if !allowed_services.contains(service) {
-                return false;
+        let allowed_sets = [
+            ("environment", env, &self.context.environments),
+            ("service", service, &self.context.services),
+            ("namespace", namespace, &self.context.namespaces),
+        ];
+        for (name, value, allowed) in allowed_sets {
+            if !context_allows(allowed.as_ref().map(|set| set.iter().map(String::as_str)), value) {
+                return Err(CapabilityError::ScopeMismatch(format!(
+                    "{} {} is not allowed",
+                    name, value
+                )).into());
             }
         }
 
-        // Check namespace
-        if let Some(allowed_namespaces) = &self.context.namespaces {
-            if !allowed_namespaces.contains(namespace) {
-                return false;
+        if let Some(ips) = &self.context.ip_constraints {
+            if !ips.iter().any(|constraint| ip_allowed(constraint, client_ip)) {
+                return Err(CapabilityError::ScopeMismatch(format!(
+                    "client IP {} is not allowed",
+                    client_ip
+                )).into());
             }
         }
 
-        true
+        Ok(())
+    }
+
+    /// Whether this capability already grants everything `other` would
+    ///
+    /// Requires the same domain, an action implying `other`'s (see
+    /// [`Action::implies`]), a target covering `other`'s
+    /// (see [`Capability::covers_target`]), an expiry no earlier than `other`'s,
+    /// and context constraints no tighter than `other`'s. An unset (`None`)
+    /// constraint is unrestricted, so it covers anything, but is only
+    /// covered by another unset constraint.
+    pub fn implies(&self, other: &Capability) -> bool {
+        self.is_valid()
+            && self.domain == other.domain
+            && self.action.implies(&other.action)
+            && self.covers_target(&other.target)
+            && self.expires_at >= other.expires_at
+            && self.context.covers(&other.context)
+    }
+
+    /// Whether this capability's target covers the `requested` resource
+    ///
+    /// A target identical to `requested` always matches, even if it contains
+    /// glob characters. Otherwise the target is a glob over `/`-separated
+    /// paths:
+    ///
+    /// - `*` matches any run of characters within one path segment
+    /// - `**` matches any run of characters across segments, including `/`
+    /// - `?` matches exactly one character other than `/`
+    /// - `\` makes the next character literal (e.g. `\*`)
+    pub fn matches_target(&self, requested: &str) -> bool {
+        let literal: Vec<GlobToken> = requested.chars().map(GlobToken::Literal).collect();
+        self.target == requested || glob_covers(&glob_tokens(&self.target), &literal)
+    }
+
+    /// Whether this capability's target covers every resource the `other`
+    /// target pattern does
+    ///
+    /// Unlike [`Capability::matches_target`], glob characters in `other`
+    /// keep their meaning: `users/**` covers `users/*`, but `users/*` does
+    /// not cover `users/**`. The comparison is conservative, so a pattern
+    /// may occasionally be reported as not covered when it is.
+    pub fn covers_target(&self, other: &str) -> bool {
+        self.target == other || glob_covers(&glob_tokens(&self.target), &glob_tokens(other))
+    }
+
+    /// Whether this capability grants `action`, directly or by implication
+    pub fn allows_action(&self, action: &Action) -> bool {
+        self.action.implies(action)
+    }
+
+    /// Derive a capability with a narrower context for delegation
+    ///
+    /// The result keeps this capability's expiry. See
+    /// [`Capability::attenuate_with_ttl`].
+    pub fn attenuate(&self, narrower: CapabilityContext) -> Result<Capability> {
+        self.derive(narrower, self.expires_at)
+    }
+
+    /// Derive a capability with a narrower context and a TTL no longer than ours
+    ///
+    /// Each constraint of the result is the tighter of the two: unset
+    /// (`None`) constraints in `narrower` inherit ours, and set ones must be
+    /// subsets of ours. Anything that would broaden access (an extra
+    /// environment, a later expiry, more uses) fails with
+    /// `CapabilityError::ScopeMismatch`. The result references this
+    /// capability through `parent_id` and is unsigned.
+    pub fn attenuate_with_ttl(&self, narrower: CapabilityContext, ttl: std::time::Duration) -> Result<Capability> {
+        let expires_at = chrono::Duration::from_std(ttl)
+            .ok()
+            .and_then(|ttl| Utc::now().checked_add_signed(ttl))
+            .filter(|expires_at| *expires_at <= self.expires_at)
+            .ok_or_else(|| scope_broadened("TTL extends past the parent's expiry"))?;
+
+        self.derive(narrower, expires_at)
+    }
+
+    /// Build an attenuated child expiring at `expires_at`
+    fn derive(&self, narrower: CapabilityContext, expires_at: DateTime<Utc>) -> Result<Capability> {
+        if !self.is_valid() {
+            return Err(CapabilityError::Expired(self.expires_at).into());
+        }
+
+        Ok(Capability {
+            version: CURRENT_CAPABILITY_VERSION,
+            id: Uuid::new_v4(),
+            domain: self.domain.clone(),
+            action: self.action.clone(),
+            target: self.target.clone(),
+            context: self.context.narrow(narrower)?,
+            issued_at: Utc::now(),
+            expires_at,
+            issuer: self.issuer.clone(),
+            subject: self.subject.clone(),
+            signature: Vec::new(),
+            parent_id: Some(self.id),
+        })
     }
 
     /// Get remaining time until expiration
     pub fn remaining_ttl(&self) -> Option<std::time::Duration> {
-        let now = Utc::now();
+        self.remaining_ttl_at(SystemClock.now())
+    }
+
+    /// Get time left until expiration as of `now`
+    pub fn remaining_ttl_at(&self, now: DateTime<Utc>) -> Option<std::time::Duration> {
         if now < self.expires_at {
             Some((self.expires_at - now).to_std().unwrap())
         } else {
@@ -299,8 +572,26 @@ impl Capability {
     }
 
     /// Increment usage count
+    ///
+    /// Fails when either the absolute limit or the `uses_per_window` rate
+    /// limit has been reached. A use rejected by the rate window is not
+    /// counted.
     pub fn increment_usage(&mut self) -> Result<()> {
         if let Some(usage_limits) = &mut self.context.usage_limits {
+            if let Some((limit, window)) = usage_limits.uses_per_window {
+                let now = Utc::now();
+                while usage_limits.window_uses.front().map_or(false, |used| now - *used >= window) {
+                    usage_limits.window_uses.pop_front();
+                }
+
+                if usage_limits.window_uses.len() >= limit as usize {
+                    return Err(CapabilityError::ScopeMismatch(
+                        "rate window exceeded".to_string(),
+                    ).into());
+                }
+                usage_limits.window_uses.push_back(now);
+            }
+
             usage_limits.current_uses += 1;
             
             if let Some(max_uses) = usage_limits.max_uses {
@@ -314,11 +605,85 @@ impl Capability {
         Ok(())
     }
 
+    /// Undo the last successful [`Capability::increment_usage`]
+    ///
+    /// For a use that was counted but never happened, such as an access
+    /// the server failed.
+    pub fn release_usage(&mut self) {
+        if let Some(usage_limits) = &mut self.context.usage_limits {
+            usage_limits.current_uses = usage_limits.current_uses.saturating_sub(1);
+            if usage_limits.uses_per_window.is_some() {
+                usage_limits.window_uses.pop_back();
+            }
+        }
+    }
+
+    /// Canonical byte representation covered by the capability signature
+    ///
+    /// Server and SDK must agree on this layout exactly. From version 2 the
+    /// payload is the ASCII domain separator `AETHER-VAULT-CAPABILITY-V2` and
+    /// `version` as a big-endian `u16`, followed by each field below encoded
+    /// as a big-endian `u32` byte length and its bytes, in this order:
+    ///
+    /// 1. `id` (hyphenated lowercase UUID)
+    /// 2. `domain` (its `Display` form, e.g. `database` or `custom:x`)
+    /// 3. `action` (its `Display` form)
+    /// 4. `target`
+    /// 5. `issued_at` (RFC 3339, UTC, nanosecond precision, `Z` suffix)
+    /// 6. `expires_at` (same format as `issued_at`)
+    /// 7. `issuer`
+    /// 8. `subject`
+    /// 9. `parent_id` (hyphenated lowercase UUID), empty unless attenuated
+    /// 10. `context` (see [`CapabilityContext::canonical_bytes`])
+    ///
+    /// Version 1 capabilities keep their original layout: the separator
+    /// `AETHER-VAULT-CAPABILITY-V1` without the version, fields 1 to 8, then
+    /// `parent_id` only for attenuated capabilities. Their context is not
+    /// covered by the signature.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        if self.version <= 1 {
+            let mut fields = vec![
+                self.id.hyphenated().to_string(),
+                self.domain.to_string(),
+                self.action.to_string(),
+                self.target.clone(),
+                self.issued_at.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true),
+                self.expires_at.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true),
+                self.issuer.clone(),
+                self.subject.clone(),
+            ];
+            if let Some(parent_id) = &self.parent_id {
+                fields.push(parent_id.hyphenated().to_string());
+            }
+            return crate::portable::signing_payload(self.version, fields.iter().map(String::as_bytes));
+        }
+
+        let fields = [
+            self.id.hyphenated().to_string().into_bytes(),
+            self.domain.to_string().into_bytes(),
+            self.action.to_string().into_bytes(),
+            self.target.clone().into_bytes(),
+            self.issued_at.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true).into_bytes(),
+            self.expires_at.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true).into_bytes(),
+            self.issuer.clone().into_bytes(),
+            self.subject.clone().into_bytes(),
+            self.parent_id.map(|id| id.hyphenated().to_string().into_bytes()).unwrap_or_default(),
+            self.context.canonical_bytes(),
+        ];
+
+        crate::portable::signing_payload(self.version, fields.iter().map(Vec::as_slice))
+    }
+
     /// Validate capability signature
+    ///
+    /// Verifies the detached Ed25519 signature over [`Capability::signing_payload`]
+    /// against a raw 32-byte public key.
+    /// The check is a public-key verification by `ring`, not a byte
+    /// comparison against an expected signature, so it needs no
+    /// [`crate::crypto::constant_time_eq`].
     pub fn validate_signature(&self, public_key: &[u8]) -> Result<bool> {
-        // TODO: Implement signature validation using ring
-        // This would verify the capability signature against the public key
-        Ok(true) // Placeholder
+        crate::crypto::ed25519_verify(public_key, &self.signing_payload(), &self.signature)?;
+        Ok(true)
     }
 
     /// Serialize capability for transport
@@ -327,46 +692,589 @@ impl Capability {
     }
 
     /// Deserialize capability from bytes
+    ///
+    /// Older versions are migrated; newer ones are rejected.
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
-        serde_json::from_slice(data).map_err(|e| CapabilityError::InvalidFormat(e.to_string()).into())
+        let capability: Self = serde_json::from_slice(data)
+            .map_err(|e| CapabilityError::InvalidFormat(e.to_string()))?;
+        capability.migrate()
+    }
+
+    /// Upgrade a decoded capability to [`CURRENT_CAPABILITY_VERSION`]
+    ///
+    /// Version 1 capabilities are kept as they are: their signature covers
+    /// the version 1 payload, which upgrading would invalidate. Fails with
+    /// `CapabilityError::InvalidFormat` for versions this SDK does not
+    /// know, rather than trusting a layout it cannot read.
+    pub fn migrate(self) -> Result<Self> {
+        match self.version {
+            1 | CURRENT_CAPABILITY_VERSION => Ok(self),
+            version => Err(CapabilityError::InvalidFormat(format!("unsupported capability version {}", version)).into()),
+        }
+    }
+
+    /// Serialize capability as CBOR for binary transports
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        ciborium::ser::into_writer(self, &mut data)
+            .map_err(|e| CapabilityError::InvalidFormat(e.to_string()))?;
+        Ok(data)
+    }
+
+    /// Deserialize capability from CBOR, migrating it like [`Capability::from_bytes`]
+    pub fn from_cbor(data: &[u8]) -> Result<Self> {
+        let capability: Self = ciborium::de::from_reader(data)
+            .map_err(|e| CapabilityError::InvalidFormat(e.to_string()))?;
+        capability.migrate()
+    }
+
+    /// Encode as a compact JWT signed with Ed25519 (`alg: EdDSA`)
+    ///
+    /// Standard claims mirror the capability (`jti` = id, `iss`, `sub`,
+    /// `iat`, `exp`); the full capability travels in the `cap` claim.
+    /// `signing_key` is a raw 32-byte seed or a PKCS#8 document.
+    pub fn to_jwt(&self, signing_key: &[u8]) -> Result<String> {
+        let header = serde_json::json!({ "alg": "EdDSA", "typ": "JWT" });
+        let claims = CapabilityClaims {
+            jti: self.id,
+            iss: self.issuer.clone(),
+            sub: self.subject.clone(),
+            iat: self.issued_at.timestamp(),
+            exp: self.expires_at.timestamp(),
+            cap: self.clone(),
+        };
+
+        let signing_input = format!("{}.{}", jwt_segment(&header)?, jwt_segment(&claims)?);
+        let signature = crate::crypto::ed25519_sign(signing_key, signing_input.as_bytes())?;
+
+        Ok(format!("{}.{}", signing_input, JWT_BASE64.encode(signature)))
+    }
+
+    /// Decode and verify a JWT produced by [`Capability::to_jwt`]
+    ///
+    /// Fails with `CryptoError::SignatureVerificationFailed` if the token was
+    /// not signed by `public_key` (raw 32 bytes), `CapabilityError::Expired`
+    /// once `exp` has passed, and `CapabilityError::InvalidFormat` if the
+    /// token is malformed, its claims disagree with the embedded capability,
+    /// or the capability has an unsupported version.
+    pub fn from_jwt(token: &str, public_key: &[u8]) -> Result<Self> {
+        let invalid = |reason: &str| CapabilityError::InvalidFormat(format!("invalid capability JWT: {}", reason));
+
+        let token = token.trim();
+        let segments: Vec<&str> = token.split('.').collect();
+        let [header, claims, signature] = segments[..] else {
+            return Err(invalid("expected three segments").into());
+        };
+        let decode = |segment: &str| JWT_BASE64.decode(segment).map_err(|e| invalid(&e.to_string()));
+
+        let header: serde_json::Value = serde_json::from_slice(&decode(header)?)
+            .map_err(|e| invalid(&e.to_string()))?;
+        if header.get("alg").and_then(|alg| alg.as_str()) != Some("EdDSA") {
+            return Err(invalid("unsupported alg (expected EdDSA)").into());
+        }
+
+        let signing_input = &token[..token.len() - signature.len() - 1];
+        crate::crypto::ed25519_verify(public_key, signing_input.as_bytes(), &decode(signature)?)?;
+
+        let claims: CapabilityClaims = serde_json::from_slice(&decode(claims)?)
+            .map_err(|e| invalid(&e.to_string()))?;
+        let cap = claims.cap.migrate()?;
+        if claims.jti != cap.id
+            || claims.iss != cap.issuer
+            || claims.sub != cap.subject
+            || claims.exp != cap.expires_at.timestamp()
+        {
+            return Err(invalid("claims do not match the embedded capability").into());
+        }
+
+        if claims.exp <= Utc::now().timestamp() || cap.expires_at <= Utc::now() {
+            return Err(CapabilityError::Expired(cap.expires_at).into());
+        }
+
+        Ok(cap)
+    }
+}
+
+/// Base64 variant used by JWT segments
+const JWT_BASE64: base64::engine::GeneralPurpose = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+/// Encode a JWT header or claims segment
+fn jwt_segment<T: Serialize>(value: &T) -> Result<String> {
+    let json = serde_json::to_vec(value).map_err(|e| CapabilityError::InvalidFormat(e.to_string()))?;
+    Ok(JWT_BASE64.encode(json))
+}
+
+/// Claims of a capability JWT
+#[derive(Debug, Serialize, Deserialize)]
+struct CapabilityClaims {
+    jti: Uuid,
+    iss: String,
+    sub: String,
+    iat: i64,
+    exp: i64,
+    cap: Capability,
+}
+
+impl CompositeCapability {
+    /// Create a composite from its member capabilities
+    pub fn new(capabilities: Vec<Capability>) -> Self {
+        Self { capabilities }
+    }
+
+    /// Add a member capability
+    pub fn push(&mut self, capability: Capability) {
+        self.capabilities.push(capability);
+    }
+
+    /// Get the member capabilities
+    pub fn capabilities(&self) -> &[Capability] {
+        &self.capabilities
+    }
+
+    /// Check that the composite is non-empty and every member is valid
+    pub fn is_valid(&self) -> bool {
+        !self.capabilities.is_empty() && self.first_invalid().is_none()
+    }
+
+    /// Get the first member that is no longer valid
+    pub fn first_invalid(&self) -> Option<&Capability> {
+        self.capabilities.iter().find(|cap| !cap.is_valid())
+    }
+
+    /// Get the earliest expiration among members
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.capabilities.iter().map(|cap| cap.expires_at).min()
+    }
+}
+
+impl CapabilityContext {
+    /// Validate that the constraints are internally consistent
+    ///
+    /// Rejects contradictory constraints that could never be satisfied, such
+    /// as an inverted time window or an empty allowed set.
+    pub fn validate(&self) -> Result<()> {
+        let allowed_sets = [
+            ("environments", &self.environments),
+            ("services", &self.services),
+            ("namespaces", &self.namespaces),
+        ];
+        for (name, allowed) in allowed_sets {
+            if matches!(allowed, Some(set) if set.is_empty()) {
+                return Err(CapabilityError::InvalidFormat(format!(
+                    "allowed {} set is empty and would deny every request",
+                    name
+                )).into());
+            }
+        }
+
+        if matches!(&self.ip_constraints, Some(ips) if ips.is_empty()) {
+            return Err(CapabilityError::InvalidFormat(
+                "IP constraint list is empty and would deny every request".to_string(),
+            ).into());
+        }
+        if let Some(bad) = self.ip_constraints.iter().flatten().find(|ip| parse_ip_constraint(ip).is_none()) {
+            return Err(CapabilityError::InvalidFormat(format!(
+                "invalid IP constraint '{}' (expected an address or CIDR block)",
+                bad
+            )).into());
+        }
+
+        if let Some(time_window) = &self.time_window {
+            if time_window.end <= time_window.start {
+                return Err(CapabilityError::InvalidFormat(format!(
+                    "time window end ({}) must be after start ({})",
+                    time_window.end, time_window.start
+                )).into());
+            }
+
+            if let Some(tz) = &time_window.tz {
+                if tz.parse::<chrono_tz::Tz>().is_err() {
+                    return Err(CapabilityError::InvalidFormat(format!(
+                        "unknown time zone {}",
+                        tz
+                    )).into());
+                }
+            }
+
+            if let Some(days) = &time_window.days_of_week {
+                if days.is_empty() {
+                    return Err(CapabilityError::InvalidFormat(
+                        "days_of_week is empty and would deny every request".to_string(),
+                    ).into());
+                }
+                if let Some(day) = days.iter().find(|day| **day > 6) {
+                    return Err(CapabilityError::InvalidFormat(format!(
+                        "invalid day of week {} (expected 0=Sunday..6=Saturday)",
+                        day
+                    )).into());
+                }
+            }
+        }
+
+        if let Some(usage_limits) = &self.usage_limits {
+            if usage_limits.max_uses == Some(0) {
+                return Err(CapabilityError::InvalidFormat(
+                    "max_uses must be greater than zero".to_string(),
+                ).into());
+            }
+            if let Some((limit, window)) = usage_limits.uses_per_window {
+                if limit == 0 || window <= chrono::Duration::zero() {
+                    return Err(CapabilityError::InvalidFormat(
+                        "uses_per_window requires a positive count and window".to_string(),
+                    ).into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Canonical encoding of the constraints, as covered by the capability signature
+    ///
+    /// Each constraint below is encoded with [`crate::portable::encode_set`] or
+    /// [`crate::portable::encode_list`], empty when absent, and the results are
+    /// concatenated as length-prefixed fields:
+    ///
+    /// 1. `environments`, `services`, `namespaces` and `ip_constraints` as sets
+    /// 2. `time_window` as a list of `start`, `end` (RFC 3339 as in
+    ///    `issued_at`), the encoded set of `days_of_week` (decimal) and the
+    ///    encoded optional `tz`
+    /// 3. `usage_limits` as a list of the encoded optional `max_uses` and the
+    ///    encoded optional `uses_per_window` count and window in seconds
+    ///
+    /// Usage counters change as the capability is used and are not covered.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        use crate::portable::{encode_list, encode_set};
+
+        fn set(values: &Option<HashSet<String>>) -> Vec<u8> {
+            encode_set(values.as_ref().map(|values| values.iter().map(String::as_str)))
+        }
+        fn optional(values: Option<Vec<String>>) -> Vec<u8> {
+            encode_list(values.as_ref().map(|values| values.iter().map(String::as_bytes)))
+        }
+
+        let time_window = self.time_window.as_ref().map(|window| {
+            let days = window
+                .days_of_week
+                .as_ref()
+                .map(|days| days.iter().map(u8::to_string).collect::<Vec<_>>());
+            vec![
+                window.start.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true).into_bytes(),
+                window.end.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true).into_bytes(),
+                encode_set(days.as_ref().map(|days| days.iter().map(String::as_str))),
+                optional(window.tz.clone().map(|tz| vec![tz])),
+            ]
+        });
+        let usage_limits = self.usage_limits.as_ref().map(|limits| {
+            vec![
+                optional(limits.max_uses.map(|max| vec![max.to_string()])),
+                optional(limits.uses_per_window.map(|(count, window)| {
+                    vec![count.to_string(), window.num_seconds().to_string()]
+                })),
+            ]
+        });
+
+        let fields = [
+            set(&self.environments),
+            set(&self.services),
+            set(&self.namespaces),
+            encode_set(self.ip_constraints.as_ref().map(|ips| ips.iter().map(String::as_str))),
+            encode_list(time_window.as_ref().map(|fields| fields.iter().map(Vec::as_slice))),
+            encode_list(usage_limits.as_ref().map(|fields| fields.iter().map(Vec::as_slice))),
+        ];
+        encode_list(Some(fields.iter().map(Vec::as_slice)))
+    }
+}
+
+impl CapabilityContext {
+    /// Whether these constraints allow everything `other` allows
+    fn covers(&self, other: &CapabilityContext) -> bool {
+        fn covers_set<T: PartialEq>(ours: Option<&[T]>, theirs: Option<&[T]>) -> bool {
+            match (ours, theirs) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(ours), Some(theirs)) => theirs.iter().all(|item| ours.contains(item)),
+            }
+        }
+        fn as_vec(set: &Option<HashSet<String>>) -> Option<Vec<&String>> {
+            set.as_ref().map(|set| set.iter().collect())
+        }
+
+        let sets_covered = [
+            (as_vec(&self.environments), as_vec(&other.environments)),
+            (as_vec(&self.services), as_vec(&other.services)),
+            (as_vec(&self.namespaces), as_vec(&other.namespaces)),
+        ]
+        .iter()
+        .all(|(ours, theirs)| covers_set(ours.as_deref(), theirs.as_deref()));
+
+        let ips_covered = covers_set(self.ip_constraints.as_deref(), other.ip_constraints.as_deref());
+
+        let window_covered = match (&self.time_window, &other.time_window) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(ours), Some(theirs)) => {
+                ours.start <= theirs.start
+                    && theirs.end <= ours.end
+                    && ours.zone() == theirs.zone()
+                    && covers_set(ours.days_of_week.as_deref(), theirs.days_of_week.as_deref())
+            }
+        };
+
+        let usage_covered = match (&self.usage_limits, &other.usage_limits) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(ours), Some(theirs)) => {
+                let remaining = ours.max_uses.map(|max| max.saturating_sub(ours.current_uses));
+                let uses_covered = match (remaining, theirs.max_uses) {
+                    (None, _) => true,
+                    (Some(_), None) => false,
+                    (Some(remaining), Some(max)) => max <= remaining,
+                };
+                let window_covered = match (ours.uses_per_window, theirs.uses_per_window) {
+                    (None, _) => true,
+                    (Some(_), None) => false,
+                    (Some((our_uses, our_window)), Some((uses, window))) => {
+                        uses <= our_uses && window >= our_window
+                    }
+                };
+                uses_covered && window_covered
+            }
+        };
+
+        sets_covered && ips_covered && window_covered && usage_covered
+    }
+
+    /// Intersect with a narrower context, rejecting any broadening
+    fn narrow(&self, narrower: CapabilityContext) -> Result<CapabilityContext> {
+        Ok(CapabilityContext {
+            environments: narrow_set("environments", &self.environments, narrower.environments)?,
+            services: narrow_set("services", &self.services, narrower.services)?,
+            namespaces: narrow_set("namespaces", &self.namespaces, narrower.namespaces)?,
+            ip_constraints: match (&self.ip_constraints, narrower.ip_constraints) {
+                (Some(ours), Some(theirs)) => {
+                    if let Some(ip) = theirs.iter().find(|ip| !ours.contains(ip)) {
+                        return Err(scope_broadened(&format!("ip constraint {} not allowed by parent", ip)));
+                    }
+                    Some(theirs)
+                }
+                (ours, theirs) => theirs.or_else(|| ours.clone()),
+            },
+            time_window: match (&self.time_window, narrower.time_window) {
+                (Some(ours), Some(theirs)) => {
+                    if theirs.start < ours.start || theirs.end > ours.end {
+                        return Err(scope_broadened("time window extends outside the parent's"));
+                    }
+                    if theirs.tz.is_some() && ours.zone() != theirs.zone() {
+                        return Err(scope_broadened("time window uses a different time zone than the parent's"));
+                    }
+                    let days_of_week = match (&ours.days_of_week, theirs.days_of_week) {
+                        (Some(our_days), Some(their_days)) => {
+                            if their_days.iter().any(|day| !our_days.contains(day)) {
+                                return Err(scope_broadened("days of week not allowed by parent"));
+                            }
+                            Some(their_days)
+                        }
+                        (our_days, their_days) => their_days.or_else(|| our_days.clone()),
+                    };
+                    Some(TimeWindow {
+                        start: theirs.start,
+                        end: theirs.end,
+                        days_of_week,
+                        tz: ours.tz.clone(),
+                    })
+                }
+                (ours, theirs) => theirs.or_else(|| ours.clone()),
+            },
+            usage_limits: narrow_usage(&self.usage_limits, narrower.usage_limits)?,
+        })
+    }
+}
+
+/// Element of a target glob pattern
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GlobToken {
+    Literal(char),
+    AnyInSegment,
+    AnyAcrossSegments,
+    OneChar,
+}
+
+/// Split a target pattern into glob tokens
+fn glob_tokens(pattern: &str) -> Vec<GlobToken> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '\\' => GlobToken::Literal(chars.next().unwrap_or('\\')),
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                GlobToken::AnyAcrossSegments
+            }
+            '*' => GlobToken::AnyInSegment,
+            '?' => GlobToken::OneChar,
+            c => GlobToken::Literal(c),
+        });
+    }
+    tokens
+}
+
+impl GlobToken {
+    /// Whether this token only ever stands for characters within one segment
+    fn within_segment(self) -> bool {
+        match self {
+            GlobToken::Literal(c) => c != '/',
+            GlobToken::AnyInSegment | GlobToken::OneChar => true,
+            GlobToken::AnyAcrossSegments => false,
+        }
+    }
+}
+
+/// Match glob tokens against the whole of the `other` pattern
+///
+/// A plain text is a pattern of literals. Each token of `other` is matched
+/// as the set of strings it stands for, so a wildcard in `other` is only
+/// covered by a wildcard at least as broad.
+fn glob_covers(tokens: &[GlobToken], other: &[GlobToken]) -> bool {
+    // matched[j]: the tokens so far cover other[..j]
+    let mut matched = vec![false; other.len() + 1];
+    matched[0] = true;
+
+    for token in tokens {
+        let mut next = vec![false; other.len() + 1];
+        for j in 0..=other.len() {
+            match token {
+                GlobToken::AnyInSegment | GlobToken::AnyAcrossSegments => {
+                    let crosses = *token == GlobToken::AnyAcrossSegments;
+                    next[j] = matched[j]
+                        || (j > 0 && next[j - 1] && (crosses || other[j - 1].within_segment()));
+                }
+                GlobToken::OneChar => {
+                    next[j] = j > 0
+                        && matched[j - 1]
+                        && other[j - 1] != GlobToken::AnyInSegment
+                        && other[j - 1].within_segment();
+                }
+                GlobToken::Literal(c) => {
+                    next[j] = j > 0 && matched[j - 1] && other[j - 1] == GlobToken::Literal(*c);
+                }
+            }
+        }
+        matched = next;
+    }
+
+    matched[other.len()]
+}
+
+/// Error for an attenuation that would widen access
+fn scope_broadened(reason: &str) -> crate::error::VaultError {
+    CapabilityError::ScopeMismatch(format!("attenuation cannot broaden scope: {}", reason)).into()
+}
+
+/// Narrow an allow-list, where `None` means unrestricted
+fn narrow_set(
+    field: &str,
+    ours: &Option<HashSet<String>>,
+    theirs: Option<HashSet<String>>,
+) -> Result<Option<HashSet<String>>> {
+    match (ours, theirs) {
+        (Some(ours), Some(theirs)) => {
+            if !theirs.is_subset(ours) {
+                return Err(scope_broadened(&format!("{} not a subset of the parent's", field)));
+            }
+            Ok(Some(theirs))
+        }
+        (ours, theirs) => Ok(theirs.or_else(|| ours.clone())),
     }
 }
 
+/// Narrow usage limits; the child may use at most what the parent has left
+fn narrow_usage(ours: &Option<UsageLimits>, theirs: Option<UsageLimits>) -> Result<Option<UsageLimits>> {
+    let Some(ours) = ours else {
+        return Ok(theirs.map(|limits| UsageLimits {
+            current_uses: 0,
+            window_uses: VecDeque::new(),
+            ..limits
+        }));
+    };
+    let theirs = theirs.unwrap_or_default();
+
+    let remaining = ours.max_uses.map(|max| max.saturating_sub(ours.current_uses));
+    let max_uses = match (remaining, theirs.max_uses) {
+        (Some(remaining), Some(max)) if max > remaining => {
+            return Err(scope_broadened("max_uses exceeds the parent's remaining uses"));
+        }
+        (remaining, max) => max.or(remaining),
+    };
+
+    let uses_per_window = match (ours.uses_per_window, theirs.uses_per_window) {
+        (Some((our_uses, our_window)), Some((uses, window))) => {
+            if uses > our_uses || window < our_window {
+                return Err(scope_broadened("uses_per_window is looser than the parent's"));
+            }
+            Some((uses, window))
+        }
+        (ours, theirs) => theirs.or(ours),
+    };
+
+    Ok(Some(UsageLimits {
+        max_uses,
+        uses_per_window,
+        current_uses: 0,
+        window_uses: VecDeque::new(),
+    }))
+}
+
 impl CapabilityRequest {
     /// Create a new capability request
+    ///
+    /// `target` may be a string or a [`ResourcePath`](crate::capability::ResourcePath).
     pub fn new(
         domain: Domain,
         action: Action,
-        target: String,
+        target: impl Into<String>,
         context: CapabilityContext,
         ttl: std::time::Duration,
     ) -> Self {
         Self {
+            version: CURRENT_CAPABILITY_VERSION,
             domain,
             action,
-            target,
+            target: target.into(),
             context,
             ttl,
             justification: None,
+            idempotency_key: None,
         }
     }
 
+    /// Create a builder for a capability request
+    pub fn builder() -> CapabilityRequestBuilder {
+        CapabilityRequestBuilder::default()
+    }
+
     /// Add justification to the request
     pub fn with_justification(mut self, justification: String) -> Self {
         self.justification = Some(justification);
         self
     }
 
+    /// Longest TTL a request may ask for
+    pub const MAX_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+    /// Shortest TTL a request may ask for
+    pub const MIN_TTL: std::time::Duration = std::time::Duration::from_secs(10);
+
     /// Validate the request
     pub fn validate(&self) -> Result<()> {
         // Validate TTL (must be reasonable)
-        if self.ttl > std::time::Duration::from_secs(24 * 60 * 60) {
+        if self.ttl > Self::MAX_TTL {
             return Err(CapabilityError::InvalidFormat(
                 "TTL too long (max 24 hours)".to_string(),
             ).into());
         }
 
-        if self.ttl < std::time::Duration::from_secs(10) {
+        if self.ttl < Self::MIN_TTL {
             return Err(CapabilityError::InvalidFormat(
                 "TTL too short (min 10 seconds)".to_string(),
             ).into());
@@ -379,71 +1287,231 @@ impl CapabilityRequest {
             ).into());
         }
 
+        // Validate context constraints
+        self.context.validate()?;
+
         Ok(())
     }
-}
 
-impl Domain {
-    /// Parse domain from string
-    pub fn parse(s: &str) -> Result<Self> {
-        match s.to_lowercase().as_str() {
-            "database" => Ok(Domain::Database),
-            "tls" => Ok(Domain::Tls),
-            "smtp" => Ok(Domain::Smtp),
-            "imap" => Ok(Domain::Imap),
-            "docker" => Ok(Domain::Docker),
-            "git" => Ok(Domain::Git),
-            "filesystem" => Ok(Domain::Filesystem),
-            "cloud" => Ok(Domain::Cloud),
-            "api" => Ok(Domain::Api),
-            "ssh" => Ok(Domain::Ssh),
-            custom if custom.starts_with("custom:") => {
-                Ok(Domain::Custom(custom[7..].to_string()))
-            }
-            _ => Err(CapabilityError::InvalidDomain(s.to_string()).into()),
+    /// Reject a missing justification when `(domain, action)` is in `required`
+    ///
+    /// A justification that is blank counts as missing.
+    pub fn check_justification(&self, required: &HashSet<(Domain, Action)>) -> Result<()> {
+        let justified = self.justification.as_deref().map_or(false, |j| !j.trim().is_empty());
+        if !justified && required.contains(&(self.domain.clone(), self.action.clone())) {
+            return Err(CapabilityError::InvalidFormat(format!(
+                "justification required for {}/{}",
+                self.domain, self.action
+            )).into());
         }
+        Ok(())
+    }
+}
+
+impl CapabilityRequestBuilder {
+    /// Default TTL when none is set
+    pub const DEFAULT_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+    /// Set the domain
+    pub fn domain(mut self, domain: Domain) -> Self {
+        self.domain = Some(domain);
+        self
     }
 
-    /// Get all standard domains
-    pub fn standard_domains() -> Vec<&'static str> {
-        vec![
-            "database", "tls", "smtp", "imap", "docker", 
-            "git", "filesystem", "cloud", "api", "ssh"
-        ]
+    /// Set the action
+    pub fn action(mut self, action: Action) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    /// Set the target resource
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Set the requested TTL (defaults to 5 minutes)
+    pub fn ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Allow an environment (may be called repeatedly)
+    pub fn environment(mut self, environment: impl Into<String>) -> Self {
+        self.context.environments.get_or_insert_with(HashSet::new).insert(environment.into());
+        self
+    }
+
+    /// Allow a service (may be called repeatedly)
+    pub fn service(mut self, service: impl Into<String>) -> Self {
+        self.context.services.get_or_insert_with(HashSet::new).insert(service.into());
+        self
+    }
+
+    /// Allow a namespace (may be called repeatedly)
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.context.namespaces.get_or_insert_with(HashSet::new).insert(namespace.into());
+        self
+    }
+
+    /// Add an IP address constraint (may be called repeatedly)
+    pub fn ip_constraint(mut self, ip: impl Into<String>) -> Self {
+        self.context.ip_constraints.get_or_insert_with(Vec::new).push(ip.into());
+        self
+    }
+
+    /// Restrict use to a time window
+    pub fn time_window(mut self, time_window: TimeWindow) -> Self {
+        self.context.time_window = Some(time_window);
+        self
+    }
+
+    /// Limit the total number of uses
+    pub fn max_uses(mut self, max_uses: u32) -> Self {
+        self.context.usage_limits
+            .get_or_insert_with(UsageLimits::default)
+            .max_uses = Some(max_uses);
+        self
+    }
+
+    /// Limit the number of uses within a sliding time window
+    pub fn uses_per_window(mut self, uses: u32, window: chrono::Duration) -> Self {
+        self.context.usage_limits
+            .get_or_insert_with(UsageLimits::default)
+            .uses_per_window = Some((uses, window));
+        self
+    }
+
+    /// Set the justification for access
+    pub fn justification(mut self, justification: impl Into<String>) -> Self {
+        self.justification = Some(justification.into());
+        self
+    }
+
+    /// Build and validate the request
+    pub fn build(self) -> Result<CapabilityRequest> {
+        let domain = self.domain
+            .ok_or_else(|| CapabilityError::InvalidFormat("domain is required".to_string()))?;
+        let action = self.action
+            .ok_or_else(|| CapabilityError::InvalidFormat("action is required".to_string()))?;
+        let target = self.target
+            .ok_or_else(|| CapabilityError::InvalidFormat("target is required".to_string()))?;
+
+        let mut request = CapabilityRequest::new(
+            domain,
+            action,
+            target,
+            self.context,
+            self.ttl.unwrap_or(Self::DEFAULT_TTL),
+        );
+        request.justification = self.justification;
+
+        request.validate()?;
+        Ok(request)
     }
 }
 
-impl Action {
-    /// Parse action from string
-    pub fn parse(s: &str) -> Result<Self> {
-        match s.to_lowercase().as_str() {
-            "read" => Ok(Action::Read),
-            "write" => Ok(Action::Write),
-            "delete" => Ok(Action::Delete),
-            "execute" => Ok(Action::Execute),
-            "list" => Ok(Action::List),
-            "admin" => Ok(Action::Admin),
-            "create" => Ok(Action::Create),
-            "update" => Ok(Action::Update),
-            custom if custom.starts_with("custom:") => {
-                Ok(Action::Custom(custom[7..].to_string()))
+/// Define a capability wrapper restricted to one domain and its valid actions
+macro_rules! typed_capability {
+    ($(#[$meta:meta])* $name:ident, $domain:expr, [$($action:expr),+ $(,)?]) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[serde(try_from = "Capability", into = "Capability")]
+        pub struct $name(Capability);
+
+        impl $name {
+            /// Actions valid for this domain
+            pub const ALLOWED_ACTIONS: &'static [Action] = &[$($action),+];
+
+            /// Create a capability, rejecting actions not valid for this domain
+            pub fn new(
+                action: Action,
+                target: String,
+                context: CapabilityContext,
+                ttl: std::time::Duration,
+                issuer: String,
+                subject: String,
+            ) -> Result<Self> {
+                Self::check_action(&action)?;
+                Ok(Self(Capability::new($domain, action, target, context, ttl, issuer, subject)))
+            }
+
+            /// Reject actions not valid for this domain
+            pub fn check_action(action: &Action) -> Result<()> {
+                if Self::ALLOWED_ACTIONS.contains(action) {
+                    Ok(())
+                } else {
+                    Err(CapabilityError::InvalidAction(format!(
+                        "{} is not valid for the {} domain",
+                        action,
+                        $domain
+                    )).into())
+                }
+            }
+
+            /// Unwrap into the untyped capability
+            pub fn into_inner(self) -> Capability {
+                self.0
             }
-            _ => Err(CapabilityError::InvalidAction(s.to_string()).into()),
         }
-    }
 
-    /// Get all standard actions
-    pub fn standard_actions() -> Vec<&'static str> {
-        vec![
-            "read", "write", "delete", "execute", "list", 
-            "admin", "create", "update"
-        ]
-    }
+        impl TryFrom<Capability> for $name {
+            type Error = crate::error::VaultError;
+
+            fn try_from(capability: Capability) -> Result<Self> {
+                if capability.domain != $domain {
+                    return Err(CapabilityError::InvalidDomain(format!(
+                        "expected {}, got {}",
+                        $domain,
+                        capability.domain
+                    )).into());
+                }
+                Self::check_action(&capability.action)?;
+                Ok(Self(capability))
+            }
+        }
+
+        impl From<$name> for Capability {
+            fn from(typed: $name) -> Capability {
+                typed.0
+            }
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = Capability;
+
+            fn deref(&self) -> &Capability {
+                &self.0
+            }
+        }
+    };
 }
 
+typed_capability!(
+    /// Database capability (read, write, list or admin)
+    DatabaseCapability,
+    Domain::Database,
+    [Action::Read, Action::Write, Action::List, Action::Admin]
+);
+
+typed_capability!(
+    /// SSH capability (execute only)
+    SshCapability,
+    Domain::Ssh,
+    [Action::Execute]
+);
+
+typed_capability!(
+    /// TLS certificate capability (read, create or delete)
+    TlsCapability,
+    Domain::Tls,
+    [Action::Read, Action::Create, Action::Delete]
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::CryptoError;
     use std::collections::HashSet;
 
     #[test]
@@ -487,7 +1555,7 @@ mod tests {
         let capability = Capability::new(
             Domain::Database,
             Action::Read,
-            "users",
+            "users".to_string(),
             context,
             std::time::Duration::from_millis(1), // Very short TTL
             "vault".to_string(),
@@ -495,11 +1563,14 @@ mod tests {
         );
 
         // Should be valid initially
-        assert!(capability.is_valid());
-        
-        // Wait for expiration
-        std::thread::sleep(std::time::Duration::from_millis(10));
-        assert!(!capability.is_valid());
+        let clock = crate::clock::MockClock::new(capability.issued_at);
+        assert!(capability.is_valid_at(clock.now()));
+        assert_eq!(capability.remaining_ttl_at(clock.now()), Some(std::time::Duration::from_millis(1)));
+
+        // Expires once the clock passes the TTL
+        clock.advance(std::time::Duration::from_millis(10));
+        assert!(!capability.is_valid_at(clock.now()));
+        assert_eq!(capability.remaining_ttl_at(clock.now()), None);
     }
 
     #[test]
@@ -516,6 +1587,50 @@ mod tests {
         assert!(Action::parse("invalid").is_err());
     }
 
+    #[test]
+    fn test_request_target_from_resource_path() {
+        let path = crate::capability::ResourcePath::parse("/database/prod/users/").unwrap();
+        let request = CapabilityRequest::new(
+            Domain::Database,
+            Action::Read,
+            &path,
+            CapabilityContext::default(),
+            std::time::Duration::from_secs(60),
+        );
+        assert_eq!(request.target, "database/prod/users");
+
+        let built = CapabilityRequest::builder()
+            .domain(Domain::Database)
+            .action(Action::Read)
+            .target(path.parent().unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(built.target, "database/prod");
+    }
+
+    #[test]
+    fn test_justification_required() {
+        let required: HashSet<(Domain, Action)> = [(Domain::Ssh, Action::Admin)].into_iter().collect();
+        let request = CapabilityRequest::builder()
+            .domain(Domain::Ssh)
+            .action(Action::Admin)
+            .target("bastion")
+            .ttl(std::time::Duration::from_secs(60))
+            .build()
+            .unwrap();
+
+        let err = request.check_justification(&required).unwrap_err();
+        assert!(err.to_string().contains("justification required for ssh/admin"), "{}", err);
+        assert!(request.clone().with_justification("  ".to_string()).check_justification(&required).is_err());
+        request.clone().with_justification("INC-42 triage".to_string()).check_justification(&required).unwrap();
+
+        // Other pairs, and the default empty set, don't need one
+        request.check_justification(&HashSet::new()).unwrap();
+        let mut read = request;
+        read.action = Action::Read;
+        read.check_justification(&required).unwrap();
+    }
+
     #[test]
     fn test_capability_request_validation() {
         let context = CapabilityContext {
@@ -545,4 +1660,769 @@ mod tests {
         );
         assert!(invalid_request.validate().is_err());
     }
-}
\ No newline at end of file
+
+    fn signed_capability() -> (Capability, Vec<u8>) {
+        use ring::signature::KeyPair;
+
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = ring::signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let key_pair = ring::signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+        let mut capability = Capability::new(
+            Domain::Database,
+            Action::Read,
+            "users".to_string(),
+            CapabilityContext {
+                environments: None,
+                services: None,
+                namespaces: None,
+                ip_constraints: None,
+                time_window: None,
+                usage_limits: None,
+            },
+            std::time::Duration::from_secs(300),
+            "vault".to_string(),
+            "test".to_string(),
+        );
+        capability.signature = key_pair.sign(&capability.signing_payload()).as_ref().to_vec();
+
+        (capability, key_pair.public_key().as_ref().to_vec())
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let (capability, public_key) = signed_capability();
+        assert!(capability.validate_signature(&public_key).unwrap());
+
+        // Survives serialization for transport
+        let decoded = Capability::from_bytes(&capability.to_bytes().unwrap()).unwrap();
+        assert!(decoded.validate_signature(&public_key).unwrap());
+    }
+
+    #[test]
+    fn test_signature_rejects_tampering() {
+        let (mut capability, public_key) = signed_capability();
+        capability.target = "admins".to_string();
+
+        assert!(matches!(
+            capability.validate_signature(&public_key),
+            Err(crate::error::VaultError::Crypto(CryptoError::SignatureVerificationFailed))
+        ));
+    }
+
+    #[test]
+    fn test_signature_rejects_bad_key() {
+        let (capability, _) = signed_capability();
+
+        assert!(matches!(
+            capability.validate_signature(&[0u8; 16]),
+            Err(crate::error::VaultError::Crypto(CryptoError::InvalidKeyFormat(_)))
+        ));
+    }
+
+    fn empty_context() -> CapabilityContext {
+        CapabilityContext {
+            environments: None,
+            services: None,
+            namespaces: None,
+            ip_constraints: None,
+            time_window: None,
+            usage_limits: None,
+        }
+    }
+
+    fn request_with(context: CapabilityContext) -> CapabilityRequest {
+        CapabilityRequest::new(
+            Domain::Database,
+            Action::Read,
+            "users".to_string(),
+            context,
+            std::time::Duration::from_secs(300),
+        )
+    }
+
+    fn assert_invalid_format(request: CapabilityRequest, fragment: &str) {
+        match request.validate() {
+            Err(crate::error::VaultError::Capability(CapabilityError::InvalidFormat(msg))) => {
+                assert!(msg.contains(fragment), "unexpected message: {}", msg);
+            }
+            other => panic!("expected InvalidFormat, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_context_rejects_inverted_time_window() {
+        let now = Utc::now();
+        let mut context = empty_context();
+        context.time_window = Some(TimeWindow {
+            start: now,
+            end: now - chrono::Duration::hours(1),
+            days_of_week: None,
+            tz: None,
+        });
+        assert_invalid_format(request_with(context), "time window end");
+    }
+
+    #[test]
+    fn test_context_rejects_out_of_range_day() {
+        let now = Utc::now();
+        let mut context = empty_context();
+        context.time_window = Some(TimeWindow {
+            start: now,
+            end: now + chrono::Duration::hours(1),
+            days_of_week: Some(vec![1, 7]),
+            tz: None,
+        });
+        assert_invalid_format(request_with(context), "invalid day of week 7");
+    }
+
+    #[test]
+    fn test_context_rejects_empty_allowed_set() {
+        let mut context = empty_context();
+        context.environments = Some(HashSet::new());
+        assert_invalid_format(request_with(context), "allowed environments set is empty");
+    }
+
+    #[test]
+    fn test_context_rejects_zero_max_uses() {
+        let mut context = empty_context();
+        context.usage_limits = Some(UsageLimits {
+            max_uses: Some(0),
+            ..UsageLimits::default()
+        });
+        assert_invalid_format(request_with(context), "max_uses");
+    }
+
+    #[test]
+    fn test_request_builder() {
+        let request = CapabilityRequest::builder()
+            .domain(Domain::Database)
+            .action(Action::Read)
+            .target("users")
+            .ttl(std::time::Duration::from_secs(60))
+            .environment("production")
+            .service("api-service")
+            .ip_constraint("10.0.0.0/8")
+            .max_uses(5)
+            .justification("nightly report")
+            .build()
+            .unwrap();
+
+        assert_eq!(request.domain, Domain::Database);
+        assert_eq!(request.target, "users");
+        assert_eq!(request.ttl, std::time::Duration::from_secs(60));
+        assert!(request.context.environments.unwrap().contains("production"));
+        assert!(request.context.services.unwrap().contains("api-service"));
+        assert_eq!(request.context.ip_constraints.unwrap(), vec!["10.0.0.0/8".to_string()]);
+        assert_eq!(request.context.usage_limits.unwrap().max_uses, Some(5));
+        assert!(request.context.namespaces.is_none());
+        assert_eq!(request.justification.as_deref(), Some("nightly report"));
+    }
+
+    #[test]
+    fn test_request_builder_rejects_long_ttl() {
+        let result = CapabilityRequest::builder()
+            .domain(Domain::Database)
+            .action(Action::Read)
+            .target("users")
+            .ttl(std::time::Duration::from_secs(48 * 60 * 60))
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(crate::error::VaultError::Capability(CapabilityError::InvalidFormat(_)))
+        ));
+    }
+
+    #[test]
+    fn test_uses_per_window_limits_and_recovers() {
+        let (mut capability, _) = signed_capability();
+        capability.context.usage_limits = Some(UsageLimits {
+            uses_per_window: Some((3, chrono::Duration::milliseconds(100))),
+            ..UsageLimits::default()
+        });
+
+        for _ in 0..3 {
+            capability.increment_usage().unwrap();
+        }
+        let err = capability.increment_usage().unwrap_err();
+        assert!(err.to_string().contains("rate window exceeded"));
+
+        std::thread::sleep(std::time::Duration::from_millis(150));
+        capability.increment_usage().unwrap();
+
+        let usage_limits = capability.context.usage_limits.unwrap();
+        assert_eq!(usage_limits.current_uses, 4);
+        assert_eq!(usage_limits.window_uses.len(), 1);
+    }
+
+    #[test]
+    fn test_attenuate_narrows_environments() {
+        let (mut parent, _) = signed_capability();
+        parent.context.environments = Some(HashSet::from(["staging".to_string(), "production".to_string()]));
+        parent.context.usage_limits = Some(UsageLimits { max_uses: Some(10), ..UsageLimits::default() });
+
+        let child = parent
+            .attenuate(CapabilityContext {
+                environments: Some(HashSet::from(["staging".to_string()])),
+                ..CapabilityContext::default()
+            })
+            .unwrap();
+        assert_eq!(child.parent_id, Some(parent.id));
+        assert_ne!(child.id, parent.id);
+        assert_eq!(child.context.environments, Some(HashSet::from(["staging".to_string()])));
+        assert_eq!(child.context.usage_limits.as_ref().unwrap().max_uses, Some(10));
+        assert!(child.expires_at <= parent.expires_at);
+        assert!(child.signature.is_empty());
+
+        // Unset constraints inherit the parent's
+        let inherited = parent.attenuate(CapabilityContext::default()).unwrap();
+        assert_eq!(inherited.context.environments, parent.context.environments);
+
+        let err = parent
+            .attenuate(CapabilityContext {
+                environments: Some(HashSet::from(["development".to_string()])),
+                ..CapabilityContext::default()
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("scope mismatch"));
+
+        let err = parent
+            .attenuate(CapabilityContext {
+                usage_limits: Some(UsageLimits { max_uses: Some(11), ..UsageLimits::default() }),
+                ..CapabilityContext::default()
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("max_uses"));
+    }
+
+    #[test]
+    fn test_attenuate_rejects_ttl_extension() {
+        let (parent, _) = signed_capability();
+
+        let shorter = parent
+            .attenuate_with_ttl(CapabilityContext::default(), std::time::Duration::from_secs(60))
+            .unwrap();
+        assert!(shorter.expires_at < parent.expires_at);
+
+        let err = parent
+            .attenuate_with_ttl(CapabilityContext::default(), std::time::Duration::from_secs(3600))
+            .unwrap_err();
+        assert!(err.to_string().contains("TTL"));
+    }
+
+    #[test]
+    fn test_implies_matrix() {
+        let envs = |names: &[&str]| Some(names.iter().map(|n| n.to_string()).collect::<HashSet<_>>());
+        let expires_at = Utc::now() + chrono::Duration::minutes(5);
+        let capability = |domain: Domain, action: Action, environments: Option<HashSet<String>>| {
+            let mut capability = Capability::new(
+                domain,
+                action,
+                "users".to_string(),
+                CapabilityContext { environments, ..CapabilityContext::default() },
+                std::time::Duration::from_secs(300),
+                "vault".to_string(),
+                "test".to_string(),
+            );
+            capability.expires_at = expires_at;
+            capability
+        };
+
+        let broad = capability(Domain::Database, Action::Read, envs(&["staging", "production"]));
+        let cases = [
+            // (other, expected)
+            (capability(Domain::Database, Action::Read, envs(&["staging"])), true),
+            (capability(Domain::Database, Action::Read, envs(&["staging", "production"])), true),
+            (capability(Domain::Database, Action::Read, envs(&["development"])), false),
+            (capability(Domain::Database, Action::Read, None), false),
+            (capability(Domain::Ssh, Action::Read, envs(&["staging"])), false),
+            (capability(Domain::Database, Action::Write, envs(&["staging"])), false),
+        ];
+        for (other, expected) in &cases {
+            assert_eq!(broad.implies(other), *expected, "{:?} / {:?}", other.domain, other.context.environments);
+        }
+
+        // Unrestricted environments cover any set
+        let unrestricted = capability(Domain::Database, Action::Read, None);
+        assert!(unrestricted.implies(&cases[0].0));
+
+        // Different target, unless ours is the `*` wildcard
+        let mut orders = capability(Domain::Database, Action::Read, envs(&["staging"]));
+        orders.target = "orders".to_string();
+        assert!(!broad.implies(&orders));
+        let mut any_target = broad.clone();
+        any_target.target = "*".to_string();
+        assert!(any_target.implies(&orders));
+    }
+
+    #[test]
+    fn test_implies_compares_target_globs() {
+        let expires_at = Utc::now() + chrono::Duration::minutes(5);
+        let with_target = |target: &str| {
+            let (mut capability, _) = signed_capability();
+            capability.target = target.to_string();
+            capability.expires_at = expires_at;
+            capability
+        };
+
+        // A glob in the other target is a pattern, not a literal
+        assert!(!with_target("users/*").implies(&with_target("users/**")));
+        assert!(!with_target("users/*").implies(&with_target("users/*/keys")));
+        assert!(!with_target("shard-?").implies(&with_target("shard-*")));
+        assert!(with_target("users/**").implies(&with_target("users/*")));
+        assert!(with_target("users/**").implies(&with_target("users/*/keys")));
+        assert!(with_target("users/*").implies(&with_target("users/?")));
+        assert!(with_target("shard-*").implies(&with_target("shard-?")));
+        assert!(with_target("users/*").implies(&with_target("users/*")));
+
+        // Escaped glob characters are literals on both sides
+        assert!(with_target("users/*").implies(&with_target(r"users/\*")));
+        assert!(!with_target(r"users/\*").implies(&with_target("users/alice")));
+        assert!(with_target(r"users/\*").implies(&with_target(r"users/\*")));
+    }
+
+    #[test]
+    fn test_matches_target_globs() {
+        let with_target = |target: &str| {
+            let (mut capability, _) = signed_capability();
+            capability.target = target.to_string();
+            capability
+        };
+
+        let single = with_target("users/*");
+        assert!(single.matches_target("users/alice"));
+        assert!(single.matches_target("users/"));
+        assert!(!single.matches_target("users/alice/keys"));
+        assert!(!single.matches_target("orders/alice"));
+
+        let prefix = with_target("db/prod-*");
+        assert!(prefix.matches_target("db/prod-eu"));
+        assert!(!prefix.matches_target("db/staging-eu"));
+
+        let deep = with_target("users/**");
+        assert!(deep.matches_target("users/alice/keys/ssh"));
+        assert!(!deep.matches_target("admins/alice"));
+        assert!(with_target("**/keys").matches_target("users/alice/keys"));
+
+        let one = with_target("shard-?");
+        assert!(one.matches_target("shard-1"));
+        assert!(!one.matches_target("shard-10"));
+        assert!(!with_target("a?b").matches_target("a/b"));
+
+        // Escaped metacharacters match literally
+        let escaped = with_target(r"reports/\*");
+        assert!(escaped.matches_target("reports/*"));
+        assert!(!escaped.matches_target("reports/q1"));
+
+        // Exact matches always win
+        assert!(with_target("users").matches_target("users"));
+        assert!(with_target("[raw]*").matches_target("[raw]*"));
+        assert!(!with_target("users").matches_target("users2"));
+    }
+
+    #[test]
+    fn test_jwt_round_trip() {
+        let seed = [9u8; 32];
+        let public_key = crate::crypto::public_key_for(&seed).unwrap();
+        let (capability, _) = signed_capability();
+
+        let token = capability.to_jwt(&seed).unwrap();
+        assert_eq!(token.split('.').count(), 3);
+
+        let decoded = Capability::from_jwt(&token, &public_key).unwrap();
+        assert_eq!(decoded.to_bytes().unwrap(), capability.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_jwt_rejects_tampering_and_expiry() {
+        let seed = [9u8; 32];
+        let public_key = crate::crypto::public_key_for(&seed).unwrap();
+        let (mut capability, _) = signed_capability();
+        let token = capability.to_jwt(&seed).unwrap();
+
+        // Swap in claims for a different target, keeping the old signature
+        let mut forged = capability.clone();
+        forged.target = "admins".to_string();
+        let forged_token = forged.to_jwt(&[7u8; 32]).unwrap();
+        let parts: Vec<&str> = token.split('.').collect();
+        let forged_parts: Vec<&str> = forged_token.split('.').collect();
+        let tampered = format!("{}.{}.{}", parts[0], forged_parts[1], parts[2]);
+        assert!(matches!(
+            Capability::from_jwt(&tampered, &public_key),
+            Err(crate::error::VaultError::Crypto(CryptoError::SignatureVerificationFailed))
+        ));
+
+        // Wrong key
+        let other_key = crate::crypto::public_key_for(&[7u8; 32]).unwrap();
+        assert!(Capability::from_jwt(&token, &other_key).is_err());
+
+        capability.expires_at = Utc::now() - chrono::Duration::seconds(5);
+        let expired = capability.to_jwt(&seed).unwrap();
+        assert!(matches!(
+            Capability::from_jwt(&expired, &public_key),
+            Err(crate::error::VaultError::Capability(CapabilityError::Expired(_)))
+        ));
+
+        assert!(Capability::from_jwt("not.a-jwt", &public_key).is_err());
+    }
+
+    #[test]
+    fn test_cbor_round_trip() {
+        let (capability, _) = signed_capability();
+        let encoded = capability.to_cbor().unwrap();
+        let decoded = Capability::from_cbor(&encoded).unwrap();
+        assert_eq!(decoded.to_bytes().unwrap(), capability.to_bytes().unwrap());
+        assert!(Capability::from_cbor(b"garbage").is_err());
+    }
+
+    #[test]
+    fn test_capability_versions() {
+        let (capability, _) = signed_capability();
+        assert_eq!(capability.version, CURRENT_CAPABILITY_VERSION);
+
+        // Payloads predating the field decode as version 1
+        let mut unversioned: serde_json::Value = serde_json::from_slice(&capability.to_bytes().unwrap()).unwrap();
+        unversioned.as_object_mut().unwrap().remove("version");
+        let decoded = Capability::from_bytes(unversioned.to_string().as_bytes()).unwrap();
+        assert_eq!(decoded.version, 1);
+        assert_eq!(decoded.id, capability.id);
+
+        let mut future = capability.clone();
+        future.version = CURRENT_CAPABILITY_VERSION + 1;
+        let expected = format!("unsupported capability version {}", CURRENT_CAPABILITY_VERSION + 1);
+        for result in [
+            Capability::from_bytes(&future.to_bytes().unwrap()),
+            Capability::from_cbor(&future.to_cbor().unwrap()),
+            Capability::from_jwt(&future.to_jwt(&[9u8; 32]).unwrap(), &crate::crypto::public_key_for(&[9u8; 32]).unwrap()),
+        ] {
+            match result {
+                Err(crate::error::VaultError::Capability(CapabilityError::InvalidFormat(msg))) => assert_eq!(msg, expected),
+                other => panic!("expected an unsupported version error, got {:?}", other),
+            }
+        }
+
+        let request: CapabilityRequest = serde_json::from_value(serde_json::json!({
+            "domain": "database",
+            "action": "read",
+            "target": "users",
+            "context": {},
+            "ttl": { "secs": 60, "nanos": 0 },
+            "justification": null,
+        })).unwrap();
+        assert_eq!(request.version, 1);
+        assert_eq!(request_with(CapabilityContext::default()).version, CURRENT_CAPABILITY_VERSION);
+    }
+
+    #[test]
+    fn test_typed_capabilities_restrict_actions() {
+        let ssh = |action: Action| SshCapability::new(
+            action,
+            "bastion".to_string(),
+            CapabilityContext::default(),
+            std::time::Duration::from_secs(60),
+            "vault".to_string(),
+            "test".to_string(),
+        );
+
+        let err = ssh(Action::Write).unwrap_err();
+        assert!(matches!(err, crate::error::VaultError::Capability(CapabilityError::InvalidAction(_))));
+
+        let capability = ssh(Action::Execute).unwrap();
+        assert_eq!(capability.domain, Domain::Ssh);
+        assert_eq!(capability.target, "bastion");
+
+        // Untyped capabilities convert only when domain and action fit
+        let (database, _) = signed_capability();
+        assert!(DatabaseCapability::try_from(database.clone()).is_ok());
+        assert!(TlsCapability::try_from(database.clone()).is_err());
+
+        let mut execute = database;
+        execute.action = Action::Execute;
+        assert!(DatabaseCapability::try_from(execute).is_err());
+    }
+
+    #[test]
+    fn test_action_lattice() {
+        let builtin = [
+            Action::Read, Action::Write, Action::Delete, Action::Execute,
+            Action::List, Action::Admin, Action::Create, Action::Update,
+        ];
+
+        for action in &builtin {
+            assert!(action.implies(action));
+            assert!(Action::Admin.implies(action));
+        }
+
+        assert!(Action::Write.implies(&Action::Update));
+        assert!(Action::Update.implies(&Action::Read));
+        assert!(Action::Write.implies(&Action::Read)); // transitive
+        assert!(!Action::Read.implies(&Action::Write));
+        assert!(!Action::Update.implies(&Action::Write));
+        assert!(!Action::Write.implies(&Action::Delete));
+        assert!(!Action::List.implies(&Action::Read));
+
+        let custom = Action::Custom("rotate".to_string());
+        assert!(custom.implies(&Action::Custom("rotate".to_string())));
+        assert!(!custom.implies(&Action::Read));
+        assert!(!Action::Admin.implies(&custom));
+
+        let (mut capability, _) = signed_capability();
+        capability.action = Action::Write;
+        assert!(capability.allows_action(&Action::Read));
+        let mut read = capability.clone();
+        read.action = Action::Read;
+        assert!(capability.implies(&read));
+        assert!(!read.implies(&capability));
+    }
+
+    #[test]
+    fn test_time_window_time_zone() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let weekdays = |tz: Option<&str>| TimeWindow {
+            start: at("2024-01-01T00:00:00Z"),
+            end: at("2024-02-01T00:00:00Z"),
+            days_of_week: Some(vec![1, 2, 3, 4, 5]),
+            tz: tz.map(str::to_string),
+        };
+
+        // Saturday 02:00 UTC is still Friday evening in New York
+        let saturday_utc = at("2024-01-06T02:00:00Z");
+        assert!(!weekdays(None).allows(saturday_utc));
+        assert!(weekdays(Some("America/New_York")).allows(saturday_utc));
+
+        // Monday 03:00 UTC is still Sunday night in New York
+        let monday_utc = at("2024-01-08T03:00:00Z");
+        assert!(weekdays(None).allows(monday_utc));
+        assert!(!weekdays(Some("America/New_York")).allows(monday_utc));
+
+        // Unknown zones deny at evaluation and fail validation
+        assert!(!weekdays(Some("Mars/Olympus_Mons")).allows(saturday_utc));
+        let mut context = empty_context();
+        context.time_window = Some(weekdays(Some("Mars/Olympus_Mons")));
+        assert_invalid_format(request_with(context), "unknown time zone");
+    }
+
+    #[test]
+    fn test_time_window_with_mock_clock() {
+        let clock = crate::clock::MockClock::new(Utc::now());
+        let mut context = empty_context();
+        context.time_window = Some(TimeWindow {
+            start: clock.now() + chrono::Duration::minutes(10),
+            end: clock.now() + chrono::Duration::minutes(20),
+            days_of_week: None,
+            tz: None,
+        });
+        let capability = Capability::new(
+            Domain::Database,
+            Action::Read,
+            "users".to_string(),
+            context,
+            std::time::Duration::from_secs(3600),
+            "vault".to_string(),
+            "test".to_string(),
+        );
+
+        assert!(!capability.is_valid_at(clock.now()));
+        clock.advance(std::time::Duration::from_secs(15 * 60));
+        assert!(capability.is_valid_at(clock.now()));
+        clock.advance(std::time::Duration::from_secs(15 * 60));
+        assert!(!capability.is_valid_at(clock.now()));
+    }
+
+    #[test]
+    fn test_validate_offline() {
+        let (mut capability, public_key) = signed_capability();
+        let ip: IpAddr = "10.1.2.3".parse().unwrap();
+        capability.validate_offline(&public_key, "prod", "api", "default", ip).unwrap();
+
+        // Scoped context, re-signed
+        let resign = |capability: &mut Capability| {
+            let key = [9u8; 32];
+            let key_pair = ring::signature::Ed25519KeyPair::from_seed_unchecked(&key).unwrap();
+            capability.signature = key_pair.sign(&capability.signing_payload()).as_ref().to_vec();
+            crate::crypto::public_key_for(&key).unwrap()
+        };
+        capability.context.environments = Some(["prod".to_string()].into_iter().collect());
+        capability.context.services = Some(["api".to_string()].into_iter().collect());
+        capability.context.namespaces = Some(["default".to_string()].into_iter().collect());
+        capability.context.ip_constraints = Some(vec!["10.0.0.0/8".to_string(), "192.168.1.7".to_string()]);
+        let public_key = resign(&mut capability);
+
+        capability.validate_offline(&public_key, "prod", "api", "default", ip).unwrap();
+        capability.validate_offline(&public_key, "prod", "api", "default", "192.168.1.7".parse().unwrap()).unwrap();
+
+        let scope_error = |result: Result<()>| match result {
+            Err(crate::error::VaultError::Capability(CapabilityError::ScopeMismatch(reason))) => reason,
+            other => panic!("expected scope mismatch, got {:?}", other),
+        };
+        assert!(scope_error(capability.validate_offline(&public_key, "staging", "api", "default", ip)).contains("environment staging"));
+        assert!(scope_error(capability.validate_offline(&public_key, "prod", "web", "default", ip)).contains("service web"));
+        assert!(scope_error(capability.validate_offline(&public_key, "prod", "api", "other", ip)).contains("namespace other"));
+        assert!(scope_error(capability.validate_offline(&public_key, "prod", "api", "default", "11.0.0.1".parse().unwrap())).contains("client IP 11.0.0.1"));
+        assert!(scope_error(capability.validate_offline(&public_key, "prod", "api", "default", "::1".parse().unwrap())).contains("client IP"));
+
+        // Time window
+        let now = Utc::now();
+        capability.context.time_window = Some(TimeWindow {
+            start: now + chrono::Duration::hours(1),
+            end: now + chrono::Duration::hours(2),
+            days_of_week: None,
+            tz: None,
+        });
+        let public_key = resign(&mut capability);
+        assert!(scope_error(capability.validate_offline(&public_key, "prod", "api", "default", ip)).contains("time window"));
+        capability.context.time_window = None;
+
+        // Use counters are unsigned, so exhausted usage is left to the server
+        capability.context.usage_limits = Some(UsageLimits { max_uses: Some(1), current_uses: 1, ..Default::default() });
+        let public_key = resign(&mut capability);
+        capability.validate_offline(&public_key, "prod", "api", "default", ip).unwrap();
+        capability.context.usage_limits = None;
+        let public_key = resign(&mut capability);
+
+        // Expiry
+        let later = capability.expires_at + chrono::Duration::seconds(1);
+        assert!(matches!(
+            capability.validate_offline_at(&public_key, "prod", "api", "default", ip, later),
+            Err(crate::error::VaultError::Capability(CapabilityError::Expired(at))) if at == capability.expires_at
+        ));
+
+        // Tampering breaks the signature before any other check
+        capability.target = "admins".to_string();
+        assert!(matches!(
+            capability.validate_offline(&public_key, "prod", "api", "default", ip),
+            Err(crate::error::VaultError::Crypto(CryptoError::SignatureVerificationFailed))
+        ));
+    }
+
+    #[test]
+    fn test_version_1_signatures_still_verify() {
+        let (mut capability, _) = signed_capability();
+        let key_pair = ring::signature::Ed25519KeyPair::from_seed_unchecked(&[9u8; 32]).unwrap();
+        let public_key = crate::crypto::public_key_for(&[9u8; 32]).unwrap();
+
+        capability.version = 1;
+        let payload = capability.signing_payload();
+        assert!(payload.starts_with(crate::portable::SIGNING_DOMAIN_SEPARATOR_V1));
+        capability.signature = key_pair.sign(&payload).as_ref().to_vec();
+
+        let decoded = Capability::from_bytes(&capability.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.version, 1);
+        decoded.validate_signature(&public_key).unwrap();
+
+        // Read as version 2, the same signature no longer verifies
+        let mut upgraded = decoded.clone();
+        upgraded.version = 2;
+        assert!(upgraded.validate_signature(&public_key).is_err());
+    }
+
+    #[test]
+    fn test_signature_covers_context_and_version() {
+        let (mut capability, _) = signed_capability();
+        let key_pair = ring::signature::Ed25519KeyPair::from_seed_unchecked(&[9u8; 32]).unwrap();
+        let public_key = crate::crypto::public_key_for(&[9u8; 32]).unwrap();
+        let ip: IpAddr = "10.1.2.3".parse().unwrap();
+
+        capability.context.environments = Some(["prod".to_string()].into_iter().collect());
+        capability.context.ip_constraints = Some(vec!["10.0.0.0/8".to_string()]);
+        capability.context.time_window = Some(TimeWindow {
+            start: Utc::now() - chrono::Duration::hours(1),
+            end: Utc::now() + chrono::Duration::hours(1),
+            days_of_week: Some(vec![0, 1, 2, 3, 4, 5, 6]),
+            tz: None,
+        });
+        capability.context.usage_limits = Some(UsageLimits { max_uses: Some(5), ..Default::default() });
+        capability.signature = key_pair.sign(&capability.signing_payload()).as_ref().to_vec();
+        capability.validate_offline(&public_key, "prod", "api", "default", ip).unwrap();
+
+        // Usage counters are not signed
+        let mut used = capability.clone();
+        used.context.usage_limits.as_mut().unwrap().current_uses = 3;
+        used.validate_offline(&public_key, "prod", "api", "default", ip).unwrap();
+
+        let tampered: [fn(&mut Capability); 6] = [
+            |c| c.context.ip_constraints = None,
+            |c| c.context.environments = None,
+            |c| c.context.services = Some(["api".to_string()].into_iter().collect()),
+            |c| c.context.time_window = None,
+            |c| c.context.usage_limits.as_mut().unwrap().max_uses = None,
+            |c| c.version += 1,
+        ];
+        for tamper in tampered {
+            let mut stripped = capability.clone();
+            tamper(&mut stripped);
+            assert!(matches!(
+                stripped.validate_offline(&public_key, "prod", "api", "default", ip),
+                Err(crate::error::VaultError::Crypto(CryptoError::SignatureVerificationFailed))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_custom_names_round_trip() {
+        let domain = Domain::Custom("PaymentGateway".to_string());
+        let json = serde_json::to_string(&domain).unwrap();
+        assert_eq!(json, r#""custom:PaymentGateway""#);
+        assert_eq!(serde_json::from_str::<Domain>(&json).unwrap(), domain);
+        let text: String = serde_json::from_str(&json).unwrap();
+        assert_eq!(Domain::parse(&text).unwrap(), domain);
+        assert_eq!(Domain::parse(&domain.to_string()).unwrap(), domain);
+
+        let action = Action::Custom("rotate".to_string());
+        let json = serde_json::to_string(&action).unwrap();
+        assert_eq!(json, r#""custom:rotate""#);
+        assert_eq!(serde_json::from_str::<Action>(&json).unwrap(), action);
+        assert_eq!(Action::parse(&serde_json::from_str::<String>(&json).unwrap()).unwrap(), action);
+
+        // Built-in names keep their lowercase form
+        assert_eq!(serde_json::to_string(&Domain::Database).unwrap(), r#""database""#);
+        assert_eq!(serde_json::from_str::<Action>(r#""read""#).unwrap(), Action::Read);
+
+        // The tagged form from earlier releases still reads
+        assert_eq!(serde_json::from_str::<Domain>(r#"{"custom":"legacy"}"#).unwrap(), Domain::Custom("legacy".to_string()));
+        assert!(serde_json::from_str::<Domain>(r#""nonsense""#).is_err());
+    }
+
+    #[test]
+    fn test_request_ttl_serialized_as_seconds() {
+        let request = CapabilityRequest::new(
+            Domain::Database,
+            Action::Read,
+            "users",
+            CapabilityContext::default(),
+            std::time::Duration::from_secs(90),
+        );
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["ttl_seconds"], serde_json::json!(90));
+        assert!(value.get("ttl").is_none());
+
+        let parsed: CapabilityRequest = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.ttl, std::time::Duration::from_secs(90));
+    }
+
+    #[test]
+    fn test_ip_constraints_validated() {
+        let with_ips = |ips: &[&str]| CapabilityContext {
+            ip_constraints: Some(ips.iter().map(|ip| ip.to_string()).collect()),
+            ..CapabilityContext::default()
+        };
+
+        assert!(with_ips(&["10.0.0.1", "10.0.0.0/24", "2001:db8::1", "2001:db8::/32", "0.0.0.0/0"]).validate().is_ok());
+
+        for bad in ["10.0.0/24", "10.0.0.0/33", "2001:db8::/129", "10.0.0.0/", "bastion"] {
+            let err = with_ips(&["10.0.0.1", bad]).validate().unwrap_err();
+            assert!(matches!(err, crate::error::VaultError::Capability(CapabilityError::InvalidFormat(_))));
+            assert!(err.to_string().contains(&format!("'{}'", bad)), "{}", err);
+        }
+
+        let err = CapabilityRequest::builder()
+            .domain(Domain::Database)
+            .action(Action::Read)
+            .target("users")
+            .ip_constraint("192.168.1.300")
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("192.168.1.300"), "{}", err);
+    }
+}
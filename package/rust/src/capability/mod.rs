@@ -1,3 +1,9 @@
 pub mod capability;
+pub mod path;
 
-pub use capability::{Capability, CapabilityRequest, Domain, Action};
\ No newline at end of file
+pub use capability::{
+    Action, Capability, CapabilityContext, CapabilityRequest, CapabilityRequestBuilder,
+    CompositeCapability, DatabaseCapability, Domain, SshCapability, TimeWindow, TlsCapability,
+    UsageLimits, CURRENT_CAPABILITY_VERSION, ttl_seconds,
+};
+pub use path::ResourcePath;
@@ -0,0 +1,141 @@
+//! Structured resource paths for capability targets.
+
+use crate::error::{CapabilityError, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Separator between path segments
+const SEPARATOR: char = '/';
+
+/// Target path split into segments, e.g. `database/prod/users`
+///
+/// Parsing normalizes leading and trailing slashes away and rejects empty,
+/// `.` and `..` segments and backslash separators, so equal paths always
+/// have one spelling.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResourcePath {
+    segments: Vec<String>,
+}
+
+impl ResourcePath {
+    /// Parse a `/`-separated path
+    pub fn parse(s: &str) -> Result<Self> {
+        let trimmed = s.trim().trim_matches(SEPARATOR);
+        if trimmed.is_empty() {
+            return Err(CapabilityError::InvalidFormat("resource path is empty".to_string()).into());
+        }
+        if trimmed.contains('\\') {
+            return Err(CapabilityError::InvalidFormat(format!(
+                "resource path {} must use '/' as separator",
+                s
+            )).into());
+        }
+
+        let segments = trimmed
+            .split(SEPARATOR)
+            .map(|segment| match segment {
+                "" | "." | ".." => Err(CapabilityError::InvalidFormat(format!(
+                    "resource path {} has an invalid segment '{}'",
+                    s, segment
+                )).into()),
+                segment => Ok(segment.to_string()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { segments })
+    }
+
+    /// Path segments, outermost first
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// Path without its last segment, or `None` for a single-segment path
+    pub fn parent(&self) -> Option<ResourcePath> {
+        (self.segments.len() > 1).then(|| ResourcePath {
+            segments: self.segments[..self.segments.len() - 1].to_vec(),
+        })
+    }
+
+    /// Whether `prefix` is this path or one of its ancestors
+    ///
+    /// Compares whole segments, so `database/prod` is not a prefix of
+    /// `database/production`.
+    pub fn starts_with(&self, prefix: &ResourcePath) -> bool {
+        self.segments.starts_with(&prefix.segments)
+    }
+
+    /// Append the segments of the relative path `child`
+    pub fn join(&self, child: &str) -> Result<ResourcePath> {
+        let child = ResourcePath::parse(child)?;
+        let mut segments = self.segments.clone();
+        segments.extend(child.segments);
+        Ok(ResourcePath { segments })
+    }
+}
+
+impl fmt::Display for ResourcePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.segments.join("/"))
+    }
+}
+
+impl FromStr for ResourcePath {
+    type Err = crate::error::VaultError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        ResourcePath::parse(s)
+    }
+}
+
+impl From<ResourcePath> for String {
+    fn from(path: ResourcePath) -> Self {
+        path.to_string()
+    }
+}
+
+impl From<&ResourcePath> for String {
+    fn from(path: &ResourcePath) -> Self {
+        path.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_normalizes_slashes() {
+        let path = ResourcePath::parse("database/prod/users").unwrap();
+        assert_eq!(path.segments(), ["database", "prod", "users"]);
+        assert_eq!(ResourcePath::parse("/database/prod/users/").unwrap(), path);
+        assert_eq!(path.to_string(), "database/prod/users");
+
+        for invalid in ["", "/", "database//users", "database/./users", "database/../users", "database\\prod"] {
+            assert!(ResourcePath::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_parent_and_join() {
+        let path = ResourcePath::parse("database/prod/users").unwrap();
+        let parent = path.parent().unwrap();
+        assert_eq!(parent.to_string(), "database/prod");
+        assert!(ResourcePath::parse("database").unwrap().parent().is_none());
+
+        assert_eq!(parent.join("users").unwrap(), path);
+        assert_eq!(parent.join("/users/").unwrap(), path);
+        assert_eq!(parent.join("users/archive").unwrap().to_string(), "database/prod/users/archive");
+        assert!(parent.join("..").is_err());
+    }
+
+    #[test]
+    fn test_starts_with_matches_whole_segments() {
+        let path = ResourcePath::parse("database/prod/users").unwrap();
+        assert!(path.starts_with(&ResourcePath::parse("database/prod/").unwrap()));
+        assert!(path.starts_with(&path));
+        assert!(!path.starts_with(&ResourcePath::parse("database/pro").unwrap()));
+        assert!(!ResourcePath::parse("database/production").unwrap()
+            .starts_with(&ResourcePath::parse("database/prod").unwrap()));
+    }
+}
@@ -0,0 +1,185 @@
+//! Capability bundles for handing capabilities to another process.
+//!
+//! A bundle is a JSON envelope around the serialized capabilities, tagged
+//! with HMAC-SHA256 and optionally encrypted with AES-256-GCM. Both keys are
+//! derived from the single shared key, which never appears in the bundle.
+
+use crate::capability::Capability;
+use crate::crypto::{hmac_sha256, open, seal, verify_hmac, SEAL_KEY_LEN};
+use crate::error::{CapabilityError, CryptoError, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// Bundle format version
+const BUNDLE_VERSION: u16 = 1;
+
+/// What [`Client::export_bundle`](crate::Client::export_bundle) puts in a bundle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BundleOptions {
+    /// Include the client's identity token, so the importer can request more
+    pub include_identity: bool,
+
+    /// Encrypt the contents as well as signing them
+    ///
+    /// Unencrypted bundles are tamper-evident but readable by anyone who
+    /// can read the file or pipe.
+    pub encrypt: bool,
+}
+
+/// How [`Client::import_bundle`](crate::Client::import_bundle) treats a bundle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// Import capabilities even without a `verification_key` to check them against
+    ///
+    /// The bundle key only proves who sealed the bundle; anyone holding it
+    /// can put any capability in. Leave off unless the sealer is trusted
+    /// as much as Vault itself.
+    pub trust_unsigned: bool,
+}
+
+/// Payload of a bundle
+#[derive(Serialize, Deserialize)]
+pub(crate) struct BundleContents {
+    pub capabilities: Vec<Capability>,
+    #[serde(default)]
+    pub identity: Option<String>,
+}
+
+/// Wire form of a bundle
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    version: u16,
+    encrypted: bool,
+    payload: Zeroizing<String>,
+    mac: String,
+}
+
+/// Sign, and optionally encrypt, `contents` under `key`
+pub(crate) fn seal_bundle(contents: &BundleContents, key: &[u8], encrypt: bool) -> Result<Vec<u8>> {
+    check_key(key)?;
+    let plaintext = Zeroizing::new(serde_json::to_vec(contents)?);
+    let payload = if encrypt {
+        Zeroizing::new(seal(&plaintext, &subkey(key, b"encrypt"), b"aether-vault-bundle")?)
+    } else {
+        plaintext
+    };
+
+    let mac = hmac_sha256(&subkey(key, b"mac"), &mac_input(BUNDLE_VERSION, encrypt, &payload));
+    let engine = base64::engine::general_purpose::STANDARD;
+    let envelope = Envelope {
+        version: BUNDLE_VERSION,
+        encrypted: encrypt,
+        payload: Zeroizing::new(engine.encode(&payload)),
+        mac: engine.encode(mac),
+    };
+    Ok(serde_json::to_vec(&envelope)?)
+}
+
+/// Verify, and decrypt if needed, a bundle made by [`seal_bundle`]
+///
+/// Fails with `CryptoError::SignatureVerificationFailed` if the bundle was
+/// altered or signed under another key.
+pub(crate) fn open_bundle(bundle: &[u8], key: &[u8]) -> Result<BundleContents> {
+    check_key(key)?;
+    let envelope: Envelope = serde_json::from_slice(bundle)
+        .map_err(|e| CapabilityError::InvalidFormat(format!("malformed bundle: {}", e)))?;
+    if envelope.version != BUNDLE_VERSION {
+        return Err(CapabilityError::InvalidFormat(format!(
+            "unsupported bundle version {}",
+            envelope.version
+        )).into());
+    }
+
+    let engine = base64::engine::general_purpose::STANDARD;
+    let decode = |field: &str| {
+        engine
+            .decode(field)
+            .map_err(|e| CapabilityError::InvalidFormat(format!("malformed bundle: {}", e)))
+    };
+    let payload = Zeroizing::new(decode(&envelope.payload)?);
+    let mac = decode(&envelope.mac)?;
+    if !verify_hmac(&subkey(key, b"mac"), &mac_input(envelope.version, envelope.encrypted, &payload), &mac) {
+        return Err(CryptoError::SignatureVerificationFailed.into());
+    }
+
+    let plaintext = if envelope.encrypted {
+        Zeroizing::new(open(&payload, &subkey(key, b"encrypt"), b"aether-vault-bundle")?)
+    } else {
+        payload
+    };
+    serde_json::from_slice(&plaintext)
+        .map_err(|e| CapabilityError::InvalidFormat(format!("malformed bundle contents: {}", e)).into())
+}
+
+fn check_key(key: &[u8]) -> Result<()> {
+    if key.len() != SEAL_KEY_LEN {
+        return Err(CryptoError::InvalidKeyFormat(format!(
+            "expected {}-byte bundle key, got {} bytes",
+            SEAL_KEY_LEN,
+            key.len()
+        )).into());
+    }
+    Ok(())
+}
+
+/// Key for one purpose, so signing and encryption never share a key
+fn subkey(key: &[u8], purpose: &[u8]) -> Zeroizing<Vec<u8>> {
+    Zeroizing::new(hmac_sha256(key, purpose))
+}
+
+/// Bytes covered by the MAC: the header fields, then the payload
+fn mac_input(version: u16, encrypted: bool, payload: &[u8]) -> Zeroizing<Vec<u8>> {
+    let mut input = Zeroizing::new(format!("{}.{}.", version, u8::from(encrypted)).into_bytes());
+    input.extend_from_slice(payload);
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::{Action, CapabilityContext, Domain};
+    use crate::error::VaultError;
+
+    fn contents() -> BundleContents {
+        BundleContents {
+            capabilities: vec![Capability::new(
+                Domain::Database,
+                Action::Read,
+                "users".to_string(),
+                CapabilityContext::default(),
+                std::time::Duration::from_secs(60),
+                "vault".to_string(),
+                "init".to_string(),
+            )],
+            identity: Some("parent-token".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_bundle_round_trip_and_tampering() {
+        let key = [7u8; SEAL_KEY_LEN];
+        for encrypt in [false, true] {
+            let bundle = seal_bundle(&contents(), &key, encrypt).unwrap();
+            assert_eq!(String::from_utf8_lossy(&bundle).contains("users"), !encrypt);
+
+            let opened = open_bundle(&bundle, &key).unwrap();
+            assert_eq!(opened.capabilities[0].target, "users");
+            assert_eq!(opened.identity.as_deref(), Some("parent-token"));
+
+            let err = open_bundle(&bundle, &[8u8; SEAL_KEY_LEN]).unwrap_err();
+            assert!(matches!(err, VaultError::Crypto(CryptoError::SignatureVerificationFailed)));
+
+            // Flipping the encryption flag invalidates the signature
+            let mut envelope: serde_json::Value = serde_json::from_slice(&bundle).unwrap();
+            envelope["encrypted"] = serde_json::json!(!encrypt);
+            let err = open_bundle(&serde_json::to_vec(&envelope).unwrap(), &key).unwrap_err();
+            assert!(matches!(err, VaultError::Crypto(CryptoError::SignatureVerificationFailed)));
+        }
+
+        assert!(matches!(
+            seal_bundle(&contents(), b"short", false),
+            Err(VaultError::Crypto(CryptoError::InvalidKeyFormat(_)))
+        ));
+    }
+}
@@ -3,231 +3,2804 @@
 //! Provides the primary interface for interacting with Aether Vault
 //! with strong capability-based access control and lifetime management.
 
-use crate::capability::{Capability, CapabilityRequest, Domain, Action};
+use crate::audit::{AuditEvent, AuditSink, NullSink, StdoutSink};
+use crate::cache::{CapabilityCache, ResponseCache};
+use crate::capability::{
+    Action, Capability, CapabilityRequest, CompositeCapability, DatabaseCapability, Domain,
+    SshCapability, TlsCapability,
+};
 use crate::config::Config;
 use crate::context::Context;
-use crate::error::{Result, VaultError};
-use crate::identity::Identity;
-use crate::transport::Transport;
-use std::sync::Arc;
+use crate::error::{ErrorContext, Result, VaultError};
+use crate::identity::{Identity, WorkloadIdentity};
+use crate::metrics::{NoopRecorder, Recorder};
+use crate::transform::SecretTransform;
+use crate::transport::{Transport, TransportPool};
+use super::bundle::{open_bundle, seal_bundle, BundleContents, BundleOptions, ImportOptions};
+use std::sync::{Arc, PoisonError};
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, RwLockWriteGuard};
 
 /// Main Vault client
-#[derive(Debug, Clone)]
+///
+/// # Lock ordering
+///
+/// The client guards its state with two locks, `identity` and `capabilities`.
+/// Any operation that holds both at the same time must acquire `identity`
+/// first and `capabilities` second. Paths needing both go through
+/// [`Client::capabilities_after_identity`], which takes the identity guard as
+/// proof of ordering. Operations needing only one lock must release it
+/// before taking the other.
+#[derive(Clone)]
 pub struct Client {
-    /// Client configuration
-    config: Arc<Config>,
+    /// Client configuration, replaced by [`Client::apply_config`]
+    config: Arc<std::sync::RwLock<Arc<Config>>>,
+    
+    /// Transport layer, replaced when the config's transport settings change
+    transport: Arc<std::sync::RwLock<Arc<dyn Transport + Send + Sync>>>,
     
-    /// Transport layer
-    transport: Arc<dyn Transport + Send + Sync>,
+    /// Whether the transport was connected from the config, and so may be rebuilt
+    owns_transport: bool,
     
     /// Current identity
     identity: Arc<RwLock<Option<Identity>>>,
     
     /// Capability cache (short-lived, in-memory only)
-    capabilities: Arc<RwLock<std::collections::HashMap<uuid::Uuid, Capability>>>,
+    capabilities: Arc<RwLock<CapabilityCache>>,
+    
+    /// Access response cache, when `cache.enabled` is set
+    responses: Option<Arc<std::sync::Mutex<ResponseCache>>>,
+    
+    /// Audit event destination
+    audit_sink: Arc<std::sync::RwLock<Arc<dyn AuditSink>>>,
+    
+    /// Metrics destination
+    metrics: Arc<std::sync::RwLock<Arc<dyn Recorder>>>,
+    
+    /// Latest server-pushed configuration hints
+    hints: Arc<RwLock<ServerHints>>,
+    
+    /// Recent capability requests per domain, for the hinted client-side quota
+    quota: Arc<std::sync::Mutex<std::collections::HashMap<Domain, std::collections::VecDeque<std::time::Instant>>>>,
+    
+    /// Background capability refresh state
+    auto_refresh: Arc<std::sync::Mutex<AutoRefresh>>,
+    
+    /// Provider the identity is refreshed from, if any
+    workload: Arc<std::sync::Mutex<Option<WorkloadIdentity>>>,
+    
+    /// Threshold and callback for accesses close to expiry
+    near_expiry: Arc<std::sync::RwLock<Option<(Duration, NearExpiryCallback)>>>,
+    
+    /// Most recent server status and when it was fetched
+    last_status: Arc<std::sync::Mutex<Option<(std::time::Instant, VaultStatus)>>>,
+    
+    /// Number of operations in progress, shared by every clone
+    in_flight: Arc<tokio::sync::watch::Sender<usize>>,
+    
+    /// Set while [`Client::close_gracefully`] drains, refusing new operations
+    closing: Arc<std::sync::atomic::AtomicBool>,
+    
+    /// Ids of capabilities revoked through this client, for watchers
+    revocations: tokio::sync::broadcast::Sender<uuid::Uuid>,
+}
+
+/// Counts one in-progress operation until dropped
+struct InFlightGuard(Arc<tokio::sync::watch::Sender<usize>>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.send_modify(|count| *count -= 1);
+    }
+}
+
+/// Whether `capability_id` is among the revocations announced since the last check
+///
+/// Drains the receiver. If it lagged behind, the missed ids are unknown and
+/// the caller falls back to asking the server.
+fn revoked_through(revocations: &mut tokio::sync::broadcast::Receiver<uuid::Uuid>, capability_id: uuid::Uuid) -> bool {
+    let mut revoked = false;
+    loop {
+        match revocations.try_recv() {
+            Ok(id) => revoked |= id == capability_id,
+            Err(tokio::sync::broadcast::error::TryRecvError::Lagged(_)) => continue,
+            Err(_) => return revoked,
+        }
+    }
+}
+
+/// Whether `endpoint` addresses this host
+fn is_loopback_endpoint(endpoint: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(endpoint) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<std::net::IpAddr>().map_or(false, |ip| ip.is_loopback())
+}
+
+/// Audit sink selected by `config.logging`
+fn audit_sink_for(config: &Config) -> Arc<dyn AuditSink> {
+    if config.logging.audit {
+        Arc::new(StdoutSink::new(config.logging.format.clone()))
+    } else {
+        Arc::new(NullSink)
+    }
+}
+
+/// Response cache for `config`, if access responses may be cached
+fn response_cache(config: &Config) -> Option<Arc<std::sync::Mutex<ResponseCache>>> {
+    config.cache.as_ref()
+        .filter(|cache| cache.enabled)
+        .map(|cache| Arc::new(std::sync::Mutex::new(ResponseCache::new(cache.max_size, cache.ttl))))
+}
+
+/// Whether responses to `action` may be served from the response cache
+fn caches_responses(action: &Action) -> bool {
+    matches!(action, Action::Read | Action::List)
+}
+
+/// Health status reported when the server could not be checked
+fn unhealthy_status(message: String, response_time_ms: u64) -> HealthStatus {
+    HealthStatus {
+        healthy: false,
+        status: HealthStatusType::Unhealthy,
+        details: vec![HealthDetail {
+            component: "transport".to_string(),
+            status: HealthStatusType::Unhealthy,
+            message: Some(message),
+            response_time_ms: Some(response_time_ms),
+        }],
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+/// Reject per-call options that cannot be honoured
+fn check_options(options: &RequestOptions) -> Result<()> {
+    if options.timeout.map_or(false, |timeout| timeout.is_zero()) {
+        return Err(VaultError::Validation("request timeout override must be positive".to_string()));
+    }
+    Ok(())
+}
+
+/// How often expired capabilities are dropped from the cache
+const CACHE_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often a watched capability is checked for changes
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Revocations kept for watchers that have not polled yet
+const REVOCATION_BACKLOG: usize = 64;
+
+/// How long a fetched status may short-circuit requests to a sealed Vault
+const SEALED_STATUS_MAX_AGE: Duration = Duration::from_secs(10);
+
+/// Callback invoked with a capability's id and remaining TTL
+pub type NearExpiryCallback = Arc<dyn Fn(uuid::Uuid, Duration) + Send + Sync>;
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("config", &self.config())
+            .field("transport", &self.transport())
+            .field("identity", &"***")
+            .field("cached_capabilities", &self.capabilities.try_read().map(|caps| caps.len()).ok())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builder for [`Client`]
+///
+/// Starts from [`Config::default`] unless a config is given. Without an
+/// injected transport, the config is validated and the transport selected
+/// by `config.transport` is connected, exactly as in [`Client::new`].
+#[derive(Default)]
+pub struct ClientBuilder {
+    config: Option<Config>,
+    transport: Option<Arc<dyn Transport + Send + Sync>>,
+    identity: Option<Identity>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    metrics: Option<Arc<dyn Recorder>>,
+}
+
+impl std::fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("config", &self.config)
+            .field("transport", &self.transport)
+            .field("identity", &self.identity.as_ref().map(|_| "***"))
+            .field("audit_sink", &self.audit_sink.is_some())
+            .field("metrics", &self.metrics.is_some())
+            .finish()
+    }
+}
+
+impl ClientBuilder {
+    /// Use `config` instead of the default configuration
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Use `transport` instead of connecting one from the config
+    pub fn transport(mut self, transport: Arc<dyn Transport + Send + Sync>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Start with `identity` already set
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Send audit events to `sink` instead of the one chosen by the config
+    pub fn audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// Report metrics to `recorder`
+    pub fn metrics_recorder(mut self, recorder: Arc<dyn Recorder>) -> Self {
+        self.metrics = Some(recorder);
+        self
+    }
+
+    /// Build the client
+    pub async fn build(self) -> Result<Client> {
+        let config = self.config.unwrap_or_default();
+        let (transport, owns_transport) = match self.transport {
+            Some(transport) => (transport, false),
+            None => {
+                config.validate()?;
+                (crate::transport::connect(&config).await?, true)
+            }
+        };
+
+        let client = Client::assemble(config, transport, owns_transport).await?;
+        if let Some(sink) = self.audit_sink {
+            client.set_audit_sink(sink);
+        }
+        if let Some(recorder) = self.metrics {
+            client.set_metrics_recorder(recorder);
+        }
+        if let Some(identity) = self.identity {
+            client.set_identity(identity).await?;
+        }
+        Ok(client)
+    }
+}
+
+/// Per-call options overriding configured defaults
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestOptions {
+    /// Request timeout replacing `TimeoutConfig::request` for this call
+    pub timeout: Option<Duration>,
+}
+
+impl RequestOptions {
+    /// Options overriding the request timeout
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self { timeout: Some(timeout) }
+    }
+}
+
+/// State of the background capability refresh task
+#[derive(Debug, Default)]
+struct AutoRefresh {
+    /// Running refresh task, if enabled
+    task: Option<tokio::task::JoinHandle<()>>,
+    
+    /// Capabilities opted out of refresh
+    excluded: std::collections::HashSet<uuid::Uuid>,
+}
+
+/// Handle on a [`Client`] that does not keep it alive
+///
+/// Held by background tasks, which stop once every clone of the client has
+/// been dropped instead of keeping its state around forever.
+struct WeakClient {
+    config: std::sync::Weak<std::sync::RwLock<Arc<Config>>>,
+    transport: std::sync::Weak<std::sync::RwLock<Arc<dyn Transport + Send + Sync>>>,
+    owns_transport: bool,
+    identity: std::sync::Weak<RwLock<Option<Identity>>>,
+    capabilities: std::sync::Weak<RwLock<CapabilityCache>>,
+    responses: Option<std::sync::Weak<std::sync::Mutex<ResponseCache>>>,
+    audit_sink: std::sync::Weak<std::sync::RwLock<Arc<dyn AuditSink>>>,
+    metrics: std::sync::Weak<std::sync::RwLock<Arc<dyn Recorder>>>,
+    hints: std::sync::Weak<RwLock<ServerHints>>,
+    quota: std::sync::Weak<std::sync::Mutex<std::collections::HashMap<Domain, std::collections::VecDeque<std::time::Instant>>>>,
+    auto_refresh: std::sync::Weak<std::sync::Mutex<AutoRefresh>>,
+    workload: std::sync::Weak<std::sync::Mutex<Option<WorkloadIdentity>>>,
+    near_expiry: std::sync::Weak<std::sync::RwLock<Option<(Duration, NearExpiryCallback)>>>,
+    last_status: std::sync::Weak<std::sync::Mutex<Option<(std::time::Instant, VaultStatus)>>>,
+    in_flight: std::sync::Weak<tokio::sync::watch::Sender<usize>>,
+    closing: std::sync::Weak<std::sync::atomic::AtomicBool>,
+    revocations: tokio::sync::broadcast::Sender<uuid::Uuid>,
+}
+
+impl WeakClient {
+    /// The client, unless every clone of it has been dropped
+    fn upgrade(&self) -> Option<Client> {
+        Some(Client {
+            config: self.config.upgrade()?,
+            transport: self.transport.upgrade()?,
+            owns_transport: self.owns_transport,
+            identity: self.identity.upgrade()?,
+            capabilities: self.capabilities.upgrade()?,
+            responses: match &self.responses {
+                Some(responses) => Some(responses.upgrade()?),
+                None => None,
+            },
+            audit_sink: self.audit_sink.upgrade()?,
+            metrics: self.metrics.upgrade()?,
+            hints: self.hints.upgrade()?,
+            quota: self.quota.upgrade()?,
+            auto_refresh: self.auto_refresh.upgrade()?,
+            workload: self.workload.upgrade()?,
+            near_expiry: self.near_expiry.upgrade()?,
+            last_status: self.last_status.upgrade()?,
+            in_flight: self.in_flight.upgrade()?,
+            closing: self.closing.upgrade()?,
+            revocations: self.revocations.clone(),
+        })
+    }
 }
 
 impl Client {
+    /// Handle on this client that does not keep it alive
+    fn downgrade(&self) -> WeakClient {
+        WeakClient {
+            config: Arc::downgrade(&self.config),
+            transport: Arc::downgrade(&self.transport),
+            owns_transport: self.owns_transport,
+            identity: Arc::downgrade(&self.identity),
+            capabilities: Arc::downgrade(&self.capabilities),
+            responses: self.responses.as_ref().map(Arc::downgrade),
+            audit_sink: Arc::downgrade(&self.audit_sink),
+            metrics: Arc::downgrade(&self.metrics),
+            hints: Arc::downgrade(&self.hints),
+            quota: Arc::downgrade(&self.quota),
+            auto_refresh: Arc::downgrade(&self.auto_refresh),
+            workload: Arc::downgrade(&self.workload),
+            near_expiry: Arc::downgrade(&self.near_expiry),
+            last_status: Arc::downgrade(&self.last_status),
+            in_flight: Arc::downgrade(&self.in_flight),
+            closing: Arc::downgrade(&self.closing),
+            revocations: self.revocations.clone(),
+        }
+    }
+
     /// Create a new Vault client
     pub async fn new(config: Config) -> Result<Self> {
-        // Validate configuration
+        Self::builder().config(config).build().await
+    }
+
+    /// Start building a client
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Create a client reusing a transport from `pool`
+    ///
+    /// Clients whose configs share transport type, endpoint, auth, TLS and
+    /// timeout settings use the same underlying connection pool, avoiding
+    /// repeated TLS handshakes when many short-lived clients are created.
+    /// See [`TransportPool`] for ownership and credential-sharing caveats.
+    pub async fn with_shared_transport(config: Config, pool: &TransportPool) -> Result<Self> {
         config.validate()?;
-        
-        // Create transport layer
-        let transport: Arc<dyn Transport + Send + Sync> = match config.transport {
-            crate::config::TransportType::Http => {
-                Arc::new(crate::transport::HttpTransport::new(&config).await?)
-            }
-            crate::config::TransportType::Unix => {
-                Arc::new(crate::transport::UnixTransport::new(&config).await?)
-            }
-            crate::config::TransportType::Mtls => {
-                Arc::new(crate::transport::MtlsTransport::new(&config).await?)
-            }
-        };
-        
-        Ok(Self {
-            config: Arc::new(config),
-            transport,
+
+        let transport = pool.get_or_connect(&config).await?;
+
+        Self::with_transport(config, transport).await
+    }
+
+    /// Create a client around a caller-supplied transport
+    ///
+    /// Skips the built-in transport selection (and with it the config
+    /// validation done by [`Client::new`]), so in-memory test doubles or
+    /// transports the crate doesn't ship can be plugged in.
+    ///
+    /// ```rust,no_run
+    /// use aether_vault::capability::{Capability, CapabilityRequest};
+    /// use aether_vault::client::{HealthStatus, RevocationReceipt, ServerHints, VaultStatus};
+    /// use aether_vault::error::TransportError;
+    /// use aether_vault::transport::Transport;
+    /// use aether_vault::{Client, Config, Identity, Result};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// /// Transport for a network the client must never reach
+    /// #[derive(Debug)]
+    /// struct Offline;
+    ///
+    /// fn offline<T>() -> Result<T> {
+    ///     Err(TransportError::ConnectionFailed("offline".to_string()).into())
+    /// }
+    ///
+    /// #[async_trait::async_trait]
+    /// impl Transport for Offline {
+    ///     async fn request_capability(&self, _: &Identity, _: &CapabilityRequest) -> Result<Capability> { offline() }
+    ///     async fn access_with_capability<T>(&self, _: &Capability) -> Result<T>
+    ///     where
+    ///         T: serde::de::DeserializeOwned + Send,
+    ///     {
+    ///         offline()
+    ///     }
+    ///     async fn access_with_reason<T>(&self, _: &Capability, _: &str) -> Result<T>
+    ///     where
+    ///         T: serde::de::DeserializeOwned + Send,
+    ///     {
+    ///         offline()
+    ///     }
+    ///     async fn revoke_capability(&self, _: uuid::Uuid) -> Result<RevocationReceipt> { offline() }
+    ///     async fn revocation_status(&self, _: uuid::Uuid) -> Result<RevocationReceipt> { offline() }
+    ///     async fn refresh_capability(&self, _: &Identity, _: uuid::Uuid, _: Duration) -> Result<Capability> { offline() }
+    ///     async fn status(&self) -> Result<VaultStatus> { offline() }
+    ///     async fn health_check(&self) -> Result<HealthStatus> { offline() }
+    ///     async fn client_config(&self) -> Result<ServerHints> { Ok(ServerHints::default()) }
+    ///     async fn bootstrap_identity(&self, _: &str) -> Result<Identity> { offline() }
+    ///     async fn close(&self) -> Result<()> { Ok(()) }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     let client = Client::with_transport(Config::default(), Arc::new(Offline)).await?;
+    ///     assert!(client.status().await.is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn with_transport(config: Config, transport: Arc<dyn Transport + Send + Sync>) -> Result<Self> {
+        Self::assemble(config, transport, false).await
+    }
+
+    /// Create a client, noting whether the transport may be rebuilt from the config
+    async fn assemble(config: Config, transport: Arc<dyn Transport + Send + Sync>, owns_transport: bool) -> Result<Self> {
+        let audit_sink = audit_sink_for(&config);
+        let max_cached = config.cache.as_ref().map(|cache| cache.max_size);
+        let responses = response_cache(&config);
+        let hints_config = config.server_hints.clone();
+        let client = Self {
+            config: Arc::new(std::sync::RwLock::new(Arc::new(config))),
+            transport: Arc::new(std::sync::RwLock::new(transport)),
+            owns_transport,
             identity: Arc::new(RwLock::new(None)),
-            capabilities: Arc::new(RwLock::new(std::collections::HashMap::new())),
-        })
+            capabilities: Arc::new(RwLock::new(CapabilityCache::new(max_cached))),
+            responses,
+            audit_sink: Arc::new(std::sync::RwLock::new(audit_sink)),
+            metrics: Arc::new(std::sync::RwLock::new(Arc::new(NoopRecorder))),
+            hints: Arc::new(RwLock::new(ServerHints::default())),
+            quota: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            auto_refresh: Arc::new(std::sync::Mutex::new(AutoRefresh::default())),
+            workload: Arc::new(std::sync::Mutex::new(None)),
+            near_expiry: Arc::new(std::sync::RwLock::new(None)),
+            last_status: Arc::new(std::sync::Mutex::new(None)),
+            in_flight: Arc::new(tokio::sync::watch::channel(0).0),
+            closing: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            revocations: tokio::sync::broadcast::channel(REVOCATION_BACKLOG).0,
+        };
+
+        if hints_config.fetch {
+            client.fetch_server_hints().await;
+            if let Some(interval) = hints_config.refresh_interval {
+                client.spawn_hint_refresh(interval);
+            }
+        }
+        client.spawn_cache_sweep(CACHE_SWEEP_INTERVAL);
+
+        Ok(client)
     }
 
-    /// Set identity for the client
-    pub async fn set_identity(&self, identity: Identity) -> Result<()> {
-        let mut id_lock = self.identity.write().await;
-        *id_lock = Some(identity);
-        Ok(())
+    /// Create a client backed by an in-process issuer, for local development only
+    ///
+    /// No Vault server is contacted: capabilities are minted by a
+    /// [`MockTransport`](crate::transport::MockTransport) and signed with a
+    /// key generated for this client. The matching public key is returned
+    /// alongside, so [`Capability::validate_signature`] passes locally and
+    /// integration tests can run offline. A development identity is set.
+    ///
+    /// The endpoint (`VAULT_ENDPOINT`, default `http://localhost:8200`)
+    /// must be a loopback address; anything else is refused so this mode
+    /// can't be mistaken for a real deployment.
+    pub async fn local_dev() -> Result<(Self, Vec<u8>)> {
+        Self::local_dev_with_config(Config::from_env()?).await
     }
 
-    /// Get current identity
-    pub async fn get_identity(&self) -> Option<Identity> {
-        let id_lock = self.identity.read().await;
-        id_lock.clone()
+    /// [`Client::local_dev`] with an explicit configuration
+    ///
+    /// Auth is forced to `none`; the endpoint must still be localhost.
+    pub async fn local_dev_with_config(mut config: Config) -> Result<(Self, Vec<u8>)> {
+        config.auth.method = crate::config::AuthMethod::None;
+        if !is_loopback_endpoint(&config.endpoint) {
+            return Err(crate::error::ConfigError::InvalidValue(
+                "endpoint".to_string(),
+                format!("local dev mode only runs against localhost, not {}", config.endpoint),
+            ).into());
+        }
+        config.validate()?;
+
+        let seed: [u8; 32] = rand::random();
+        let transport = crate::transport::MockTransport::new().with_signing_key(&seed)?;
+        let public_key = transport.public_key().expect("signing key was just set");
+
+        let client = Self::with_transport(config, Arc::new(transport)).await?;
+        client.set_identity(Identity::new("local-dev".to_string())).await?;
+        Ok((client, public_key))
     }
 
-    /// Request a capability from Vault
-    pub async fn request_capability(
-        &self,
-        domain: Domain,
-        action: Action,
-        target: &str,
-        context: &Context,
-        ttl: Duration,
-    ) -> Result<Capability> {
-        // Check if we have an identity
-        let identity = self.get_identity().await
-            .ok_or(VaultError::Identity(crate::error::IdentityError::MissingIdentity))?;
+    /// Configuration currently in effect
+    pub fn config(&self) -> Arc<Config> {
+        Arc::clone(&self.config.read().unwrap_or_else(PoisonError::into_inner))
+    }
 
-        // Create capability request
-        let cap_request = CapabilityRequest::new(
-            domain,
-            action,
-            target.to_string(),
-            context.to_capability_context(),
-            ttl,
-        );
+    /// Transport currently in use
+    fn transport(&self) -> Arc<dyn Transport + Send + Sync> {
+        Arc::clone(&self.transport.read().unwrap_or_else(PoisonError::into_inner))
+    }
 
-        // Validate request
-        cap_request.validate()?;
+    /// Count an operation as in flight until the guard is dropped
+    ///
+    /// Fails while the client is closing. The operation is counted before
+    /// the flag is checked, so a drain either waits for it or refuses it.
+    fn enter(&self) -> Result<InFlightGuard> {
+        self.in_flight.send_modify(|count| *count += 1);
+        let guard = InFlightGuard(Arc::clone(&self.in_flight));
+        if self.closing.load(std::sync::atomic::Ordering::SeqCst) {
+            return Err(VaultError::Internal("client is closing".to_string()));
+        }
+        Ok(guard)
+    }
 
-        // Send request to Vault
-        let capability = self.transport.request_capability(&identity, &cap_request).await?;
+    /// Switch to a new configuration without recreating the client
+    ///
+    /// Retry, logging, access-reason, `server_hints.auto_apply` and default
+    /// TTL settings apply from the next call, and switching
+    /// `server_hints.fetch` on fetches the hints; a change to `logging.audit` or
+    /// `logging.format` replaces the audit sink with the built-in one.
+    /// The request timeout is changed on the current transport where it
+    /// supports that. Changes to the endpoint, transport type, auth, TLS,
+    /// proxy or connect timeout connect a new transport and swap it in,
+    /// letting calls already in flight finish on the old one. Clients around
+    /// an injected or pooled transport refuse such changes. Cache sizing
+    /// keeps its startup value. Invalid configs are rejected and the current
+    /// one kept.
+    pub async fn apply_config(&self, config: Config) -> Result<()> {
+        config.validate()?;
+        let current = self.config();
 
-        // Cache capability (short-lived)
-        {
-            let mut caps = self.capabilities.write().await;
-            caps.insert(capability.id, capability.clone());
+        // Compare everything but the timeouts that can change in place
+        let mut unchanged_timeouts = config.clone();
+        unchanged_timeouts.timeouts.request = current.timeouts.request;
+        unchanged_timeouts.timeouts.capability = current.timeouts.capability;
+        let mut reconnect = TransportPool::key(&current) != TransportPool::key(&unchanged_timeouts);
+        if !reconnect && current.timeouts.request != config.timeouts.request {
+            reconnect = !self.owns_transport || !self.transport().set_request_timeout(config.timeouts.request);
         }
 
-        Ok(capability)
-    }
+        if reconnect {
+            if !self.owns_transport {
+                return Err(VaultError::Validation(
+                    "transport settings cannot change on a client with an injected or pooled transport".to_string(),
+                ));
+            }
+            let transport = crate::transport::connect(&config).await?;
+            let previous = std::mem::replace(&mut *self.transport.write().unwrap_or_else(PoisonError::into_inner), transport);
+            tracing::info!("Switched Vault transport to {}", config.endpoint_url());
+            if let Err(e) = previous.close().await {
+                tracing::debug!("Closing the previous transport failed: {}", e);
+            }
+        }
 
-    /// Access resource using a capability
-    pub async fn access_with_capability<T>(&self, capability: &Capability) -> Result<T>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        // Validate capability
-        if !capability.is_valid() {
-            return Err(VaultError::Capability(
-                crate::error::CapabilityError::Expired(capability.expires_at)
-            ));
+        if current.logging.audit != config.logging.audit || current.logging.format != config.logging.format {
+            self.set_audit_sink(audit_sink_for(&config));
         }
 
-        // Check if capability is cached
-        let cached_cap = {
-            let caps = self.capabilities.read().await;
-            caps.get(&capability.id).cloned()
-        };
+        let enables_hints = config.server_hints.fetch && !current.server_hints.fetch;
+        *self.config.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(config);
+        if enables_hints {
+            self.fetch_server_hints().await;
+        }
+        Ok(())
+    }
+
+    /// Periodically re-fetch server hints until the client is dropped
+    fn spawn_hint_refresh(&self, interval: Duration) {
+        let transport = Arc::clone(&self.transport);
+        let hints = Arc::downgrade(&self.hints);
 
-        let cap_to_use = cached_cap.unwrap_or_else(|| capability.clone());
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(hints) = hints.upgrade() else { break };
+                let current = Arc::clone(&transport.read().unwrap_or_else(PoisonError::into_inner));
+                match current.client_config().await {
+                    Ok(fresh) => *hints.write().await = fresh,
+                    Err(e) => tracing::debug!("Server hint refresh failed: {}", e),
+                }
+            }
+        });
+    }
 
-        // Increment usage
-        let mut cap_for_usage = cap_to_use.clone();
-        cap_for_usage.increment_usage()?;
+    /// Periodically drop expired capabilities until the client is dropped
+    ///
+    /// Only the local copy is dropped; the server needs no revocation for
+    /// capabilities that have already expired.
+    fn spawn_cache_sweep(&self, interval: Duration) {
+        let capabilities = Arc::downgrade(&self.capabilities);
 
-        // Access resource
-        let result = self.transport.access_with_capability(&cap_for_use).await?;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(capabilities) = capabilities.upgrade() else { break };
+                let expired = capabilities.write().await.remove_expired(chrono::Utc::now());
+                if !expired.is_empty() {
+                    tracing::debug!("Dropped {} expired capabilities from the cache", expired.len());
+                }
+            }
+        });
+    }
 
-        // Update cached capability
-        {
-            let mut caps = self.capabilities.write().await;
-            caps.insert(capability.id, cap_for_usage);
+    /// Fetch hints, treating failure as having none
+    ///
+    /// Hints are advisory; servers without the endpoint are fine.
+    async fn fetch_server_hints(&self) {
+        if let Err(e) = self.refresh_server_hints().await {
+            tracing::debug!("Server hints unavailable: {}", e);
         }
+    }
 
-        Ok(result)
+    /// Fetch the server's client configuration hints now
+    pub async fn refresh_server_hints(&self) -> Result<ServerHints> {
+        let fresh = self.transport().client_config().await?;
+        *self.hints.write().await = fresh.clone();
+        Ok(fresh)
     }
 
-    /// Revoke a capability
-    pub async fn revoke_capability(&self, capability_id: uuid::Uuid) -> Result<()> {
-        // Remove from cache
-        {
-            let mut caps = self.capabilities.write().await;
-            caps.remove(&capability_id);
+    /// Latest hints published by the server (empty until fetched)
+    ///
+    /// Available whether or not `server_hints.auto_apply` is on.
+    pub async fn server_hints(&self) -> ServerHints {
+        self.hints.read().await.clone()
+    }
+
+    /// Default TTL for capabilities in `domain`
+    ///
+    /// A `default_ttl` in the domain's configured TTL policy wins. Otherwise,
+    /// with `server_hints.auto_apply` this is the server's recommended TTL,
+    /// clamped to the range the request validator accepts; otherwise (or
+    /// without a hint) it is `timeouts.capability`. Either is capped at the
+    /// domain's `max_ttl`.
+    pub async fn default_ttl(&self, domain: &Domain) -> Duration {
+        let policy = self.config().domain_ttls.get(domain).cloned();
+        if let Some(default_ttl) = policy.as_ref().and_then(|policy| policy.default_ttl) {
+            return default_ttl;
         }
+        let max_ttl = policy.map_or(CapabilityRequest::MAX_TTL, |policy| policy.max_ttl);
 
-        // Send revocation request
-        self.transport.revoke_capability(capability_id).await
+        if self.config().server_hints.auto_apply {
+            let hints = self.hints.read().await;
+            if let Some(hint) = hints.recommended_ttls.iter().find(|hint| &hint.domain == domain) {
+                return Duration::from_secs(hint.ttl_secs)
+                    .clamp(CapabilityRequest::MIN_TTL, CapabilityRequest::MAX_TTL)
+                    .min(max_ttl);
+            }
+        }
+        self.config().timeouts.capability.min(max_ttl)
     }
 
-    /// List active capabilities
-    pub async fn list_capabilities(&self) -> Result<Vec<Capability>> {
-        let caps = self.capabilities.read().await;
-        let mut active_caps = Vec::new();
+    /// Domains the server reports as available to this client
+    ///
+    /// Empty unless `server_hints.auto_apply` is enabled.
+    pub async fn domain_catalog(&self) -> Vec<Domain> {
+        if !self.config().server_hints.auto_apply {
+            return Vec::new();
+        }
+        self.hints.read().await.allowed_domains.clone()
+    }
+
+    /// Enforce the server-hinted request rate for `domain`, if any
+    async fn check_quota(&self, domain: &Domain) -> Result<()> {
+        if !self.config().server_hints.auto_apply {
+            return Ok(());
+        }
 
-        for cap in caps.values() {
-            if cap.is_valid() {
-                active_caps.push(cap.clone());
+        let limit = {
+            let hints = self.hints.read().await;
+            match hints.rate_limits.iter().find(|limit| &limit.domain == domain) {
+                Some(limit) => limit.clone(),
+                None => return Ok(()),
             }
+        };
+
+        let window = Duration::from_secs(limit.window_secs);
+        let now = std::time::Instant::now();
+        let mut quota = self.quota.lock().unwrap_or_else(PoisonError::into_inner);
+        let recent = quota.entry(domain.clone()).or_default();
+        while recent.front().map_or(false, |at| now.duration_since(*at) >= window) {
+            recent.pop_front();
         }
 
-        Ok(active_caps)
+        if recent.len() >= limit.max_requests as usize {
+            let oldest = recent.front().copied().unwrap_or(now);
+            return Err(VaultError::RateLimit(window.saturating_sub(now.duration_since(oldest))));
+        }
+        recent.push_back(now);
+        Ok(())
     }
 
-    /// Refresh a capability (extend TTL)
-    pub async fn refresh_capability(
-        &self,
-        capability_id: uuid::Uuid,
-        new_ttl: Duration,
-    ) -> Result<Capability> {
-        let identity = self.get_identity().await
-            .ok_or(VaultError::Identity(crate::error::IdentityError::MissingIdentity))?;
+    /// Acquire the capabilities lock while already holding the identity lock
+    ///
+    /// Requiring the identity guard enforces the identity-then-capabilities
+    /// ordering documented on [`Client`].
+    async fn capabilities_after_identity<'a>(
+        &'a self,
+        _identity: &RwLockWriteGuard<'_, Option<Identity>>,
+    ) -> RwLockWriteGuard<'a, CapabilityCache> {
+        debug_assert!(
+            self.identity.try_read().is_err(),
+            "identity lock of this client must be held before taking the capabilities lock"
+        );
+        self.capabilities.write().await
+    }
 
-        // Request refresh from Vault
-        let refreshed_cap = self.transport.refresh_capability(&identity, capability_id, new_ttl).await?;
+    /// Attach operation details and the endpoint to a failed transport call
+    fn contextualize<T, F>(&self, result: Result<T>, context: F) -> Result<T>
+    where
+        F: FnOnce() -> ErrorContext,
+    {
+        result.map_err(|e| e.contextualized(context().with_endpoint(self.config().endpoint_url())))
+    }
 
-        // Update cache
-        {
-            let mut caps = self.capabilities.write().await;
-            caps.insert(capability_id, refreshed_cap.clone());
+    /// Run a transport call under per-call options
+    async fn with_options<F>(&self, options: RequestOptions, call: F) -> F::Output
+    where
+        F: std::future::Future,
+    {
+        match options.timeout {
+            Some(timeout) => crate::transport::with_request_timeout(timeout, call).await,
+            None => call.await,
         }
+    }
 
-        Ok(refreshed_cap)
+    /// Run a transport operation, retrying retryable failures
+    ///
+    /// Sleeps the delays from `RetryConfig::backoff` between attempts (or
+    /// the server's `Retry-After` when rate limited, capped at
+    /// `RetryConfig::max_delay`) and gives up after
+    /// `max_retries`, returning the last error.
+    /// Access calls are not retried since they may have side effects.
+    async fn with_retry<T, F, Fut>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let config = self.config();
+        let retry = &config.retry;
+        let mut backoff = retry.backoff();
+        let mut attempt = 0;
+
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if e.is_retryable() && attempt < retry.max_retries => {
+                    // Honor the server-suggested interval when rate limited,
+                    // but never wait longer than the client is configured to
+                    let backoff_delay = backoff.next_delay();
+                    let delay = match e.root() {
+                        VaultError::RateLimit(retry_after) => (*retry_after).min(retry.max_delay),
+                        _ => backoff_delay,
+                    };
+                    self.metrics().record_retry(e.error_code());
+                    tracing::debug!(
+                        "Retryable error ({}), attempt {}/{}, retrying in {:?}",
+                        e.error_code(),
+                        attempt + 1,
+                        retry.max_retries,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
-    /// Get Vault status
-    pub async fn status(&self) -> Result<VaultStatus> {
-        self.transport.status().await
+    /// Send audit events to `sink` from now on
+    pub fn set_audit_sink(&self, sink: Arc<dyn AuditSink>) {
+        *self.audit_sink.write().unwrap_or_else(PoisonError::into_inner) = sink;
     }
 
-    /// Health check
-    pub async fn health_check(&self) -> Result<HealthStatus> {
-        self.transport.health_check().await
+    /// Report metrics to `recorder` from now on
+    pub fn set_metrics_recorder(&self, recorder: Arc<dyn Recorder>) {
+        *self.metrics.write().unwrap_or_else(PoisonError::into_inner) = recorder;
     }
 
-    /// Close the client and cleanup resources
-    pub async fn close(&self) -> Result<()> {
-        // Clear capabilities cache
-        {
-            let mut caps = self.capabilities.write().await;
-            caps.clear();
+    /// Current metrics recorder
+    fn metrics(&self) -> Arc<dyn Recorder> {
+        Arc::clone(&self.metrics.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Record the count, latency and any error of a finished operation
+    ///
+    /// The outcome is also recorded on the current operation span.
+    fn observe<T>(&self, operation: &'static str, started: std::time::Instant, result: &Result<T>) {
+        let metrics = self.metrics();
+        let span = tracing::Span::current();
+        metrics.record_request(operation);
+        metrics.record_latency(operation, started.elapsed());
+        match result {
+            Ok(_) => {
+                span.record("outcome", "success");
+            }
+            Err(e) => {
+                metrics.record_error(operation, e.error_code());
+                span.record("outcome", "failure");
+                span.record("error_code", e.error_code());
+            }
         }
+        tracing::debug!(latency = ?started.elapsed(), "{} finished", operation);
+    }
 
-        // Clear identity
-        {
-            let mut id = self.identity.write().await;
-            *id = None;
+    /// Hand an event to the current audit sink, tagged with the namespace
+    async fn audit(&self, mut event: AuditEvent) {
+        if event.namespace.is_none() {
+            event.namespace = self.config().namespace.clone();
         }
+        let sink = Arc::clone(&self.audit_sink.read().unwrap_or_else(PoisonError::into_inner));
+        sink.record(event).await;
+    }
 
-        // Close transport
-        self.transport.close().await
+    /// Set identity for the client
+    pub async fn set_identity(&self, identity: Identity) -> Result<()> {
+        let mut id_lock = self.identity.write().await;
+        *id_lock = Some(identity);
+        Ok(())
     }
-}
 
-/// Vault status information
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct VaultStatus {
-    /// Vault version
-    pub version: String,
-    
-    /// Server time
-    pub server_time: chrono::DateTime<chrono::Utc>,
-    
-    /// Initialization status
+    /// Get current identity
+    pub async fn get_identity(&self) -> Option<Identity> {
+        let id_lock = self.identity.read().await;
+        id_lock.clone()
+    }
+
+    /// Use a workload identity, refreshing it from its provider near expiry
+    pub async fn set_workload_identity(&self, workload: WorkloadIdentity) -> Result<()> {
+        let identity = workload.identity();
+        *self.workload.lock().unwrap_or_else(PoisonError::into_inner) = Some(workload);
+        self.set_identity(identity).await
+    }
+
+    /// Identity to present for a request
+    ///
+    /// A workload identity expiring within `auth.token_refresh_skew` is
+    /// reloaded from its provider first. Fails early with
+    /// `IdentityError::TokenExpired` rather than sending a dead token.
+    async fn usable_identity(&self) -> Result<Identity> {
+        let mut identity = self.get_identity().await
+            .ok_or(VaultError::Identity(crate::error::IdentityError::MissingIdentity))?;
+
+        // A skew too large to subtract reaches back past any expiry
+        let skew = chrono::Duration::from_std(self.config().auth.token_refresh_skew).ok();
+        let expiring = identity.expires_at().map_or(false, |expires_at| {
+            skew.and_then(|skew| expires_at.checked_sub_signed(skew))
+                .map_or(true, |refresh_at| refresh_at <= chrono::Utc::now())
+        });
+
+        if expiring {
+            let workload = self.workload.lock().unwrap_or_else(PoisonError::into_inner).clone();
+            if let Some(workload) = workload {
+                if let Err(e) = workload.reload() {
+                    tracing::warn!("Failed to refresh workload identity: {}", e);
+                }
+                let refreshed = workload.identity();
+                if refreshed.token() != identity.token() {
+                    self.set_identity(refreshed.clone()).await?;
+                    // Same workload, new token: it keeps its capabilities
+                    self.capabilities.write().await.reassign(&identity.fingerprint(), &refreshed.fingerprint());
+                    identity = refreshed;
+                }
+            }
+        }
+
+        match identity.expires_at() {
+            Some(expires_at) if identity.is_expired() => {
+                Err(crate::error::IdentityError::TokenExpired(expires_at).into())
+            }
+            _ => Ok(identity),
+        }
+    }
+
+    /// Bootstrap a workload identity from a one-time token
+    ///
+    /// Exchanges the token provisioned by the orchestrator at
+    /// `/v1/identity/bootstrap` and sets the resulting identity on the client.
+    /// The server enforces single use; the SDK's copy of the token is wiped as
+    /// soon as the exchange completes. The call is never retried, since a
+    /// consumed token cannot be presented twice.
+    pub async fn bootstrap_identity(&self, one_time_token: &str) -> Result<Identity> {
+        let started = std::time::Instant::now();
+        let token = zeroize::Zeroizing::new(one_time_token.to_string());
+        let request_id = uuid::Uuid::new_v4();
+        let result = crate::transport::with_request_id(request_id, self.transport().bootstrap_identity(&token)).await;
+        drop(token);
+
+        self.observe("bootstrap", started, &result);
+        self.audit(AuditEvent::new("bootstrap").with_request_id(request_id).with_outcome(&result)).await;
+        let identity = result?;
+
+        self.set_identity(identity.clone()).await?;
+        Ok(identity)
+    }
+
+    /// Request a capability from Vault
+    pub async fn request_capability(
+        &self,
+        domain: Domain,
+        action: Action,
+        target: &str,
+        context: &Context,
+        ttl: Duration,
+    ) -> Result<Capability> {
+        self.request_capability_with_options(domain, action, target, context, ttl, RequestOptions::default())
+            .await
+    }
+
+    /// Request a capability with per-call options
+    ///
+    /// `options.timeout` replaces the configured request timeout for each
+    /// attempt of this call; exceeding it yields `VaultError::Timeout`.
+    pub async fn request_capability_with_options(
+        &self,
+        domain: Domain,
+        action: Action,
+        target: &str,
+        context: &Context,
+        ttl: Duration,
+        options: RequestOptions,
+    ) -> Result<Capability> {
+        // Create capability request
+        let cap_request = CapabilityRequest::new(
+            domain,
+            action,
+            target.to_string(),
+            context.to_capability_context(),
+            ttl,
+        );
+
+        self.request_with_options(cap_request, options).await
+    }
+
+    /// Request a capability on behalf of `identity`
+    ///
+    /// Uses `identity` for this call only, leaving the client-wide identity
+    /// untouched, so a gateway can act for several tenants concurrently. The
+    /// issued capability is cached under the identity's subject; see
+    /// [`Client::list_capabilities_as`].
+    pub async fn request_capability_as(
+        &self,
+        identity: &Identity,
+        domain: Domain,
+        action: Action,
+        target: &str,
+        context: &Context,
+        ttl: Duration,
+    ) -> Result<Capability> {
+        let cap_request = CapabilityRequest::new(
+            domain,
+            action,
+            target.to_string(),
+            context.to_capability_context(),
+            ttl,
+        );
+
+        self.request_as(Some(identity), cap_request, RequestOptions::default()).await
+    }
+
+    /// Request a capability described by a prepared request
+    pub async fn request(&self, cap_request: CapabilityRequest) -> Result<Capability> {
+        self.request_with_options(cap_request, RequestOptions::default()).await
+    }
+
+    /// Request a capability described by a prepared request, with per-call options
+    pub async fn request_with_options(&self, cap_request: CapabilityRequest, options: RequestOptions) -> Result<Capability> {
+        self.request_as(None, cap_request, options).await
+    }
+
+    /// Request a capability as `identity`, or as the client's own identity
+    #[tracing::instrument(
+        name = "request_capability",
+        skip_all,
+        fields(
+            domain = %cap_request.domain,
+            action = %cap_request.action,
+            target = %cap_request.target,
+            capability_id = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+            error_code = tracing::field::Empty,
+        ),
+    )]
+    async fn request_as(
+        &self,
+        identity: Option<&Identity>,
+        cap_request: CapabilityRequest,
+        options: RequestOptions,
+    ) -> Result<Capability> {
+        check_options(&options)?;
+        let _in_flight = self.enter()?;
+        self.check_not_sealed()?;
+
+        // Remember how to replace the capability, unless issued to another identity
+        let origin = (identity.is_none() && self.config().rerequest_on_revoked).then(|| {
+            let mut origin = cap_request.clone();
+            origin.idempotency_key = None;
+            origin
+        });
+
+        // Check we have a live identity
+        let identity = match identity {
+            Some(identity) => match identity.expires_at() {
+                Some(expires_at) if identity.is_expired() => {
+                    return Err(crate::error::IdentityError::TokenExpired(expires_at).into());
+                }
+                _ => identity.clone(),
+            },
+            None => self.usable_identity().await?,
+        };
+
+        self.check_quota(&cap_request.domain).await?;
+
+        // Keep the capability within the identity's lifetime
+        let mut cap_request = self.fit_ttl(&identity, cap_request)?;
+
+        // One key for every attempt, so the server can dedupe retries
+        cap_request.idempotency_key.get_or_insert_with(uuid::Uuid::new_v4);
+
+        // Validate request
+        self.validate_request(&cap_request)?;
+
+        // Send request to Vault
+        let started = std::time::Instant::now();
+        let transport = self.transport();
+        let request_id = uuid::Uuid::new_v4();
+        let result = crate::transport::with_request_id(
+            request_id,
+            self.with_options(options, self.with_retry(|| transport.request_capability(&identity, &cap_request))),
+        ).await;
+        let result = self.contextualize(result, || ErrorContext::for_request(&cap_request));
+        if let Ok(capability) = &result {
+            tracing::Span::current().record("capability_id", tracing::field::display(capability.id));
+        }
+        self.observe("request", started, &result);
+
+        let event = match &result {
+            Ok(capability) => AuditEvent::for_capability("request", capability),
+            Err(_) => {
+                let mut event = AuditEvent::new("request");
+                event.domain = Some(cap_request.domain.clone());
+                event.action = Some(cap_request.action.clone());
+                event.target = Some(cap_request.target.clone());
+                event
+            }
+        };
+        self.audit(
+            event
+                .with_reason(cap_request.justification.as_deref())
+                .with_request_id(request_id)
+                .with_outcome(&result),
+        ).await;
+        let capability = result?;
+
+        // Cache capability (short-lived)
+        {
+            let mut caps = self.capabilities.write().await;
+            caps.insert(capability.clone());
+            caps.set_owner(capability.id, identity.fingerprint());
+            if let Some(origin) = origin {
+                caps.set_origin(capability.id, origin);
+            }
+        }
+
+        Ok(capability)
+    }
+
+    /// Apply the configured `TtlPolicy` when the TTL outlives the identity
+    ///
+    /// The domain's own TTL policy, if any, is applied first.
+    fn fit_ttl(&self, identity: &Identity, request: CapabilityRequest) -> Result<CapabilityRequest> {
+        let mut request = self.fit_domain_ttl(request)?;
+        let Some(expires_at) = identity.expires_at() else {
+            return Ok(request);
+        };
+        let remaining = (expires_at - chrono::Utc::now()).to_std().unwrap_or(Duration::ZERO);
+        if request.ttl <= remaining {
+            return Ok(request);
+        }
+
+        match self.config().auth.ttl_policy {
+            crate::config::TtlPolicy::Clamp => {
+                tracing::debug!(
+                    "Clamping capability TTL from {:?} to {:?} to match identity expiry",
+                    request.ttl,
+                    remaining
+                );
+                request.ttl = remaining;
+                Ok(request)
+            }
+            crate::config::TtlPolicy::Reject => {
+                Err(crate::error::IdentityError::TokenExpiresBeforeCapability(expires_at).into())
+            }
+        }
+    }
+
+    /// Apply the domain's configured `max_ttl`, if the domain has a policy
+    fn fit_domain_ttl(&self, mut request: CapabilityRequest) -> Result<CapabilityRequest> {
+        let Some(policy) = self.config().domain_ttls.get(&request.domain) else {
+            return Ok(request);
+        };
+        if request.ttl <= policy.max_ttl {
+            return Ok(request);
+        }
+
+        match policy.on_exceed {
+            crate::config::TtlPolicy::Clamp => {
+                tracing::debug!(
+                    "Clamping capability TTL from {:?} to the {:?} maximum for domain {}",
+                    request.ttl,
+                    policy.max_ttl,
+                    request.domain
+                );
+                request.ttl = policy.max_ttl;
+                Ok(request)
+            }
+            crate::config::TtlPolicy::Reject => Err(crate::error::CapabilityError::InvalidFormat(format!(
+                "TTL {:?} exceeds the {:?} maximum for domain {}",
+                request.ttl, policy.max_ttl, request.domain
+            )).into()),
+        }
+    }
+
+    /// Validate a request, including the configured justification requirements
+    fn validate_request(&self, request: &CapabilityRequest) -> Result<()> {
+        request.validate()?;
+        request.check_justification(&self.config().require_justification_for)
+    }
+
+    /// Ask the server whether a capability request would be granted
+    ///
+    /// Nothing is issued: the check is not audited as a request and does
+    /// not count against capability quotas, so CI can verify a service's
+    /// requested scopes against policy. The request is validated and its
+    /// TTL fitted to the identity exactly as [`Client::request`] would.
+    pub async fn check_capability(&self, cap_request: CapabilityRequest) -> Result<PolicyDecision> {
+        let _in_flight = self.enter()?;
+        let identity = self.usable_identity().await?;
+        let cap_request = self.fit_ttl(&identity, cap_request)?;
+        self.validate_request(&cap_request)?;
+
+        let transport = self.transport();
+        let result = self.with_retry(|| transport.check_capability(&identity, &cap_request)).await;
+        self.contextualize(result, || ErrorContext::for_request(&cap_request))
+    }
+
+    /// Request several capabilities in one round trip
+    ///
+    /// Each request gets its own result, in order, so one invalid or refused
+    /// request doesn't fail the rest. The outer error is reserved for
+    /// failures affecting the whole batch, such as a missing identity or an
+    /// unreachable server.
+    pub async fn request_capabilities(&self, requests: Vec<CapabilityRequest>) -> Result<Vec<Result<Capability>>> {
+        let _in_flight = self.enter()?;
+        let identity = self.usable_identity().await?;
+
+        let mut results: Vec<Option<Result<Capability>>> = Vec::with_capacity(requests.len());
+        let mut pending = Vec::new();
+        for request in &requests {
+            let checked = match self.fit_ttl(&identity, request.clone()) {
+                Ok(mut request) => match self.validate_request(&request) {
+                    Ok(()) => self.check_quota(&request.domain).await.map(|()| {
+                        request.idempotency_key.get_or_insert_with(uuid::Uuid::new_v4);
+                        request
+                    }),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+            match checked {
+                Ok(request) => {
+                    pending.push(request);
+                    results.push(None);
+                }
+                Err(e) => results.push(Some(Err(e))),
+            }
+        }
+
+        let started = std::time::Instant::now();
+        let (issued, request_ids) = if pending.is_empty() {
+            (Vec::new(), Vec::new())
+        } else if self.supports(Feature::Batch).await {
+            let transport = self.transport();
+            let request_id = uuid::Uuid::new_v4();
+            let issued = crate::transport::with_request_id(
+                request_id,
+                self.with_retry(|| transport.request_capabilities(&identity, &pending)),
+            ).await?;
+            (issued, vec![request_id; pending.len()])
+        } else {
+            // One request at a time against servers without batch support
+            let transport = self.transport();
+            let mut issued = Vec::with_capacity(pending.len());
+            let mut request_ids = Vec::with_capacity(pending.len());
+            for request in &pending {
+                let request_id = uuid::Uuid::new_v4();
+                issued.push(
+                    crate::transport::with_request_id(
+                        request_id,
+                        self.with_retry(|| transport.request_capability(&identity, request)),
+                    ).await,
+                );
+                request_ids.push(request_id);
+            }
+            (issued, request_ids)
+        };
+        if issued.len() != pending.len() {
+            return Err(VaultError::InvalidResponse(format!(
+                "batch returned {} results for {} requests",
+                issued.len(),
+                pending.len()
+            )));
+        }
+
+        let mut issued = issued.into_iter().zip(request_ids);
+        let mut completed = Vec::with_capacity(results.len());
+        for (request, slot) in requests.iter().zip(results) {
+            let (result, request_id) = match slot {
+                Some(rejected) => (rejected, None),
+                None => {
+                    let (result, request_id) = issued.next().expect("one batch result per pending request");
+                    (result, Some(request_id))
+                }
+            };
+
+            let event = match &result {
+                Ok(capability) => AuditEvent::for_capability("request", capability),
+                Err(_) => {
+                    let mut event = AuditEvent::new("request");
+                    event.domain = Some(request.domain.clone());
+                    event.action = Some(request.action.clone());
+                    event.target = Some(request.target.clone());
+                    event
+                }
+            };
+            let event = match request_id {
+                Some(request_id) => event.with_request_id(request_id),
+                None => event,
+            };
+            self.observe("request", started, &result);
+            self.audit(event.with_reason(request.justification.as_deref()).with_outcome(&result)).await;
+            completed.push(result);
+        }
+
+        {
+            let mut caps = self.capabilities.write().await;
+            let owner = identity.fingerprint();
+            for capability in completed.iter().flatten() {
+                caps.insert(capability.clone());
+                caps.set_owner(capability.id, owner.clone());
+            }
+        }
+
+        Ok(completed)
+    }
+
+    /// Request a capability, run `operation` with it, then revoke it
+    ///
+    /// The capability is revoked whether `operation` succeeds or fails, so it
+    /// never outlives the call. `operation` receives its own copy of the
+    /// capability. A failed revocation is logged and does not replace the
+    /// result of `operation`.
+    pub async fn with_capability<F, Fut, T>(&self, request: CapabilityRequest, operation: F) -> Result<T>
+    where
+        F: FnOnce(Capability) -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let capability = self.request(request).await?;
+        let capability_id = capability.id;
+
+        let result = operation(capability).await;
+
+        if let Err(e) = self.revoke_capability(capability_id).await {
+            tracing::warn!("Failed to revoke scoped capability {}: {}", capability_id, e);
+        }
+
+        result
+    }
+
+    /// Request a database capability
+    ///
+    /// Actions outside [`DatabaseCapability::ALLOWED_ACTIONS`] are rejected
+    /// before contacting Vault.
+    pub async fn request_database(
+        &self,
+        action: Action,
+        target: &str,
+        context: &Context,
+        ttl: Duration,
+    ) -> Result<DatabaseCapability> {
+        DatabaseCapability::check_action(&action)?;
+        self.request_capability(Domain::Database, action, target, context, ttl).await?.try_into()
+    }
+
+    /// Request an SSH capability
+    pub async fn request_ssh(&self, target: &str, context: &Context, ttl: Duration) -> Result<SshCapability> {
+        self.request_capability(Domain::Ssh, Action::Execute, target, context, ttl).await?.try_into()
+    }
+
+    /// Request a TLS certificate capability
+    pub async fn request_tls(
+        &self,
+        action: Action,
+        target: &str,
+        context: &Context,
+        ttl: Duration,
+    ) -> Result<TlsCapability> {
+        TlsCapability::check_action(&action)?;
+        self.request_capability(Domain::Tls, action, target, context, ttl).await?.try_into()
+    }
+
+    /// Request a capability that is revoked automatically when dropped
+    ///
+    /// See [`CapabilityGuard`] for the cleanup semantics.
+    pub async fn acquire(
+        &self,
+        domain: Domain,
+        action: Action,
+        target: &str,
+        context: &Context,
+        ttl: Duration,
+    ) -> Result<CapabilityGuard> {
+        let capability = self.request_capability(domain, action, target, context, ttl).await?;
+        Ok(CapabilityGuard {
+            capability,
+            client: self.clone(),
+            revoked: false,
+        })
+    }
+
+    /// Call `callback` whenever a capability is accessed with less than
+    /// `threshold` left before it expires
+    ///
+    /// The callback runs once per access, before the request is sent, giving
+    /// the caller a chance to refresh the capability before its next use.
+    /// A warning is logged as well. Replaces any previous callback.
+    pub fn on_near_expiry<F>(&self, threshold: Duration, callback: F)
+    where
+        F: Fn(uuid::Uuid, Duration) + Send + Sync + 'static,
+    {
+        *self.near_expiry.write().unwrap_or_else(PoisonError::into_inner) = Some((threshold, Arc::new(callback)));
+    }
+
+    /// Stop checking accesses for near expiry
+    pub fn clear_near_expiry(&self) {
+        *self.near_expiry.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Access resource using a capability
+    ///
+    /// With `cache.enabled`, read and list responses are served from memory
+    /// for up to `cache.ttl` after the first fetch. The capability is still
+    /// validated, its use counted and the access audited on a cache hit, but
+    /// the server neither sees the access nor gets a chance to refuse it.
+    /// A failed access does not count against the capability's usage limits.
+    ///
+    /// With `rerequest_on_revoked`, a capability the server reports revoked
+    /// or expired is replaced once from its original request, going through
+    /// the usual validation, TTL policy and quotas, and the access retried
+    /// with the replacement. A second failure is returned as is. Later
+    /// accesses with the stale capability use the cached replacement; a
+    /// capability that already fails the local expiry checks is never replaced.
+    pub async fn access_with_capability<T>(&self, capability: &Capability) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let replacement = self.capabilities.read().await.replacement(&capability.id).cloned();
+        let capability = replacement.as_ref().unwrap_or(capability);
+
+        let (result, reached_server) = self.access_attempt(capability, None, RequestOptions::default()).await;
+        let replaceable = reached_server && matches!(
+            &result,
+            Err(e) if matches!(e.root(), VaultError::Capability(
+                crate::error::CapabilityError::Revoked(_) | crate::error::CapabilityError::Expired(_)
+            ))
+        );
+        if !replaceable || !self.config().rerequest_on_revoked {
+            return result.map(|response| response.data);
+        }
+
+        let Some(replacement) = self.rerequest(capability).await? else {
+            return result.map(|response| response.data);
+        };
+        tracing::debug!("Replaced capability {} with {}", capability.id, replacement.id);
+        self.access(&replacement, None, RequestOptions::default()).await.map(|response| response.data)
+    }
+
+    /// Request a replacement for `capability` from the request that issued it
+    ///
+    /// Returns `None` when the original request is unknown. The stale
+    /// capability leaves the cache either way; on success its id maps to
+    /// the replacement.
+    async fn rerequest(&self, capability: &Capability) -> Result<Option<Capability>> {
+        let origin = {
+            let mut caps = self.capabilities.write().await;
+            let origin = caps.origin(&capability.id).cloned();
+            if origin.is_none() {
+                caps.remove(&capability.id);
+            }
+            origin
+        };
+        let Some(request) = origin else {
+            return Ok(None);
+        };
+
+        let replacement = self.request(request).await;
+        let mut caps = self.capabilities.write().await;
+        match replacement {
+            Ok(replacement) => {
+                caps.replace(&capability.id, replacement.id);
+                Ok(Some(replacement))
+            }
+            Err(e) => {
+                caps.remove(&capability.id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Access a resource, first refreshing the capability if it is close to expiry
+    ///
+    /// When less than `min_remaining` is left, the capability is refreshed to
+    /// `refresh_ttl` and the caller's copy replaced with the refreshed one
+    /// (as is the cached copy), so it can keep being used for a while.
+    pub async fn access_refreshing<T>(
+        &self,
+        capability: &mut Capability,
+        min_remaining: Duration,
+        refresh_ttl: Duration,
+    ) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        if capability.remaining_ttl().map_or(true, |remaining| remaining < min_remaining) {
+            *capability = self.refresh_capability(capability.id, refresh_ttl).await?;
+        }
+
+        self.access_with_capability(capability).await
+    }
+
+    /// Access resource using a capability, keeping the server's lease metadata
+    ///
+    /// Lets callers renew based on the server's `lease_duration` and
+    /// `renewable` flag rather than the client-side capability TTL. On a
+    /// response cache hit the metadata is as of the cached fetch.
+    pub async fn access_detailed<T>(&self, capability: &Capability) -> Result<AccessResponse<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.access(capability, None, RequestOptions::default()).await
+    }
+
+    /// Access resource using a capability, with per-call options
+    ///
+    /// `options.timeout` replaces the configured request timeout for this
+    /// access, e.g. to allow a large artifact longer than a status ping.
+    pub async fn access_with_options<T>(&self, capability: &Capability, options: RequestOptions) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        check_options(&options)?;
+        self.access(capability, None, options).await.map(|response| response.data)
+    }
+
+    /// Access resource using a capability, recording why this use is needed
+    ///
+    /// The reason is sent with the access request and recorded in the audit
+    /// event. Domains listed in `Config::require_access_reason_for` reject
+    /// accesses without a non-empty reason.
+    pub async fn access_with_reason<T>(&self, capability: &Capability, reason: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.access(capability, Some(reason), RequestOptions::default()).await.map(|response| response.data)
+    }
+
+    /// Shared access path with optional per-use reason
+    async fn access<T>(&self, capability: &Capability, reason: Option<&str>, options: RequestOptions) -> Result<AccessResponse<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.access_attempt(capability, reason, options).await.0
+    }
+
+    /// [`Client::access`], also telling whether the capability passed the local checks
+    ///
+    /// `false` means the access was refused before reaching the transport or
+    /// the response cache.
+    #[tracing::instrument(
+        name = "access",
+        skip_all,
+        fields(
+            domain = %capability.domain,
+            action = %capability.action,
+            target = %capability.target,
+            capability_id = %capability.id,
+            outcome = tracing::field::Empty,
+            error_code = tracing::field::Empty,
+        ),
+    )]
+    async fn access_attempt<T>(
+        &self,
+        capability: &Capability,
+        reason: Option<&str>,
+        options: RequestOptions,
+    ) -> (Result<AccessResponse<T>>, bool)
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let _in_flight = match self.enter() {
+            Ok(guard) => guard,
+            Err(e) => return (Err(e), false),
+        };
+        let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+        let cap_for_usage = match self.begin_access(capability, reason).await {
+            Ok(cap_for_usage) => cap_for_usage,
+            Err(e) => return (Err(e), false),
+        };
+
+        // Access resource; reasoned accesses always reach the server
+        let started = std::time::Instant::now();
+        let request_id = uuid::Uuid::new_v4();
+        let result = crate::transport::with_request_id(request_id, self.with_options(options, async {
+            match (reason, &self.responses) {
+                (Some(reason), _) => self.transport().access_with_reason(&cap_for_usage, reason).await,
+                (None, Some(responses)) if caches_responses(&cap_for_usage.action) => {
+                    self.access_cached(responses, &cap_for_usage).await
+                }
+                (None, _) => self.transport().access_with_capability(&cap_for_usage).await,
+            }
+        })).await;
+        if result.is_err() {
+            self.release_access(capability.id).await;
+        }
+        let result = self.contextualize(result, || ErrorContext::for_capability(&cap_for_usage));
+        self.observe("access", started, &result);
+
+        self.audit(
+            AuditEvent::for_capability("access", capability)
+                .with_reason(reason)
+                .with_request_id(request_id)
+                .with_outcome(&result),
+        ).await;
+        (result, true)
+    }
+
+    /// Serve an access from the response cache, fetching it on a miss
+    async fn access_cached<T>(&self, responses: &std::sync::Mutex<ResponseCache>, capability: &Capability) -> Result<AccessResponse<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let cached = responses.lock().unwrap_or_else(PoisonError::into_inner).get(capability.id, &capability.target);
+        let payload = match cached {
+            Some(payload) => {
+                self.metrics().record_cache_hit();
+                payload
+            }
+            None => {
+                self.metrics().record_cache_miss();
+                let value: serde_json::Value = self.transport().access_with_capability(capability).await?;
+                let payload = zeroize::Zeroizing::new(serde_json::to_vec(&value)?);
+                responses.lock().unwrap_or_else(PoisonError::into_inner).insert(capability.id, &capability.target, payload.clone());
+                payload
+            }
+        };
+
+        serde_json::from_slice(&payload).map_err(|e| VaultError::InvalidResponse(e.to_string()))
+    }
+
+    /// Access a secret as a stream, without buffering the whole payload
+    ///
+    /// Validates the capability and counts the use exactly like
+    /// [`Client::access_with_capability`] before the stream is opened.
+    #[tracing::instrument(
+        name = "access",
+        skip_all,
+        fields(
+            domain = %capability.domain,
+            action = %capability.action,
+            target = %capability.target,
+            capability_id = %capability.id,
+            outcome = tracing::field::Empty,
+            error_code = tracing::field::Empty,
+        ),
+    )]
+    pub async fn access_stream(&self, capability: &Capability) -> Result<impl tokio::io::AsyncRead + Send + Unpin> {
+        let _in_flight = self.enter()?;
+        let cap_for_usage = self.begin_access(capability, None).await?;
+
+        let started = std::time::Instant::now();
+        let request_id = uuid::Uuid::new_v4();
+        let result = crate::transport::with_request_id(request_id, self.transport().access_stream(&cap_for_usage)).await;
+        if result.is_err() {
+            self.release_access(capability.id).await;
+        }
+        let result = self.contextualize(result, || ErrorContext::for_capability(&cap_for_usage));
+        self.observe("access", started, &result);
+
+        self.audit(
+            AuditEvent::for_capability("access", capability)
+                .with_request_id(request_id)
+                .with_outcome(&result),
+        ).await;
+        result
+    }
+
+    /// Check a capability may be used and count the use
+    ///
+    /// Returns the copy of the capability carrying the updated usage count.
+    /// The use is counted up front so concurrent accesses cannot exceed the
+    /// limit; [`Client::release_access`] gives it back if the access fails.
+    async fn begin_access(&self, capability: &Capability, reason: Option<&str>) -> Result<Capability> {
+        if reason.is_none() && self.config().require_access_reason_for.contains(&capability.domain) {
+            return Err(VaultError::Validation(format!(
+                "access reason required for domain {}",
+                capability.domain
+            )));
+        }
+
+        // Validate capability, leaving room for the call itself to complete
+        let min_remaining = self.config().min_remaining_ttl;
+        let enough_left = capability.remaining_ttl().map_or(false, |remaining| remaining >= min_remaining);
+        if !capability.is_valid() || !enough_left {
+            return Err(VaultError::Capability(
+                crate::error::CapabilityError::Expired(capability.expires_at)
+            ));
+        }
+
+        let near_expiry = self.near_expiry.read().unwrap_or_else(PoisonError::into_inner).clone();
+        if let (Some((threshold, callback)), Some(remaining)) = (near_expiry, capability.remaining_ttl()) {
+            if remaining < threshold {
+                tracing::warn!("Capability {} used with {:?} left before expiry", capability.id, remaining);
+                callback(capability.id, remaining);
+            }
+        }
+
+        // Count the use against the cached copy (or the caller's, if not
+        // cached) and write it back in one critical section, so concurrent
+        // accesses cannot lose increments
+        let cap_for_usage = {
+            let mut caps = self.capabilities.write().await;
+            let mut cap_for_usage = caps.get(&capability.id).cloned()
+                .unwrap_or_else(|| capability.clone());
+            cap_for_usage.increment_usage()?;
+            caps.insert(cap_for_usage.clone());
+            cap_for_usage
+        };
+
+        Ok(cap_for_usage)
+    }
+
+    /// Give back the use [`Client::begin_access`] counted for an access that failed
+    async fn release_access(&self, capability_id: uuid::Uuid) {
+        let mut caps = self.capabilities.write().await;
+        if let Some(mut capability) = caps.get(&capability_id).cloned() {
+            capability.release_usage();
+            caps.insert(capability);
+        }
+    }
+
+    /// Access a secret and post-process it through a transform
+    ///
+    /// A JSON string payload is handed to the transform as its raw text,
+    /// any other payload as its JSON encoding. The transformed bytes are
+    /// decoded into `T` as JSON, or as plain text when they aren't JSON.
+    /// Transform failures surface as `VaultError::InvalidResponse`.
+    pub async fn access_transformed<T>(&self, capability: &Capability, transform: &dyn SecretTransform) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let payload: serde_json::Value = self.access_with_capability(capability).await?;
+        let raw = zeroize::Zeroizing::new(match payload {
+            serde_json::Value::String(text) => text.into_bytes(),
+            other => serde_json::to_vec(&other)?,
+        });
+
+        let output = transform.apply(raw)?;
+
+        serde_json::from_slice(&output).or_else(|_| {
+            let text = zeroize::Zeroizing::new(String::from_utf8(output.to_vec())
+                .map_err(|_| VaultError::InvalidResponse("transformed payload is not valid UTF-8".to_string()))?);
+            serde_json::from_value(serde_json::Value::String(text.to_string()))
+                .map_err(|e| VaultError::InvalidResponse(e.to_string()))
+        })
+    }
+
+    /// Run an operation needing several capabilities at once
+    ///
+    /// Every member of the composite is validated (including cached usage
+    /// counts) before `operation` runs; if any member is invalid the call
+    /// fails with the reason from [`Capability::check_valid`] without
+    /// performing any access.
+    pub async fn access_composite<'a, F, Fut, T>(
+        &self,
+        composite: &'a CompositeCapability,
+        operation: F,
+    ) -> Result<T>
+    where
+        F: FnOnce(&'a [Capability]) -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        if composite.capabilities().is_empty() {
+            return Err(VaultError::Validation("composite capability has no members".to_string()));
+        }
+
+        {
+            let caps = self.capabilities.read().await;
+            for member in composite.capabilities() {
+                let current = caps.get(&member.id).unwrap_or(member);
+                member.check_valid()?;
+                current.check_valid()?;
+            }
+        }
+
+        operation(composite.capabilities()).await
+    }
+
+    /// Revoke a capability
+    pub async fn revoke_capability(&self, capability_id: uuid::Uuid) -> Result<()> {
+        self.revoke_capability_with_reason(capability_id, RevokeReason::Unspecified).await
+    }
+
+    /// Revoke a capability, recording why
+    ///
+    /// The reason is sent to the server and recorded on the audit event, so
+    /// forensics can tell a routine rotation from a compromise.
+    pub async fn revoke_capability_with_reason(&self, capability_id: uuid::Uuid, reason: RevokeReason) -> Result<()> {
+        self.revoke(capability_id, reason).await.map(|_| ())
+    }
+
+    /// Revoke a capability and return the server's revocation receipt
+    ///
+    /// Some deployments queue revocations, in which case the receipt is
+    /// `Pending`; use [`Client::await_revocation`] when the revocation must
+    /// have taken effect before continuing.
+    pub async fn revoke_capability_with_receipt(&self, capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        self.revoke(capability_id, RevokeReason::Unspecified).await
+    }
+
+    #[tracing::instrument(
+        name = "revoke",
+        skip_all,
+        fields(
+            capability_id = %capability_id,
+            reason = %reason,
+            domain = tracing::field::Empty,
+            action = tracing::field::Empty,
+            target = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+            error_code = tracing::field::Empty,
+        ),
+    )]
+    async fn revoke(&self, capability_id: uuid::Uuid, reason: RevokeReason) -> Result<RevocationReceipt> {
+        let _in_flight = self.enter()?;
+        // Remove from cache
+        let cached = {
+            let mut caps = self.capabilities.write().await;
+            caps.remove(&capability_id)
+        };
+        if let Some(responses) = &self.responses {
+            responses.lock().unwrap_or_else(PoisonError::into_inner).remove_capability(capability_id);
+        }
+        self.auto_refresh.lock().unwrap_or_else(PoisonError::into_inner).excluded.remove(&capability_id);
+        if let Some(capability) = &cached {
+            let span = tracing::Span::current();
+            span.record("domain", tracing::field::display(&capability.domain));
+            span.record("action", tracing::field::display(&capability.action));
+            span.record("target", tracing::field::display(&capability.target));
+        }
+
+        // Send revocation request
+        let started = std::time::Instant::now();
+        let transport = self.transport();
+        let request_id = uuid::Uuid::new_v4();
+        let result = crate::transport::with_request_id(
+            request_id,
+            self.with_retry(|| transport.revoke_capability_with_reason(capability_id, &reason)),
+        ).await;
+        let result = self.contextualize(result, || match &cached {
+            Some(capability) => ErrorContext::for_capability(capability),
+            None => ErrorContext { capability_id: Some(capability_id), ..ErrorContext::default() },
+        });
+        self.observe("revoke", started, &result);
+
+        let event = match &cached {
+            Some(capability) => AuditEvent::for_capability("revoke", capability),
+            None => {
+                let mut event = AuditEvent::new("revoke");
+                event.capability_id = Some(capability_id);
+                event
+            }
+        };
+        let recorded_reason = (reason != RevokeReason::Unspecified).then(|| reason.to_string());
+        self.audit(
+            event
+                .with_reason(recorded_reason.as_deref())
+                .with_request_id(request_id)
+                .with_outcome(&result),
+        ).await;
+
+        if result.is_ok() {
+            let _ = self.revocations.send(capability_id);
+        }
+        result
+    }
+
+    /// Revoke every cached capability held by the current identity
+    ///
+    /// Revocations go out as a single batch where the server supports it.
+    /// Successfully revoked capabilities leave the cache; failed ones stay
+    /// so the caller can retry. Returns one result per capability.
+    /// Capabilities issued to other subjects are left alone; see
+    /// [`Client::list_capabilities`].
+    pub async fn revoke_all(&self) -> Result<Vec<(uuid::Uuid, Result<()>)>> {
+        let held = self.held_capabilities().await;
+        self.revoke_cached(held).await
+    }
+
+    /// Revoke `held` as one batch, dropping the successes from the caches
+    async fn revoke_cached(&self, held: Vec<Capability>) -> Result<Vec<(uuid::Uuid, Result<()>)>> {
+        if held.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<uuid::Uuid> = held.iter().map(|capability| capability.id).collect();
+        let started = std::time::Instant::now();
+        let transport = self.transport();
+        let request_id = uuid::Uuid::new_v4();
+        let receipts = crate::transport::with_request_id(
+            request_id,
+            self.with_retry(|| transport.revoke_capabilities(&ids)),
+        ).await?;
+        if receipts.len() != ids.len() {
+            return Err(VaultError::InvalidResponse(format!(
+                "batch returned {} results for {} revocations",
+                receipts.len(),
+                ids.len()
+            )));
+        }
+
+        {
+            let mut caps = self.capabilities.write().await;
+            let mut auto_refresh = self.auto_refresh.lock().unwrap_or_else(PoisonError::into_inner);
+            for (id, receipt) in ids.iter().zip(&receipts) {
+                if receipt.is_ok() {
+                    caps.remove(id);
+                    let _ = self.revocations.send(*id);
+                    if let Some(responses) = &self.responses {
+                        responses.lock().unwrap_or_else(PoisonError::into_inner).remove_capability(*id);
+                    }
+                    auto_refresh.excluded.remove(id);
+                }
+            }
+        }
+
+        let mut outcomes = Vec::with_capacity(held.len());
+        for (capability, receipt) in held.iter().zip(receipts) {
+            let result = receipt.map(|_| ());
+            self.observe("revoke", started, &result);
+            self.audit(
+                AuditEvent::for_capability("revoke", capability)
+                    .with_request_id(request_id)
+                    .with_outcome(&result),
+            ).await;
+            outcomes.push((capability.id, result));
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Wait until the server confirms a revocation has taken effect
+    ///
+    /// Polls the revocation status with the configured backoff and returns
+    /// `VaultError::Timeout` if it is still pending after `timeout`.
+    pub async fn await_revocation(&self, capability_id: uuid::Uuid, timeout: Duration) -> Result<RevocationReceipt> {
+        let transport = self.transport();
+        let poll = async {
+            let mut attempt = 0;
+            loop {
+                let receipt = self
+                    .with_retry(|| transport.revocation_status(capability_id))
+                    .await?;
+                if receipt.status == RevocationStatus::Completed {
+                    return Ok(receipt);
+                }
+
+                tokio::time::sleep(self.config().retry.backoff_delay(attempt)).await;
+                attempt += 1;
+            }
+        };
+
+        tokio::time::timeout(timeout, poll)
+            .await
+            .map_err(|_| VaultError::Timeout(timeout))?
+    }
+
+    /// Watch a cached capability for refreshes, expiry and revocation
+    ///
+    /// Polls the capability cache, and the server where the transport can
+    /// report revocations, every 5 seconds. `NearExpiry` is emitted once per
+    /// lifetime when less than a fifth of it remains. The stream ends after
+    /// `Expired` or `Revoked`; a capability revoked server-side is dropped
+    /// from the cache before `Revoked` is emitted. Nothing is emitted until
+    /// the capability is in the cache. `Revoked` is only emitted for
+    /// revocations made through this client or reported by the server: a
+    /// capability merely evicted from the cache is watched from its last
+    /// known state until it expires.
+    pub fn watch_capability(&self, capability_id: uuid::Uuid) -> impl futures::Stream<Item = CapabilityEvent> + Send {
+        self.watch_capability_every(capability_id, WATCH_POLL_INTERVAL)
+    }
+
+    fn watch_capability_every(
+        &self,
+        capability_id: uuid::Uuid,
+        interval: Duration,
+    ) -> impl futures::Stream<Item = CapabilityEvent> + Send {
+        let (tx, rx) = tokio::sync::mpsc::channel(8);
+        let client = self.clone();
+        let mut revocations = self.revocations.subscribe();
+
+        tokio::spawn(async move {
+            let mut last: Option<Capability> = None;
+            let mut warned = false;
+            loop {
+                let event = client.poll_capability(capability_id, &mut last, &mut warned, &mut revocations).await;
+                if let Some(event) = event {
+                    let done = matches!(event, CapabilityEvent::Expired | CapabilityEvent::Revoked);
+                    if tx.send(event).await.is_err() || done {
+                        break;
+                    }
+                    continue;
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = tx.closed() => break,
+                }
+            }
+        });
+
+        futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|event| (event, rx)) })
+    }
+
+    /// Next event for a watched capability, given the state last seen
+    async fn poll_capability(
+        &self,
+        capability_id: uuid::Uuid,
+        last: &mut Option<Capability>,
+        warned: &mut bool,
+        revocations: &mut tokio::sync::broadcast::Receiver<uuid::Uuid>,
+    ) -> Option<CapabilityEvent> {
+        let cached = self.capabilities.read().await.get(&capability_id).cloned();
+        let Some(capability) = cached else {
+            // Leaving the cache alone says nothing about the server
+            let previous = last.as_ref()?;
+            if previous.remaining_ttl().is_none() {
+                last.take();
+                return Some(CapabilityEvent::Expired);
+            }
+            if revoked_through(revocations, capability_id)
+                || matches!(self.transport().is_revoked(capability_id).await, Ok(Some(true)))
+            {
+                last.take();
+                return Some(CapabilityEvent::Revoked);
+            }
+            return None;
+        };
+
+        if let Some(previous) = last.replace(capability.clone()) {
+            if previous.expires_at != capability.expires_at {
+                *warned = false;
+                return Some(CapabilityEvent::Refreshed(capability));
+            }
+        }
+
+        let Some(remaining) = capability.remaining_ttl() else {
+            return Some(CapabilityEvent::Expired);
+        };
+
+        match self.transport().is_revoked(capability_id).await {
+            Ok(Some(true)) => {
+                self.capabilities.write().await.remove(&capability_id);
+                if let Some(responses) = &self.responses {
+                    responses.lock().unwrap_or_else(PoisonError::into_inner).remove_capability(capability_id);
+                }
+                return Some(CapabilityEvent::Revoked);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::debug!("Revocation check for capability {} failed: {}", capability_id, e),
+        }
+
+        let lifetime = (capability.expires_at - capability.issued_at).to_std().unwrap_or_default();
+        if !*warned && remaining < lifetime / 5 {
+            *warned = true;
+            return Some(CapabilityEvent::NearExpiry(remaining));
+        }
+
+        None
+    }
+
+    /// Keep cached capabilities alive by refreshing them before they expire
+    ///
+    /// Spawns a task that periodically scans the capability cache and calls
+    /// [`Client::refresh_capability`] with `new_ttl` for every capability
+    /// whose `remaining_ttl()` is below `threshold`. Revoked capabilities
+    /// leave the cache and are no longer refreshed; the task stops when the
+    /// client is closed or its last clone dropped. Calling this again
+    /// replaces the previous settings.
+    pub fn enable_auto_refresh(&self, threshold: Duration, new_ttl: Duration) -> Result<()> {
+        if new_ttl < CapabilityRequest::MIN_TTL || new_ttl > CapabilityRequest::MAX_TTL {
+            return Err(VaultError::Validation(format!(
+                "auto-refresh TTL must be between {:?} and {:?}",
+                CapabilityRequest::MIN_TTL,
+                CapabilityRequest::MAX_TTL
+            )));
+        }
+        if threshold.is_zero() || threshold >= new_ttl {
+            return Err(VaultError::Validation(
+                "auto-refresh threshold must be positive and shorter than the new TTL".to_string(),
+            ));
+        }
+
+        let client = self.downgrade();
+        let scan_interval = (threshold / 4).max(Duration::from_millis(10));
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(scan_interval).await;
+                let Some(client) = client.upgrade() else { break };
+                for capability_id in client.capabilities_due_for_refresh(threshold).await {
+                    if let Err(e) = client.refresh_capability(capability_id, new_ttl).await {
+                        tracing::warn!("Auto-refresh of capability {} failed: {}", capability_id, e);
+                    }
+                }
+            }
+        });
+
+        let mut auto_refresh = self.auto_refresh.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(previous) = auto_refresh.task.replace(task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    /// Stop the background refresh task, if running
+    pub fn disable_auto_refresh(&self) {
+        if let Some(task) = self.auto_refresh.lock().unwrap_or_else(PoisonError::into_inner).task.take() {
+            task.abort();
+        }
+    }
+
+    /// Opt a capability in to (default) or out of auto-refresh
+    pub fn set_auto_refresh(&self, capability_id: uuid::Uuid, enabled: bool) {
+        let mut auto_refresh = self.auto_refresh.lock().unwrap_or_else(PoisonError::into_inner);
+        if enabled {
+            auto_refresh.excluded.remove(&capability_id);
+        } else {
+            auto_refresh.excluded.insert(capability_id);
+        }
+    }
+
+    /// Cached, still-valid capabilities expiring within `threshold`
+    async fn capabilities_due_for_refresh(&self, threshold: Duration) -> Vec<uuid::Uuid> {
+        let excluded = self.auto_refresh.lock().unwrap_or_else(PoisonError::into_inner).excluded.clone();
+        let caps = self.capabilities.read().await;
+        caps.values()
+            .filter(|cap| !excluded.contains(&cap.id))
+            .filter(|cap| cap.remaining_ttl().map_or(false, |remaining| remaining < threshold))
+            .map(|cap| cap.id)
+            .collect()
+    }
+
+    /// List active capabilities held by the current identity
+    ///
+    /// Only capabilities requested under the current identity's token are
+    /// listed, so switching identities with [`Client::set_identity`] never
+    /// exposes another identity's capabilities. A workload identity keeps
+    /// its capabilities across token rotations.
+    pub async fn list_capabilities(&self) -> Result<Vec<Capability>> {
+        let mut active_caps = self.held_capabilities().await;
+        active_caps.retain(|cap| cap.is_valid());
+        Ok(active_caps)
+    }
+
+    /// Cached capabilities requested under the current identity
+    ///
+    /// Empty when there is no identity.
+    async fn held_capabilities(&self) -> Vec<Capability> {
+        let Some(identity) = self.get_identity().await else {
+            return Vec::new();
+        };
+        let caps = self.capabilities.read().await;
+        caps.owned_by(&identity.fingerprint()).cloned().collect()
+    }
+
+    /// List active capabilities requested under `identity`
+    ///
+    /// Capabilities requested for other identities are never returned.
+    pub async fn list_capabilities_as(&self, identity: &Identity) -> Result<Vec<Capability>> {
+        let owner = identity.fingerprint();
+        let caps = self.capabilities.read().await;
+        Ok(caps.owned_by(&owner)
+            .filter(|cap| cap.is_valid())
+            .cloned()
+            .collect())
+    }
+
+    /// Serialize the current identity's valid cached capabilities into a bundle
+    ///
+    /// The bundle is signed, and with `options.encrypt` encrypted, under the
+    /// 32-byte `key`, so a child process holding the same key can load it with
+    /// [`Client::import_bundle`] without talking to Vault.
+    pub async fn export_bundle(&self, key: &[u8], options: BundleOptions) -> Result<Vec<u8>> {
+        let identity = match options.include_identity {
+            true => self.get_identity().await.map(|identity| identity.token().to_string()),
+            false => None,
+        };
+        let contents = BundleContents {
+            capabilities: self.list_capabilities().await?,
+            identity,
+        };
+        seal_bundle(&contents, key, options.encrypt)
+    }
+
+    /// Load capabilities from a bundle made by [`Client::export_bundle`]
+    ///
+    /// Fails if the bundle was not signed under `key`. Every capability's
+    /// own signature is checked against the configured `verification_key`,
+    /// and one failure rejects the whole bundle. Without a
+    /// `verification_key`, a bundle carrying capabilities is refused with
+    /// `ConfigError::MissingField` unless `options.trust_unsigned` is set.
+    /// Capabilities that have expired or are otherwise no longer valid are
+    /// dropped; a bundled identity replaces the client's. Returns how many
+    /// were imported.
+    pub async fn import_bundle(&self, bundle: &[u8], key: &[u8], options: ImportOptions) -> Result<usize> {
+        let BundleContents { capabilities, identity } = open_bundle(bundle, key)?;
+        match self.config().verification_key_bytes()? {
+            Some(public_key) => {
+                for capability in &capabilities {
+                    capability.validate_signature(&public_key)?;
+                }
+            }
+            None if !capabilities.is_empty() && !options.trust_unsigned => {
+                return Err(crate::error::ConfigError::MissingField("verification_key".to_string()).into());
+            }
+            None => {}
+        }
+
+        // Imported capabilities belong to the identity that will hold them
+        let identity = identity.map(Identity::new);
+        let owner = match &identity {
+            Some(identity) => Some(identity.fingerprint()),
+            None => self.get_identity().await.map(|identity| identity.fingerprint()),
+        };
+
+        let mut imported = 0;
+        {
+            let mut caps = self.capabilities.write().await;
+            for capability in capabilities {
+                let capability = capability.migrate()?;
+                if !capability.is_valid() {
+                    tracing::debug!("Dropping invalid capability {} from bundle", capability.id);
+                    continue;
+                }
+                let id = capability.id;
+                caps.insert(capability);
+                if let Some(owner) = &owner {
+                    caps.set_owner(id, owner.clone());
+                }
+                imported += 1;
+            }
+        }
+        if let Some(identity) = identity {
+            self.set_identity(identity).await?;
+        }
+        Ok(imported)
+    }
+
+    /// Refresh a capability (extend TTL)
+    #[tracing::instrument(
+        name = "refresh",
+        skip_all,
+        fields(
+            capability_id = %capability_id,
+            ttl = ?new_ttl,
+            outcome = tracing::field::Empty,
+            error_code = tracing::field::Empty,
+        ),
+    )]
+    pub async fn refresh_capability(
+        &self,
+        capability_id: uuid::Uuid,
+        new_ttl: Duration,
+    ) -> Result<Capability> {
+        let _in_flight = self.enter()?;
+        self.check_refresh_ttl(capability_id, new_ttl).await?;
+        let identity = self.usable_identity().await?;
+
+        // Request refresh from Vault
+        let started = std::time::Instant::now();
+        let transport = self.transport();
+        let request_id = uuid::Uuid::new_v4();
+        let result = crate::transport::with_request_id(
+            request_id,
+            self.with_retry(|| transport.refresh_capability(&identity, capability_id, new_ttl)),
+        ).await;
+        let result = self.contextualize(result, || ErrorContext { capability_id: Some(capability_id), ..ErrorContext::default() });
+        self.observe("refresh", started, &result);
+
+        let event = match &result {
+            Ok(capability) => AuditEvent::for_capability("refresh", capability),
+            Err(_) => {
+                let mut event = AuditEvent::new("refresh");
+                event.capability_id = Some(capability_id);
+                event
+            }
+        };
+        self.audit(event.with_request_id(request_id).with_outcome(&result)).await;
+        let refreshed_cap = result?;
+
+        // Update cache
+        {
+            let mut caps = self.capabilities.write().await;
+            caps.insert(refreshed_cap.clone());
+        }
+
+        Ok(refreshed_cap)
+    }
+
+    /// Apply the request TTL bounds and `max_capability_lifetime` to a refresh
+    ///
+    /// The lifetime cap needs the capability's issue time, so it is only
+    /// enforced for capabilities held in the cache.
+    async fn check_refresh_ttl(&self, capability_id: uuid::Uuid, new_ttl: Duration) -> Result<()> {
+        if new_ttl < CapabilityRequest::MIN_TTL || new_ttl > CapabilityRequest::MAX_TTL {
+            return Err(crate::error::CapabilityError::InvalidFormat(format!(
+                "refresh TTL {:?} must be between {:?} and {:?}",
+                new_ttl,
+                CapabilityRequest::MIN_TTL,
+                CapabilityRequest::MAX_TTL
+            )).into());
+        }
+
+        let Some(max_lifetime) = self.config().max_capability_lifetime else {
+            return Ok(());
+        };
+        let Some(issued_at) = self.capabilities.read().await.get(&capability_id).map(|c| c.issued_at) else {
+            return Ok(());
+        };
+        let elapsed = (chrono::Utc::now() - issued_at).to_std().unwrap_or(Duration::ZERO);
+        if elapsed + new_ttl > max_lifetime {
+            return Err(crate::error::CapabilityError::InvalidFormat(format!(
+                "refresh would extend capability {} to a lifetime of {:?}, beyond the {:?} maximum",
+                capability_id,
+                elapsed + new_ttl,
+                max_lifetime
+            )).into());
+        }
+        Ok(())
+    }
+
+    /// Get Vault status
+    pub async fn status(&self) -> Result<VaultStatus> {
+        let transport = self.transport();
+        let status = self.with_retry(|| transport.status()).await?;
+        self.remember_status(&status);
+        Ok(status)
+    }
+
+    /// Wait until the Vault is initialized, unsealed and active
+    ///
+    /// Polls [`Client::status`] with the configured retry backoff, treating
+    /// failed polls (e.g. 503s while sealed) as not ready yet. Returns the
+    /// first ready status, or `VaultError::Timeout` once `timeout` elapses.
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<VaultStatus> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut backoff = self.config().retry.backoff();
+
+        loop {
+            let transport = self.transport();
+            match tokio::time::timeout_at(deadline, transport.status()).await {
+                Err(_) => return Err(VaultError::Timeout(timeout)),
+                Ok(Ok(status)) => {
+                    self.remember_status(&status);
+                    if status.initialized && !status.sealed && !status.standby {
+                        return Ok(status);
+                    }
+                    tracing::debug!(
+                        "Vault not ready (initialized: {}, sealed: {}, standby: {})",
+                        status.initialized,
+                        status.sealed,
+                        status.standby
+                    );
+                }
+                Ok(Err(e)) => tracing::debug!("Vault status unavailable: {}", e),
+            }
+
+            let delay = backoff.next_delay();
+            if tokio::time::Instant::now() + delay >= deadline {
+                tokio::time::sleep_until(deadline).await;
+                return Err(VaultError::Timeout(timeout));
+            }
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Whether the server supports an optional `feature`
+    ///
+    /// Features are taken from the most recent [`Client::status`], fetched
+    /// on first use. If the status cannot be fetched, the feature is assumed
+    /// supported and the server left to refuse it.
+    pub async fn supports(&self, feature: Feature) -> bool {
+        let known = self.last_status.lock().unwrap_or_else(PoisonError::into_inner).as_ref().map(|(_, status)| status.supports(feature));
+        match known {
+            Some(supported) => supported,
+            None => self.status().await.map_or(true, |status| status.supports(feature)),
+        }
+    }
+
+    /// Keep `status` for the sealed short-circuit
+    fn remember_status(&self, status: &VaultStatus) {
+        *self.last_status.lock().unwrap_or_else(PoisonError::into_inner) = Some((std::time::Instant::now(), status.clone()));
+    }
+
+    /// Fail fast when a recently fetched status reports the Vault sealed
+    fn check_not_sealed(&self) -> Result<()> {
+        let last_status = self.last_status.lock().unwrap_or_else(PoisonError::into_inner);
+        match &*last_status {
+            Some((fetched, status)) if status.sealed && fetched.elapsed() < SEALED_STATUS_MAX_AGE => {
+                Err(VaultError::Server("vault sealed".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Health check
+    pub async fn health_check(&self) -> Result<HealthStatus> {
+        let transport = self.transport();
+        self.with_retry(|| transport.health_check()).await
+    }
+
+    /// Liveness probe returning the round-trip latency
+    ///
+    /// Cheap enough to call every second: a single attempt without retries
+    /// that parses no body. A sealed or standby node fails with
+    /// `VaultError::Server`; use [`Client::health_check`] for component details.
+    pub async fn ping(&self) -> Result<Duration> {
+        self.transport().ping().await
+    }
+
+    /// Health check for readiness probes, bounded by `timeout`
+    ///
+    /// Makes a single attempt without retries and adds a `transport`
+    /// component with the measured round-trip time. The overall status is
+    /// `Unhealthy` if the server or any component reports unhealthy,
+    /// `Degraded` if any component is degraded, and `Healthy` otherwise.
+    /// Errors and timeouts yield a synthesized `Unhealthy` status instead of
+    /// an error, so probes always get an answer within `timeout`.
+    pub async fn health_check_with_timeout(&self, timeout: Duration) -> HealthStatus {
+        let started = std::time::Instant::now();
+        let result = tokio::time::timeout(timeout, self.transport().health_check()).await;
+        let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+        let mut health = match result {
+            Ok(Ok(health)) => health,
+            Ok(Err(e)) => return unhealthy_status(e.to_string(), elapsed_ms),
+            Err(_) => return unhealthy_status(format!("no response within {:?}", timeout), elapsed_ms),
+        };
+
+        health.details.push(HealthDetail {
+            component: "transport".to_string(),
+            status: HealthStatusType::Healthy,
+            message: None,
+            response_time_ms: Some(elapsed_ms),
+        });
+
+        let worst = |status: HealthStatusType| health.details.iter().any(|detail| detail.status == status);
+        health.status = if !health.healthy || worst(HealthStatusType::Unhealthy) {
+            HealthStatusType::Unhealthy
+        } else if worst(HealthStatusType::Degraded) {
+            HealthStatusType::Degraded
+        } else {
+            HealthStatusType::Healthy
+        };
+        health.healthy = health.status != HealthStatusType::Unhealthy;
+        health
+    }
+
+    /// Wait for in-flight operations to finish, then close the client
+    ///
+    /// Operations started on any clone of this client count, so their
+    /// transport calls are not cut off mid-flight. Operations started while
+    /// draining and closing fail with `VaultError::Internal`. If in-flight
+    /// ones are still running after `timeout`, fails with
+    /// `VaultError::Timeout` and leaves the client open; call
+    /// [`Client::close`] to tear it down regardless.
+    pub async fn close_gracefully(&self, timeout: Duration) -> Result<()> {
+        self.closing.store(true, std::sync::atomic::Ordering::SeqCst);
+        let mut in_flight = self.in_flight.subscribe();
+        let drained = tokio::time::timeout(timeout, in_flight.wait_for(|count| *count == 0)).await;
+        let result = match drained {
+            Ok(Ok(_)) => self.close().await,
+            _ => Err(VaultError::Timeout(timeout)),
+        };
+        self.closing.store(false, std::sync::atomic::Ordering::SeqCst);
+        result
+    }
+
+    /// Close the client and cleanup resources
+    ///
+    /// Revokes every cached capability, including those minted for other
+    /// identities with [`Client::request_capability_as`].
+    pub async fn close(&self) -> Result<()> {
+        self.disable_auto_refresh();
+
+        // Don't leave live capabilities behind, whoever they were issued to
+        let cached: Vec<Capability> = self.capabilities.read().await.values().cloned().collect();
+        match self.revoke_cached(cached).await {
+            Ok(outcomes) => {
+                for (id, result) in outcomes {
+                    if let Err(e) = result {
+                        tracing::warn!("Failed to revoke capability {} on close: {}", id, e);
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("Failed to revoke capabilities on close: {}", e),
+        }
+
+        // Clear identity and capabilities cache together (identity first)
+        {
+            let mut id = self.identity.write().await;
+            let mut caps = self.capabilities_after_identity(&id).await;
+            caps.clear();
+            *id = None;
+        }
+        if let Some(responses) = &self.responses {
+            responses.lock().unwrap_or_else(PoisonError::into_inner).clear();
+        }
+
+        // Close transport
+        self.transport().close().await
+    }
+}
+
+/// Capability that is revoked when it goes out of scope
+///
+/// Dereferences to [`Capability`], so it can be passed to any access method.
+/// `Drop` cannot await, so dropping the guard spawns the revocation on the
+/// current tokio runtime and returns immediately: the revocation is best
+/// effort and is skipped entirely when dropped outside a runtime. Call
+/// [`CapabilityGuard::revoke`] for deterministic cleanup.
+#[derive(Debug)]
+pub struct CapabilityGuard {
+    /// Guarded capability
+    capability: Capability,
+
+    /// Client used to revoke the capability
+    client: Client,
+
+    /// Whether the capability was already revoked explicitly
+    revoked: bool,
+}
+
+impl CapabilityGuard {
+    /// Revoke the capability now and wait for the result
+    pub async fn revoke(mut self) -> Result<()> {
+        self.revoked = true;
+        self.client.revoke_capability(self.capability.id).await
+    }
+}
+
+impl std::ops::Deref for CapabilityGuard {
+    type Target = Capability;
+
+    fn deref(&self) -> &Capability {
+        &self.capability
+    }
+}
+
+impl Drop for CapabilityGuard {
+    fn drop(&mut self) {
+        if self.revoked {
+            return;
+        }
+
+        let capability_id = self.capability.id;
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let client = self.client.clone();
+                handle.spawn(async move {
+                    if let Err(e) = client.revoke_capability(capability_id).await {
+                        tracing::warn!("Failed to revoke capability {} on drop: {}", capability_id, e);
+                    }
+                });
+            }
+            Err(_) => {
+                tracing::warn!("Capability {} dropped outside a tokio runtime; not revoked", capability_id);
+            }
+        }
+    }
+}
+
+/// Server acknowledgement of a revocation request
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RevocationReceipt {
+    /// Revoked capability
+    pub id: uuid::Uuid,
+    
+    /// Revocation state
+    pub status: RevocationStatus,
+    
+    /// When the server expects the revocation to take effect
+    pub estimated_effective_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl RevocationReceipt {
+    /// Receipt for a revocation that has already taken effect
+    pub fn completed(id: uuid::Uuid) -> Self {
+        Self {
+            id,
+            status: RevocationStatus::Completed,
+            estimated_effective_at: None,
+        }
+    }
+}
+
+/// Accessed secret with the server's lease metadata, see [`Client::access_detailed`]
+///
+/// Servers wrap the secret as `{"data": ..., "lease_duration": ..., ...}`.
+/// A body is only taken as this envelope when it has a `data` key and a
+/// `lease_duration` or `request_id` key; any other body, including a secret
+/// that merely has a `data` field, is taken as the secret itself, with no
+/// metadata.
+#[derive(Debug, Clone)]
+pub struct AccessResponse<T> {
+    /// The secret
+    pub data: T,
+    
+    /// Remaining server-side lease in seconds
+    pub lease_duration: Option<u64>,
+    
+    /// Whether the server lets the lease be renewed
+    pub renewable: bool,
+    
+    /// Version of the secret
+    pub version: Option<u64>,
+    
+    /// Server-assigned id of the access request
+    pub request_id: Option<String>,
+}
+
+impl<T> AccessResponse<T> {
+    /// Remaining server-side lease
+    pub fn lease(&self) -> Option<Duration> {
+        self.lease_duration.map(Duration::from_secs)
+    }
+}
+
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for AccessResponse<T> {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // The lease or the request id marks a body as an envelope
+        #[derive(serde::Deserialize)]
+        struct LeasedEnvelope<T> {
+            data: T,
+            lease_duration: u64,
+            #[serde(default)]
+            renewable: bool,
+            #[serde(default)]
+            version: Option<u64>,
+            #[serde(default)]
+            request_id: Option<String>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct TrackedEnvelope<T> {
+            data: T,
+            request_id: String,
+            #[serde(default)]
+            renewable: bool,
+            #[serde(default)]
+            version: Option<u64>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Body<T> {
+            Leased(LeasedEnvelope<T>),
+            Tracked(TrackedEnvelope<T>),
+            Bare(T),
+        }
+
+        Ok(match Body::deserialize(deserializer)? {
+            Body::Leased(envelope) => Self {
+                data: envelope.data,
+                lease_duration: Some(envelope.lease_duration),
+                renewable: envelope.renewable,
+                version: envelope.version,
+                request_id: envelope.request_id,
+            },
+            Body::Tracked(envelope) => Self {
+                data: envelope.data,
+                lease_duration: None,
+                renewable: envelope.renewable,
+                version: envelope.version,
+                request_id: Some(envelope.request_id),
+            },
+            Body::Bare(data) => Self {
+                data,
+                lease_duration: None,
+                renewable: false,
+                version: None,
+                request_id: None,
+            },
+        })
+    }
+}
+
+/// Server verdict on a capability request, see [`Client::check_capability`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PolicyDecision {
+    /// Whether the request would be granted
+    pub allowed: bool,
+    
+    /// Why the request would be granted or denied
+    #[serde(default)]
+    pub reason: Option<String>,
+    
+    /// TTL the capability would be granted for, in seconds
+    #[serde(default)]
+    pub granted_ttl_secs: Option<u64>,
+}
+
+impl PolicyDecision {
+    /// Decision granting a capability for `ttl`
+    pub fn allow(ttl: Duration) -> Self {
+        Self {
+            allowed: true,
+            reason: None,
+            granted_ttl_secs: Some(ttl.as_secs()),
+        }
+    }
+
+    /// Decision refusing a capability for `reason`
+    pub fn deny(reason: impl Into<String>) -> Self {
+        Self {
+            allowed: false,
+            reason: Some(reason.into()),
+            granted_ttl_secs: None,
+        }
+    }
+
+    /// Effective TTL the capability would be granted for
+    pub fn granted_ttl(&self) -> Option<Duration> {
+        self.granted_ttl_secs.map(Duration::from_secs)
+    }
+}
+
+/// Change to a watched capability, see [`Client::watch_capability`]
+#[derive(Debug, Clone)]
+pub enum CapabilityEvent {
+    /// The capability was refreshed and now carries a later expiry
+    Refreshed(Capability),
+    /// Less than a fifth of the capability's lifetime remains
+    NearExpiry(Duration),
+    /// The capability has expired
+    Expired,
+    /// The capability was revoked, by this client or server-side
+    Revoked,
+}
+
+/// Why a capability is being revoked
+///
+/// Sent to the server and recorded in the audit trail, in its
+/// `Display` form (e.g. `compromised`, or the text of `Other`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevokeReason {
+    /// No reason given
+    Unspecified,
+    /// Replaced by a freshly issued capability
+    Rotated,
+    /// Suspected or confirmed leaked
+    Compromised,
+    /// The workload holding it is shutting down
+    WorkloadShutdown,
+    /// Free-text reason
+    Other(String),
+}
+
+impl std::fmt::Display for RevokeReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RevokeReason::Unspecified => write!(f, "unspecified"),
+            RevokeReason::Rotated => write!(f, "rotated"),
+            RevokeReason::Compromised => write!(f, "compromised"),
+            RevokeReason::WorkloadShutdown => write!(f, "workload_shutdown"),
+            RevokeReason::Other(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Revocation states
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RevocationStatus {
+    /// Revocation is queued server-side
+    Pending,
+    /// Revocation has taken effect
+    Completed,
+}
+
+/// Client configuration hints published by the server at `/v1/client-config`
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ServerHints {
+    /// Recommended capability TTL per domain
+    #[serde(default)]
+    pub recommended_ttls: Vec<TtlHint>,
+    
+    /// Domains this client may request
+    #[serde(default)]
+    pub allowed_domains: Vec<Domain>,
+    
+    /// Capability request rate limits per domain
+    #[serde(default)]
+    pub rate_limits: Vec<RateLimitHint>,
+}
+
+/// Recommended TTL for a domain
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TtlHint {
+    /// Domain the hint applies to
+    pub domain: Domain,
+    
+    /// Recommended TTL in seconds
+    pub ttl_secs: u64,
+}
+
+/// Capability request rate limit for a domain
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RateLimitHint {
+    /// Domain the limit applies to
+    pub domain: Domain,
+    
+    /// Requests allowed per window
+    pub max_requests: u32,
+    
+    /// Window length in seconds
+    pub window_secs: u64,
+}
+
+/// Vault status information
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct VaultStatus {
+    /// Vault version
+    pub version: String,
+    
+    /// Server time
+    pub server_time: chrono::DateTime<chrono::Utc>,
+    
+    /// Initialization status
     pub initialized: bool,
     
     /// Sealed status
@@ -244,104 +2817,2133 @@ pub struct VaultStatus {
     
     /// Total storage
     pub total_storage: Option<u64>,
+    
+    /// Address of the active node, when reported by an HA cluster member
+    #[serde(default)]
+    pub leader_address: Option<String>,
+
+    /// Client API version implemented by the server, if reported
+    #[serde(default)]
+    pub api_version: Option<String>,
+
+    /// Optional features the server supports, if it advertises them
+    ///
+    /// Servers predating feature advertisement leave this unset, and are
+    /// assumed to support everything.
+    #[serde(default)]
+    pub features: Option<Vec<String>>,
+}
+
+impl VaultStatus {
+    /// Whether the server supports `feature`
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.features.as_ref().map_or(true, |features| features.iter().any(|f| f == feature.name()))
+    }
+}
+
+/// Optional server features negotiated from [`VaultStatus::features`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// Several capability requests or revocations in one round trip
+    Batch,
+    /// Policy dry-runs via [`Client::check_capability`]
+    PolicyCheck,
+    /// The gRPC API
+    Grpc,
+}
+
+impl Feature {
+    /// Name the server advertises the feature under
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feature::Batch => "batch",
+            Feature::PolicyCheck => "check",
+            Feature::Grpc => "grpc",
+        }
+    }
+}
+
+/// Health check status
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HealthStatus {
+    /// Overall health status
+    pub healthy: bool,
+    
+    /// Aggregated status (`Unknown` unless computed or sent by the server)
+    #[serde(default)]
+    pub status: HealthStatusType,
+    
+    /// Detailed status information
+    pub details: Vec<HealthDetail>,
+    
+    /// Timestamp of check
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Individual health detail
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HealthDetail {
+    /// Component name
+    pub component: String,
+    
+    /// Component status
+    pub status: HealthStatusType,
+    
+    /// Status message
+    pub message: Option<String>,
+    
+    /// Response time in milliseconds
+    pub response_time_ms: Option<u64>,
+}
+
+impl HealthStatus {
+    /// Apply client-side thresholds, downgrading the status if any component fails
+    ///
+    /// A component fails if it reports `Unhealthy` or responded slower than
+    /// its threshold. Any failure sets `healthy` to false and `status` to
+    /// `Unhealthy`; the failures are returned, empty if every component passed.
+    pub fn evaluate(&mut self, thresholds: &HealthThresholds) -> Vec<HealthFailure> {
+        let mut failures = Vec::new();
+        for detail in &self.details {
+            if detail.status == HealthStatusType::Unhealthy {
+                failures.push(HealthFailure {
+                    component: detail.component.clone(),
+                    reason: detail.message.clone().unwrap_or_else(|| "reported unhealthy".to_string()),
+                });
+                continue;
+            }
+
+            let limit = thresholds
+                .component_max_response_time
+                .get(&detail.component)
+                .or(thresholds.max_response_time.as_ref());
+            if let (Some(limit), Some(elapsed_ms)) = (limit, detail.response_time_ms) {
+                if u128::from(elapsed_ms) > limit.as_millis() {
+                    failures.push(HealthFailure {
+                        component: detail.component.clone(),
+                        reason: format!("responded in {}ms, above the {:?} threshold", elapsed_ms, limit),
+                    });
+                }
+            }
+        }
+
+        if !failures.is_empty() {
+            self.healthy = false;
+            self.status = HealthStatusType::Unhealthy;
+        }
+        failures
+    }
+}
+
+/// Client-side limits for [`HealthStatus::evaluate`]
+#[derive(Debug, Clone, Default)]
+pub struct HealthThresholds {
+    /// Slowest acceptable response time for any component (unlimited when unset)
+    pub max_response_time: Option<Duration>,
+    
+    /// Per-component response time limits, overriding `max_response_time`
+    pub component_max_response_time: std::collections::HashMap<String, Duration>,
+}
+
+/// Component that failed [`HealthStatus::evaluate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthFailure {
+    /// Component name
+    pub component: String,
+    
+    /// Why the component failed
+    pub reason: String,
+}
+
+/// Health status types
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatusType {
+    /// Component is healthy
+    Healthy,
+    /// Component is degraded
+    Degraded,
+    /// Component is unhealthy
+    Unhealthy,
+    /// Component status unknown
+    #[default]
+    Unknown,
 }
 
-/// Health check status
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct HealthStatus {
-    /// Overall health status
-    pub healthy: bool,
-    
-    /// Detailed status information
-    pub details: Vec<HealthDetail>,
-    
-    /// Timestamp of check
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AuthConfig, AuthMethod, TransportType};
+    use crate::identity::identity::test_jwt;
+    use std::collections::HashSet;
+
+    #[tokio::test]
+    async fn test_client_creation() {
+        let config = Config {
+            endpoint: "http://localhost:8200".to_string(),
+            namespace: None,
+            transport: TransportType::Http,
+            auth: AuthConfig {
+                method: AuthMethod::None,
+                token_file: None,
+                token: None,
+                cert_file: None,
+                key_file: None,
+                ca_file: None,
+                hmac_key_file: None,
+                token_refresh_skew: Duration::from_secs(60),
+                ttl_policy: crate::config::TtlPolicy::Clamp,
+            },
+            timeouts: crate::config::TimeoutConfig::default(),
+            retry: crate::config::RetryConfig::default(),
+            tls: None,
+            logging: crate::config::LoggingConfig::default(),
+            cache: None,
+            require_access_reason_for: HashSet::new(),
+            require_justification_for: HashSet::new(),
+            domain_ttls: std::collections::HashMap::new(),
+            server_hints: crate::config::ServerHintsConfig::default(),
+            proxy: None,
+            max_capability_lifetime: None,
+            verification_key: None,
+            min_remaining_ttl: Duration::ZERO,
+            compression: None,
+            follow_redirects_to_active: true,
+            cluster_addresses: Vec::new(),
+            rerequest_on_revoked: false,
+        };
+
+        // This will fail in tests without a real Vault, but we can test the structure
+        let result = Client::new(config).await;
+        assert!(result.is_err() || result.is_ok()); // Either way, the structure is valid
+    }
+
+    #[tokio::test]
+    async fn test_identity_management() {
+        // Create a mock client for testing
+        let client = Client::builder()
+            .transport(Arc::new(crate::transport::MockTransport::new()))
+            .audit_sink(Arc::new(NullSink))
+            .build()
+            .await
+            .unwrap();
+
+        // Initially no identity
+        assert!(client.get_identity().await.is_none());
+
+        // Set identity
+        let identity = Identity::new("test-token".to_string());
+        client.set_identity(identity.clone()).await.unwrap();
+
+        // Get identity
+        let retrieved = client.get_identity().await;
+        assert!(retrieved.is_some());
+        assert_eq!(retrieved.unwrap().token(), identity.token());
+    }
+
+    /// Audit sink collecting events in memory
+    #[derive(Default)]
+    struct CollectingSink {
+        events: std::sync::Mutex<Vec<AuditEvent>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AuditSink for CollectingSink {
+        async fn record(&self, event: AuditEvent) {
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    async fn mock_client(config: Config, audit_sink: Arc<dyn AuditSink>) -> Client {
+        mock_client_with_transport(config, Arc::new(crate::transport::MockTransport::new()), audit_sink).await
+    }
+
+    async fn mock_client_with_transport(
+        config: Config,
+        transport: Arc<dyn Transport + Send + Sync>,
+        audit_sink: Arc<dyn AuditSink>,
+    ) -> Client {
+        Client::builder()
+            .config(config)
+            .transport(transport)
+            .audit_sink(audit_sink)
+            .build()
+            .await
+            .unwrap()
+    }
+
+    fn test_capability(domain: Domain) -> Capability {
+        Capability::new(
+            domain,
+            Action::Read,
+            "users".to_string(),
+            crate::capability::CapabilityContext {
+                environments: None,
+                services: None,
+                namespaces: None,
+                ip_constraints: None,
+                time_window: None,
+                usage_limits: None,
+            },
+            Duration::from_secs(300),
+            "vault".to_string(),
+            "test".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_access_reason_required_for_enforced_domain() {
+        let mut config = Config::default();
+        config.require_access_reason_for.insert(Domain::Ssh);
+        let client = mock_client(config, Arc::new(NullSink)).await;
+
+        let capability = test_capability(Domain::Ssh);
+
+        let result: Result<serde_json::Value> = client.access_with_capability(&capability).await;
+        assert!(matches!(result, Err(VaultError::Validation(_))));
+
+        let result: Result<serde_json::Value> = client.access_with_reason(&capability, "   ").await;
+        assert!(matches!(result, Err(VaultError::Validation(_))));
+
+        // Domains outside the enforced set don't need a reason
+        let other = test_capability(Domain::Database);
+        let result: Result<serde_json::Value> = client.access_with_capability(&other).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_justification_required_for_configured_pairs() {
+        let mut config = Config::default();
+        config.require_justification_for.insert((Domain::Ssh, Action::Admin));
+        let client = mock_client(config, Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+
+        let request = CapabilityRequest::builder()
+            .domain(Domain::Ssh)
+            .action(Action::Admin)
+            .target("bastion")
+            .ttl(Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let err = client.request(request.clone()).await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::Capability(crate::error::CapabilityError::InvalidFormat(_))), "{:?}", err);
+        assert!(err.to_string().contains("justification required for ssh/admin"), "{}", err);
+
+        client.request(request.with_justification("rotate host keys".to_string())).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_domain_max_ttl_overrides_global_bounds() {
+        let mut config = Config::default();
+        config.domain_ttls.insert(Domain::Ssh, crate::config::DomainTtlPolicy {
+            default_ttl: None,
+            max_ttl: Duration::from_secs(300),
+            on_exceed: crate::config::TtlPolicy::Reject,
+        });
+        let client = mock_client(config, Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+
+        // Within the global 24h bound, but over the ssh maximum
+        let context = Context::builder().build().unwrap();
+        let err = client
+            .request_capability(Domain::Ssh, Action::Read, "bastion", &context, Duration::from_secs(3600))
+            .await
+            .unwrap_err();
+        assert!(matches!(err.root(), VaultError::Capability(crate::error::CapabilityError::InvalidFormat(_))), "{:?}", err);
+        assert!(err.to_string().contains("maximum for domain ssh"), "{}", err);
+
+        client
+            .request_capability(Domain::Ssh, Action::Read, "bastion", &context, Duration::from_secs(300))
+            .await
+            .unwrap();
+        // Unlisted domains keep the global bounds
+        client
+            .request_capability(Domain::Tls, Action::Read, "api-cert", &context, Duration::from_secs(3600))
+            .await
+            .unwrap();
+        assert_eq!(client.default_ttl(&Domain::Ssh).await, Duration::from_secs(300).min(client.config().timeouts.capability));
+    }
+
+    #[tokio::test]
+    async fn test_domain_max_ttl_clamps() {
+        let mut config = Config::default();
+        config.domain_ttls.insert(Domain::Ssh, crate::config::DomainTtlPolicy {
+            default_ttl: Some(Duration::from_secs(120)),
+            max_ttl: Duration::from_secs(300),
+            on_exceed: crate::config::TtlPolicy::Clamp,
+        });
+        let client = mock_client(config, Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+
+        let capability = client
+            .request_capability(Domain::Ssh, Action::Read, "bastion", &Context::builder().build().unwrap(), Duration::from_secs(3600))
+            .await
+            .unwrap();
+        assert_eq!((capability.expires_at - capability.issued_at).num_seconds(), 300);
+        assert_eq!(client.default_ttl(&Domain::Ssh).await, Duration::from_secs(120));
+    }
+
+    #[tokio::test]
+    async fn test_access_rejects_capability_below_min_remaining_ttl() {
+        let mut config = Config::default();
+        config.min_remaining_ttl = Duration::from_secs(1);
+        let client = mock_client(config, Arc::new(NullSink)).await;
+
+        let mut capability = test_capability(Domain::Database);
+        capability.expires_at = chrono::Utc::now() + chrono::Duration::milliseconds(500);
+        let result: Result<serde_json::Value> = client.access_with_capability(&capability).await;
+        assert!(matches!(
+            result,
+            Err(VaultError::Capability(crate::error::CapabilityError::Expired(at))) if at == capability.expires_at
+        ));
+
+        let healthy = test_capability(Domain::Database);
+        let result: Result<serde_json::Value> = client.access_with_capability(&healthy).await;
+        assert!(result.is_ok());
+
+        // The default guard only rejects what has already expired
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let result: Result<serde_json::Value> = client.access_with_capability(&capability).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_access_reason_recorded_in_audit() {
+        let mut config = Config::default();
+        config.require_access_reason_for.insert(Domain::Ssh);
+        let sink = Arc::new(CollectingSink::default());
+        let client = mock_client(config, sink.clone()).await;
+
+        let capability = test_capability(Domain::Ssh);
+        let _: serde_json::Value = client
+            .access_with_reason(&capability, "incident INC-42 investigation")
+            .await
+            .unwrap();
+
+        let events = sink.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].operation, "access");
+        assert_eq!(events[0].capability_id, Some(capability.id));
+        assert_eq!(events[0].reason.as_deref(), Some("incident INC-42 investigation"));
+        assert!(events[0].request_id.is_some());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_identity_and_capability_access_no_deadlock() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let capability = test_capability(Domain::Database);
+        {
+            let mut caps = client.capabilities.write().await;
+            caps.insert(capability.clone());
+        }
+
+        let mut handles = Vec::new();
+        for i in 0..64 {
+            let client = client.clone();
+            let capability = capability.clone();
+            handles.push(tokio::spawn(async move {
+                for j in 0..50 {
+                    match (i + j) % 4 {
+                        0 => {
+                            let identity = Identity::new(format!("token-{}-{}", i, j));
+                            client.set_identity(identity).await.unwrap();
+                        }
+                        1 => {
+                            let _: Result<serde_json::Value> =
+                                client.access_with_capability(&capability).await;
+                        }
+                        2 => {
+                            let _ = client.list_capabilities().await.unwrap();
+                            let _ = client.get_identity().await;
+                        }
+                        _ => {
+                            // Takes both locks at once
+                            let id = client.identity.write().await;
+                            let caps = client.capabilities_after_identity(&id).await;
+                            drop(caps);
+                            drop(id);
+                        }
+                    }
+                }
+            }));
+        }
+
+        let all = futures::future::join_all(handles);
+        let results = tokio::time::timeout(Duration::from_secs(30), all)
+            .await
+            .expect("client operations deadlocked");
+        for result in results {
+            result.unwrap();
+        }
+    }
+
+    fn fast_retry_config(max_retries: u32) -> Config {
+        let mut config = Config::default();
+        config.retry = crate::config::RetryConfig {
+            max_retries,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            backoff_multiplier: 2.0,
+            jitter: crate::config::JitterStrategy::Full,
+        };
+        config
+    }
+
+    #[tokio::test]
+    async fn test_retry_recovers_from_transient_failures() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(
+            fast_retry_config(3),
+            transport.clone(),
+            Arc::new(NullSink),
+        ).await;
+
+        // Fails twice, then succeeds
+        transport.fail_next(2);
+        assert!(client.status().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_retry_surfaces_last_error_when_exhausted() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(
+            fast_retry_config(1),
+            transport.clone(),
+            Arc::new(NullSink),
+        ).await;
+
+        transport.fail_next(2);
+        let result = client.status().await;
+        assert!(matches!(result, Err(VaultError::Transport(_))));
+    }
+
+    #[tokio::test]
+    async fn test_retry_caps_retry_after_at_max_delay() {
+        let client = mock_client(fast_retry_config(1), Arc::new(NullSink)).await;
+
+        // An hour-long Retry-After must not stall the caller past max_delay
+        let calls = std::sync::atomic::AtomicU32::new(0);
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            client.with_retry(|| async {
+                match calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                    0 => Err(VaultError::RateLimit(Duration::from_secs(3600))),
+                    _ => Ok(()),
+                }
+            }),
+        ).await;
+        assert!(result.expect("Retry-After was not capped").is_ok());
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_identity_is_single_use() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        transport.add_bootstrap_token("ott-123");
+        let client = mock_client_with_transport(
+            Config::default(),
+            transport,
+            Arc::new(NullSink),
+        ).await;
+
+        let identity = client.bootstrap_identity("ott-123").await.unwrap();
+        assert_eq!(client.get_identity().await.unwrap().token(), identity.token());
+
+        let reused = client.bootstrap_identity("ott-123").await;
+        assert!(matches!(
+            reused,
+            Err(VaultError::Identity(crate::error::IdentityError::InvalidToken(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_await_revocation_waits_for_completion() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        transport.set_revocation_delay(3);
+        let client = mock_client_with_transport(
+            fast_retry_config(0),
+            transport.clone(),
+            Arc::new(NullSink),
+        ).await;
+
+        let capability = test_capability(Domain::Database);
+        let receipt = client.revoke_capability_with_receipt(capability.id).await.unwrap();
+        assert_eq!(receipt.status, RevocationStatus::Pending);
+
+        let receipt = client
+            .await_revocation(capability.id, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(receipt.status, RevocationStatus::Completed);
+        assert_eq!(receipt.id, capability.id);
+
+        // No pending polls remain once the revocation has completed
+        let status = transport.revocation_status(capability.id).await.unwrap();
+        assert_eq!(status.status, RevocationStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_access_composite_with_valid_members() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let composite = CompositeCapability::new(vec![
+            test_capability(Domain::Database),
+            test_capability(Domain::Cloud),
+        ]);
+
+        let result = client
+            .access_composite(&composite, |caps| {
+                let client = client.clone();
+                async move {
+                    let mut responses = Vec::new();
+                    for cap in caps {
+                        let response: serde_json::Value = client.access_with_capability(cap).await?;
+                        responses.push(response);
+                    }
+                    Ok(responses.len())
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result, 2);
+    }
+
+    #[tokio::test]
+    async fn test_access_composite_fails_before_any_access() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let mut expired = test_capability(Domain::Cloud);
+        expired.expires_at = chrono::Utc::now() - chrono::Duration::seconds(1);
+        let composite = CompositeCapability::new(vec![test_capability(Domain::Database), expired]);
+        assert!(!composite.is_valid());
+
+        let invoked = std::sync::atomic::AtomicBool::new(false);
+        let result = client
+            .access_composite(&composite, |_caps| async {
+                invoked.store(true, std::sync::atomic::Ordering::SeqCst);
+                Ok(())
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(VaultError::Capability(crate::error::CapabilityError::Expired(_)))
+        ));
+        assert!(!invoked.load(std::sync::atomic::Ordering::SeqCst));
+
+        // An exhausted member is reported as such, not as expired
+        let mut exhausted = test_capability(Domain::Cloud);
+        exhausted.context.usage_limits = Some(crate::capability::UsageLimits {
+            max_uses: Some(1),
+            current_uses: 1,
+            ..Default::default()
+        });
+        let composite = CompositeCapability::new(vec![test_capability(Domain::Database), exhausted]);
+        let result = client.access_composite(&composite, |_caps| async { Ok(()) }).await;
+        assert!(matches!(
+            result,
+            Err(VaultError::Capability(crate::error::CapabilityError::ScopeMismatch(msg))) if msg.contains("Usage limit")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_access_transformed_extracts_field() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let capability = test_capability(Domain::Database);
+
+        let message: String = client
+            .access_transformed(&capability, &crate::transform::JsonPointer::new("/message"))
+            .await
+            .unwrap();
+        assert_eq!(message, "Access granted");
+    }
+
+    #[tokio::test]
+    async fn test_capability_guard_revokes_on_drop() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().service("my-app").build().unwrap();
+
+        let guard = client
+            .acquire(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let capability_id = guard.id;
+        let _: serde_json::Value = client.access_with_capability(&guard).await.unwrap();
+        assert_eq!(client.list_capabilities().await.unwrap().len(), 1);
+
+        drop(guard);
+        for _ in 0..50 {
+            if client.list_capabilities().await.unwrap().is_empty() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        let remaining = client.list_capabilities().await.unwrap();
+        assert!(remaining.iter().all(|cap| cap.id != capability_id));
+
+        let guard = client
+            .acquire(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        guard.revoke().await.unwrap();
+        assert!(client.list_capabilities().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_server_hints_auto_apply() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        transport.set_server_hints(ServerHints {
+            recommended_ttls: vec![TtlHint { domain: Domain::Database, ttl_secs: 120 }],
+            allowed_domains: vec![Domain::Database, Domain::Tls],
+            rate_limits: vec![RateLimitHint { domain: Domain::Database, max_requests: 1, window_secs: 60 }],
+        });
+
+        // Hints are fetched at startup unless opted out
+        let mut config = Config::default();
+        config.server_hints.fetch = false;
+        let passive = mock_client_with_transport(config.clone(), transport.clone(), Arc::new(NullSink)).await;
+        assert!(passive.server_hints().await.allowed_domains.is_empty());
+        config.server_hints.fetch = true;
+        passive.apply_config(config.clone()).await.unwrap();
+        assert_eq!(passive.server_hints().await.allowed_domains.len(), 2);
+
+        config.server_hints.auto_apply = true;
+        let client = mock_client_with_transport(config, transport.clone(), Arc::new(NullSink)).await;
+        assert_eq!(client.server_hints().await.allowed_domains.len(), 2);
+
+        assert_eq!(client.default_ttl(&Domain::Database).await, Duration::from_secs(120));
+        assert_eq!(client.default_ttl(&Domain::Ssh).await, Duration::from_secs(300));
+        assert_eq!(client.domain_catalog().await, vec![Domain::Database, Domain::Tls]);
+
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+        client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let err = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VaultError::RateLimit(_)));
+
+        // Without auto-apply the hints are only exposed
+        let client = mock_client_with_transport(Config::default(), transport, Arc::new(NullSink)).await;
+        assert_eq!(client.server_hints().await.allowed_domains.len(), 2);
+        assert_eq!(client.default_ttl(&Domain::Database).await, Duration::from_secs(300));
+        assert!(client.domain_catalog().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_auto_refresh_extends_expiring_capabilities() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        let expiring = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(10))
+            .await
+            .unwrap();
+        let opted_out = client
+            .request_capability(Domain::Database, Action::Read, "orders", &context, Duration::from_secs(10))
+            .await
+            .unwrap();
+        client.set_auto_refresh(opted_out.id, false);
+
+        assert!(client.enable_auto_refresh(Duration::from_secs(20), Duration::from_secs(25 * 60 * 60)).is_err());
+        client.enable_auto_refresh(Duration::from_secs(20), Duration::from_secs(60)).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let cached: std::collections::HashMap<_, _> = client
+            .list_capabilities()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|cap| (cap.id, cap))
+            .collect();
+        assert!(cached[&expiring.id].expires_at > expiring.expires_at + chrono::Duration::seconds(40));
+        assert_eq!(cached[&opted_out.id].expires_at, opted_out.expires_at);
+
+        client.close().await.unwrap();
+        assert!(client.auto_refresh.lock().unwrap().task.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_auto_refresh_does_not_keep_client_alive() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        client.enable_auto_refresh(Duration::from_millis(40), Duration::from_secs(60)).unwrap();
+        let capabilities = Arc::downgrade(&client.capabilities);
+
+        drop(client);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(capabilities.upgrade().is_none(), "auto-refresh task kept the client alive");
+    }
+
+    #[tokio::test]
+    async fn test_access_counts_usage_in_cache() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(Config::default(), transport.clone(), Arc::new(NullSink)).await;
+        let mut capability = test_capability(Domain::Database);
+        capability.context.usage_limits = Some(crate::capability::UsageLimits {
+            max_uses: Some(5),
+            ..Default::default()
+        });
+        client.capabilities.write().await.insert(capability.clone());
+
+        for _ in 0..2 {
+            let _: serde_json::Value = client.access_with_capability(&capability).await.unwrap();
+        }
+
+        let cached = client.capabilities.read().await.get(&capability.id).unwrap().clone();
+        assert_eq!(cached.context.usage_limits.unwrap().current_uses, 2);
+
+        // A failed access does not use up the capability
+        transport.fail_next(1);
+        assert!(client.access_with_capability::<serde_json::Value>(&capability).await.is_err());
+        let cached = client.capabilities.read().await.get(&capability.id).unwrap().clone();
+        assert_eq!(cached.context.usage_limits.unwrap().current_uses, 2);
+    }
+
+    #[tokio::test]
+    async fn test_request_capability_rejects_expired_identity() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let context = Context::builder().build().unwrap();
+
+        let expired = chrono::Utc::now().timestamp() - 60;
+        client.set_identity(Identity::new(test_jwt(serde_json::json!({"exp": expired})))).await.unwrap();
+        let err = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VaultError::Identity(crate::error::IdentityError::TokenExpired(_))));
+
+        let valid = chrono::Utc::now().timestamp() + 3600;
+        client.set_identity(Identity::new(test_jwt(serde_json::json!({"exp": valid})))).await.unwrap();
+        assert!(client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_workload_identity_refreshed_near_expiry() {
+        let token_file = tempfile::NamedTempFile::new().unwrap();
+        let expiring = test_jwt(serde_json::json!({"exp": chrono::Utc::now().timestamp() + 10}));
+        std::fs::write(token_file.path(), &expiring).unwrap();
+
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let workload = WorkloadIdentity::from_kubernetes_token_file(token_file.path()).unwrap();
+        client.set_workload_identity(workload).await.unwrap();
+
+        // The kubelet rotated the token; within the 60s skew it is picked up
+        let rotated = test_jwt(serde_json::json!({"exp": chrono::Utc::now().timestamp() + 3600}));
+        std::fs::write(token_file.path(), &rotated).unwrap();
+
+        let context = Context::builder().build().unwrap();
+        client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(client.get_identity().await.unwrap().token(), rotated);
+    }
+
+    #[tokio::test]
+    async fn test_workload_identity_refreshed_under_oversized_skew() {
+        let token_file = tempfile::NamedTempFile::new().unwrap();
+        let current = test_jwt(serde_json::json!({"exp": chrono::Utc::now().timestamp() + 3600}));
+        std::fs::write(token_file.path(), &current).unwrap();
+
+        let mut config = Config::default();
+        config.auth.token_refresh_skew = Duration::from_secs(i64::MAX as u64 / 1000);
+        let client = mock_client(config, Arc::new(NullSink)).await;
+        let workload = WorkloadIdentity::from_kubernetes_token_file(token_file.path()).unwrap();
+        client.set_workload_identity(workload).await.unwrap();
+
+        // Subtracting the skew overflows; that counts as expiring, not a panic
+        let rotated = test_jwt(serde_json::json!({"exp": chrono::Utc::now().timestamp() + 7200}));
+        std::fs::write(token_file.path(), &rotated).unwrap();
+
+        let context = Context::builder().build().unwrap();
+        client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(client.get_identity().await.unwrap().token(), rotated);
+    }
+
+    #[tokio::test]
+    async fn test_request_database_typed() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        let capability = client
+            .request_database(Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(capability.domain, Domain::Database);
+        let _: serde_json::Value = client.access_with_capability(&capability).await.unwrap();
+
+        assert!(client
+            .request_database(Action::Execute, "users", &context, Duration::from_secs(60))
+            .await
+            .is_err());
+        assert!(client.list_capabilities().await.unwrap().len() == 1);
+    }
+
+    #[tokio::test]
+    async fn test_with_capability_revokes_after_success_and_error() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let request = || CapabilityRequest::builder()
+            .domain(Domain::Database)
+            .action(Action::Read)
+            .target("users")
+            .build()
+            .unwrap();
+        let scoped = &client;
+
+        let message: String = client
+            .with_capability(request(), |capability| async move {
+                let response: serde_json::Value = scoped.access_with_capability(&capability).await?;
+                Ok(response["message"].as_str().unwrap_or_default().to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(message, "Access granted");
+        assert!(client.list_capabilities().await.unwrap().is_empty());
+
+        let err = client
+            .with_capability(request(), |_capability| async {
+                Err::<(), _>(VaultError::Validation("operation failed".to_string()))
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VaultError::Validation(_)));
+        assert!(client.list_capabilities().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_audit_sink_records_request_access_revoke() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let sink = Arc::new(CollectingSink::default());
+        client.set_audit_sink(sink.clone());
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+
+        let context = Context::builder().build().unwrap();
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let _: serde_json::Value = client.access_with_capability(&capability).await.unwrap();
+        client.revoke_capability(capability.id).await.unwrap();
+
+        let events = sink.events.lock().unwrap();
+        let operations: Vec<&str> = events.iter().map(|e| e.operation.as_str()).collect();
+        assert_eq!(operations, vec!["request", "access", "revoke"]);
+        assert!(events.iter().all(|e| e.capability_id == Some(capability.id)));
+        assert!(events.iter().all(|e| e.outcome == crate::audit::AuditOutcome::Success));
+        assert_eq!(events[0].target.as_deref(), Some("users"));
+    }
+
+    #[tokio::test]
+    async fn test_debug_redacts_secrets() {
+        let mut token_file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut token_file, b"s3cr3t-bearer-token").unwrap();
+
+        let mut config = Config::default();
+        config.auth.method = AuthMethod::Token;
+        config.auth.token_file = Some(token_file.path().to_path_buf());
+        let transport = Arc::new(crate::transport::HttpTransport::new(&config).await.unwrap());
+        let client = mock_client_with_transport(config, transport, Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("identity-token".to_string())).await.unwrap();
+
+        let mut capability = test_capability(Domain::Database);
+        capability.signature = b"signature-bytes".to_vec();
+        client.capabilities.write().await.insert(capability.clone());
+
+        let debug = format!("{:?}", client);
+        assert!(!debug.contains("s3cr3t-bearer-token"));
+        assert!(!debug.contains("identity-token"));
+        assert!(debug.contains("cached_capabilities: Some(1)"));
+
+        let debug = format!("{:?}", capability);
+        assert!(debug.contains("signature: \"***\""));
+        assert!(!debug.contains(&format!("{:?}", capability.signature)));
+    }
+
+    #[tokio::test]
+    async fn test_clients_share_pooled_transport() {
+        let pool = TransportPool::new();
+        let mut config = Config::default();
+        config.auth.method = AuthMethod::None;
+        config.server_hints.fetch = false;
+
+        let first = Client::with_shared_transport(config.clone(), &pool).await.unwrap();
+        let second = Client::with_shared_transport(config, &pool).await.unwrap();
+        assert!(Arc::ptr_eq(&first.transport(), &second.transport()));
+        assert_eq!(pool.len().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_request_capabilities_mixed_results() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        transport.deny_target("payroll");
+        let client = mock_client_with_transport(Config::default(), transport, Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+
+        let context = Context::builder().build().unwrap().to_capability_context();
+        let request = |target: &str, ttl: u64| {
+            CapabilityRequest::new(Domain::Database, Action::Read, target.to_string(), context.clone(), Duration::from_secs(ttl))
+        };
+        let results = client
+            .request_capabilities(vec![request("users", 60), request("orders", 0), request("payroll", 60), request("cache", 60)])
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap().target, "users");
+        assert!(results[1].is_err());
+        assert!(matches!(results[2], Err(VaultError::AccessDenied(_))));
+        assert_eq!(results[3].as_ref().unwrap().target, "cache");
+
+        // Only issued capabilities are cached
+        let cached = client.list_capabilities().await.unwrap();
+        assert_eq!(cached.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+
+        let context = Context::builder().build().unwrap();
+        let mut held = Vec::new();
+        for target in ["users", "orders", "invoices"] {
+            let capability = client
+                .request_capability(Domain::Database, Action::Read, target, &context, Duration::from_secs(60))
+                .await
+                .unwrap();
+            held.push(capability.id);
+        }
+
+        let outcomes = client.revoke_all().await.unwrap();
+        assert_eq!(outcomes.len(), 3);
+        assert!(outcomes.iter().all(|(id, result)| held.contains(id) && result.is_ok()));
+        assert!(client.list_capabilities().await.unwrap().is_empty());
+
+        // Nothing left to revoke
+        assert!(client.revoke_all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_near_expiry_callback() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&calls);
+        client.on_near_expiry(Duration::from_secs(30), move |id, remaining| {
+            recorded.lock().unwrap().push((id, remaining));
+        });
+
+        let short = Capability::new(
+            Domain::Database,
+            Action::Read,
+            "users".to_string(),
+            crate::capability::CapabilityContext::default(),
+            Duration::from_secs(10),
+            "vault".to_string(),
+            "svc".to_string(),
+        );
+        let long = test_capability(Domain::Database);
+
+        let _: serde_json::Value = client.access_with_capability(&short).await.unwrap();
+        let _: serde_json::Value = client.access_with_capability(&long).await.unwrap();
+        {
+            let calls = calls.lock().unwrap();
+            assert_eq!(calls.len(), 1);
+            assert_eq!(calls[0].0, short.id);
+            assert!(calls[0].1 <= Duration::from_secs(10));
+        }
+
+        // Once per access
+        let _: serde_json::Value = client.access_with_capability(&short).await.unwrap();
+        assert_eq!(calls.lock().unwrap().len(), 2);
+
+        client.clear_near_expiry();
+        let _: serde_json::Value = client.access_with_capability(&short).await.unwrap();
+        assert_eq!(calls.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_access_stream_large_payload() {
+        use tokio::io::AsyncReadExt;
+
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let payload: Vec<u8> = (0..4 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        transport.set_stream_payload(payload.clone());
+        let client = mock_client_with_transport(Config::default(), transport, Arc::new(NullSink)).await;
+
+        let mut capability = test_capability(Domain::Tls);
+        capability.context.usage_limits = Some(crate::capability::UsageLimits {
+            max_uses: Some(1),
+            ..Default::default()
+        });
+
+        let mut stream = client.access_stream(&capability).await.unwrap();
+        let mut received = Vec::new();
+        stream.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received.len(), payload.len());
+        assert!(received == payload);
+
+        // The stream counted as a use
+        assert!(client.access_stream(&capability).await.is_err());
+
+        // Expired capabilities are rejected before streaming
+        let mut expired = test_capability(Domain::Tls);
+        expired.expires_at = chrono::Utc::now() - chrono::Duration::seconds(1);
+        assert!(client.access_stream(&expired).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ttl_clamped_to_identity_lifetime() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let expires = chrono::Utc::now() + chrono::Duration::seconds(120);
+        client.set_identity(Identity::new(test_jwt(serde_json::json!({"exp": expires.timestamp()})))).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(300))
+            .await
+            .unwrap();
+        let ttl = capability.expires_at - capability.issued_at;
+        assert!(ttl <= chrono::Duration::seconds(120));
+        assert!(ttl > chrono::Duration::seconds(100));
+
+        // TTLs within the identity's lifetime are left alone
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(30))
+            .await
+            .unwrap();
+        assert_eq!(capability.expires_at - capability.issued_at, chrono::Duration::seconds(30));
+    }
+
+    #[tokio::test]
+    async fn test_ttl_rejected_past_identity_lifetime() {
+        let mut config = Config::default();
+        config.auth.ttl_policy = crate::config::TtlPolicy::Reject;
+        let client = mock_client(config, Arc::new(NullSink)).await;
+        let expires = chrono::Utc::now() + chrono::Duration::seconds(120);
+        client.set_identity(Identity::new(test_jwt(serde_json::json!({"exp": expires.timestamp()})))).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        let err = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(300))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            VaultError::Identity(crate::error::IdentityError::TokenExpiresBeforeCapability(_))
+        ));
+        assert!(client.capabilities.read().await.is_empty());
+
+        let results = client
+            .request_capabilities(vec![
+                CapabilityRequest::new(Domain::Database, Action::Read, "users".to_string(), context.to_capability_context(), Duration::from_secs(60)),
+                CapabilityRequest::new(Domain::Database, Action::Read, "orders".to_string(), context.to_capability_context(), Duration::from_secs(300)),
+            ])
+            .await
+            .unwrap();
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_capability_cache_bounded_by_max_size() {
+        let mut config = Config::default();
+        config.cache = Some(crate::config::CacheConfig {
+            enabled: false,
+            max_size: 2,
+            ttl: Duration::from_secs(60),
+        });
+        let client = mock_client(config, Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        let mut issued = Vec::new();
+        for target in ["users", "orders"] {
+            issued.push(
+                client
+                    .request_capability(Domain::Database, Action::Read, target, &context, Duration::from_secs(60))
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        // Using the oldest capability makes "orders" the least recently used
+        let _: serde_json::Value = client.access_with_capability(&issued[0]).await.unwrap();
+        let newest = client
+            .request_capability(Domain::Database, Action::Read, "invoices", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let cached: std::collections::HashSet<uuid::Uuid> =
+            client.list_capabilities().await.unwrap().iter().map(|capability| capability.id).collect();
+        assert_eq!(cached, std::collections::HashSet::from([issued[0].id, newest.id]));
+    }
+
+    #[tokio::test]
+    async fn test_access_response_cache() {
+        let mut config = Config::default();
+        config.cache = Some(crate::config::CacheConfig {
+            enabled: true,
+            max_size: 16,
+            ttl: Duration::from_millis(100),
+        });
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(config, transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        let read = client
+            .request_capability(Domain::Tls, Action::Read, "api.example.com", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let first: serde_json::Value = client.access_with_capability(&read).await.unwrap();
+        let second: serde_json::Value = client.access_with_capability(&read).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(transport.access_count(), 1);
+
+        // Stale entries are fetched again
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let _: serde_json::Value = client.access_with_capability(&read).await.unwrap();
+        assert_eq!(transport.access_count(), 2);
+
+        // Writes always reach the server
+        let write = client
+            .request_capability(Domain::Tls, Action::Write, "api.example.com", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            let _: serde_json::Value = client.access_with_capability(&write).await.unwrap();
+        }
+        assert_eq!(transport.access_count(), 4);
+    }
+
+    #[derive(Default)]
+    struct CountingRecorder {
+        requests: std::sync::Mutex<Vec<&'static str>>,
+        errors: std::sync::Mutex<Vec<(&'static str, &'static str)>>,
+        hits: std::sync::atomic::AtomicU32,
+        misses: std::sync::atomic::AtomicU32,
+    }
+
+    impl Recorder for CountingRecorder {
+        fn record_request(&self, operation: &'static str) {
+            self.requests.lock().unwrap().push(operation);
+        }
+
+        fn record_error(&self, operation: &'static str, error_code: &'static str) {
+            self.errors.lock().unwrap().push((operation, error_code));
+        }
+
+        fn record_cache_hit(&self) {
+            self.hits.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+
+        fn record_cache_miss(&self) {
+            self.misses.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_recorder_counts_request_access_flow() {
+        let mut config = fast_retry_config(0);
+        config.cache = Some(crate::config::CacheConfig {
+            enabled: true,
+            max_size: 16,
+            ttl: Duration::from_secs(60),
+        });
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(config, transport.clone(), Arc::new(NullSink)).await;
+        let recorder = Arc::new(CountingRecorder::default());
+        client.set_metrics_recorder(recorder.clone());
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
 
-/// Individual health detail
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct HealthDetail {
-    /// Component name
-    pub component: String,
-    
-    /// Component status
-    pub status: HealthStatusType,
-    
-    /// Status message
-    pub message: Option<String>,
-    
-    /// Response time in milliseconds
-    pub response_time_ms: Option<u64>,
-}
+        let read = client
+            .request_capability(Domain::Tls, Action::Read, "api.example.com", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            let _: serde_json::Value = client.access_with_capability(&read).await.unwrap();
+        }
+        transport.fail_next(1);
+        assert!(client.revoke_capability(read.id).await.is_err());
 
-/// Health status types
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum HealthStatusType {
-    /// Component is healthy
-    Healthy,
-    /// Component is degraded
-    Degraded,
-    /// Component is unhealthy
-    Unhealthy,
-    /// Component status unknown
-    Unknown,
-}
+        assert_eq!(*recorder.requests.lock().unwrap(), vec!["request", "access", "access", "revoke"]);
+        assert_eq!(recorder.misses.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(recorder.hits.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(*recorder.errors.lock().unwrap(), vec![("revoke", "TRANSPORT_ERROR")]);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::config::{AuthConfig, AuthMethod, TransportType};
-    use std::collections::HashSet;
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_operation_spans() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(fast_retry_config(0), transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        let capability = client
+            .request_capability(Domain::Tls, Action::Read, "api.example.com", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let _: serde_json::Value = client.access_with_capability(&capability).await.unwrap();
+        transport.fail_next(1);
+        assert!(client.revoke_capability(capability.id).await.is_err());
+
+        let fields = format!("domain=tls action=read target=api.example.com capability_id={}", capability.id);
+        assert!(logs_contain(&format!("request_capability{{{} outcome=\"success\"}}", fields)));
+        assert!(logs_contain(&format!("access{{{} outcome=\"success\"}}", fields)));
+        assert!(logs_contain(&format!("revoke{{capability_id={}", capability.id)));
+        assert!(logs_contain("outcome=\"failure\" error_code=\"TRANSPORT_ERROR\""));
+        assert!(!logs_contain("test-token"));
+    }
 
     #[tokio::test]
-    async fn test_client_creation() {
-        let config = Config {
-            endpoint: "http://localhost:8200".to_string(),
-            transport: TransportType::Http,
-            auth: AuthConfig {
-                method: AuthMethod::None,
-                token_file: None,
-                cert_file: None,
-                key_file: None,
-                ca_file: None,
-            },
-            timeouts: crate::config::TimeoutConfig::default(),
-            retry: crate::config::RetryConfig::default(),
-            tls: None,
-            logging: crate::config::LoggingConfig::default(),
-            cache: None,
+    async fn test_check_capability_decisions() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(Config::default(), transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let check = |target: &str| CapabilityRequest::new(
+            Domain::Database,
+            Action::Read,
+            target.to_string(),
+            crate::capability::CapabilityContext::default(),
+            Duration::from_secs(600),
+        );
+
+        let allowed = client.check_capability(check("db.example.com")).await.unwrap();
+        assert!(allowed.allowed);
+        assert_eq!(allowed.granted_ttl(), Some(Duration::from_secs(600)));
+
+        transport.set_policy_decision("db.prod", PolicyDecision {
+            allowed: true,
+            reason: Some("ttl capped by policy db-prod".to_string()),
+            granted_ttl_secs: Some(120),
+        });
+        let capped = client.check_capability(check("db.prod")).await.unwrap();
+        assert!(capped.allowed);
+        assert_eq!(capped.granted_ttl(), Some(Duration::from_secs(120)));
+
+        transport.deny_target("db.secret");
+        let denied = client.check_capability(check("db.secret")).await.unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.reason.as_deref(), Some("target db.secret is denied"));
+        assert_eq!(denied.granted_ttl(), None);
+
+        // Nothing was issued or cached
+        assert!(client.list_capabilities().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_ttl_limits() {
+        let mut config = Config::default();
+        config.max_capability_lifetime = Some(Duration::from_secs(1800));
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(config, transport, Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+        let capability = client
+            .request_capability(Domain::Tls, Action::Read, "api.example.com", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        // Beyond the per-request maximum
+        let err = client.refresh_capability(capability.id, Duration::from_secs(25 * 3600)).await.unwrap_err();
+        assert!(matches!(err, VaultError::Capability(crate::error::CapabilityError::InvalidFormat(_))), "{}", err);
+
+        // Within the per-request bounds but past the configured lifetime
+        let err = client.refresh_capability(capability.id, Duration::from_secs(3600)).await.unwrap_err();
+        assert!(matches!(err, VaultError::Capability(crate::error::CapabilityError::InvalidFormat(_))), "{}", err);
+        assert!(err.to_string().contains("beyond the 1800s maximum"), "{}", err);
+
+        let refreshed = client.refresh_capability(capability.id, Duration::from_secs(600)).await.unwrap();
+        assert!(refreshed.expires_at > capability.expires_at);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(fast_retry_config(0), transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        // A fresh sealed status short-circuits requests
+        transport.seal_for(4);
+        assert!(client.status().await.unwrap().sealed);
+        let err = client
+            .request_capability(Domain::Tls, Action::Read, "api.example.com", &context, Duration::from_secs(60))
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, VaultError::Server(message) if message == "vault sealed"), "{}", err);
+
+        // Unsealed after three more polls
+        let status = client.wait_until_ready(Duration::from_secs(5)).await.unwrap();
+        assert!(!status.sealed);
+        client
+            .request_capability(Domain::Tls, Action::Read, "api.example.com", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        transport.seal_for(u32::MAX);
+        let err = client.wait_until_ready(Duration::from_millis(50)).await.unwrap_err();
+        assert!(matches!(err, VaultError::Timeout(timeout) if timeout == Duration::from_millis(50)));
+    }
+
+    #[tokio::test]
+    async fn test_access_detailed_metadata() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+        let capability = client
+            .request_capability(Domain::Tls, Action::Read, "api.example.com", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let response: AccessResponse<serde_json::Value> = client.access_detailed(&capability).await.unwrap();
+        assert_eq!(response.data["message"], "Access granted");
+        assert!(response.renewable);
+        assert_eq!(response.version, Some(1));
+        assert!(response.request_id.is_some());
+        let lease = response.lease().unwrap();
+        assert!(lease <= Duration::from_secs(60) && lease >= Duration::from_secs(50), "{:?}", lease);
+
+        // The plain access returns just the data
+        let data: serde_json::Value = client.access_with_capability(&capability).await.unwrap();
+        assert_eq!(data["capability_id"], capability.id.to_string());
+    }
+
+    #[test]
+    fn test_access_response_without_envelope() {
+        let response: AccessResponse<serde_json::Value> =
+            serde_json::from_str(r#"{"username":"svc","password":"hunter2"}"#).unwrap();
+        assert_eq!(response.data["username"], "svc");
+        assert_eq!(response.lease_duration, None);
+        assert!(!response.renewable);
+
+        let response: AccessResponse<String> =
+            serde_json::from_str(r#"{"data":"s3cret","lease_duration":300,"renewable":true,"version":7,"request_id":"req-1"}"#).unwrap();
+        assert_eq!(response.data, "s3cret");
+        assert_eq!(response.lease(), Some(Duration::from_secs(300)));
+        assert_eq!(response.version, Some(7));
+        assert_eq!(response.request_id.as_deref(), Some("req-1"));
+
+        let response: AccessResponse<String> =
+            serde_json::from_str(r#"{"data":"s3cret","request_id":"req-2"}"#).unwrap();
+        assert_eq!(response.data, "s3cret");
+        assert_eq!(response.lease_duration, None);
+        assert_eq!(response.request_id.as_deref(), Some("req-2"));
+
+        // A secret with its own `data` field is not an envelope
+        let response: AccessResponse<serde_json::Value> =
+            serde_json::from_str(r#"{"data":"payload","format":"pem"}"#).unwrap();
+        assert_eq!(response.data["format"], "pem");
+        assert_eq!(response.data["data"], "payload");
+        assert_eq!(response.request_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_audit_events_carry_namespace() {
+        let mut config = Config::default();
+        config.namespace = Some("tenant-a".to_string());
+        let sink = Arc::new(CollectingSink::default());
+        let client = mock_client(config, sink.clone()).await;
+
+        let _: serde_json::Value = client.access_with_capability(&test_capability(Domain::Database)).await.unwrap();
+        let events = sink.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].namespace.as_deref(), Some("tenant-a"));
+    }
+
+    #[tokio::test]
+    async fn test_ping() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        transport.set_latency(Duration::ZERO, Duration::from_millis(5));
+        let client = mock_client_with_transport(Config::default(), transport.clone(), Arc::new(NullSink)).await;
+
+        assert_eq!(client.ping().await.unwrap(), Duration::from_millis(5));
+
+        // A single attempt, so one failure is enough to report the node down
+        transport.fail_next(1);
+        assert!(client.ping().await.is_err());
+        assert!(client.ping().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_request_capability_as_isolates_identities() {
+        let client = Arc::new(mock_client(Config::default(), Arc::new(NullSink)).await);
+        let own = Identity::new(test_jwt(serde_json::json!({"sub": "gateway"})));
+        client.set_identity(own.clone()).await.unwrap();
+        let tenant_a = Identity::new(test_jwt(serde_json::json!({"sub": "tenant-a"})));
+        let tenant_b = Identity::new(test_jwt(serde_json::json!({"sub": "tenant-b"})));
+
+        let mut tasks = Vec::new();
+        for i in 0..10 {
+            let client = client.clone();
+            let identity = if i % 2 == 0 { tenant_a.clone() } else { tenant_b.clone() };
+            tasks.push(tokio::spawn(async move {
+                let context = Context::builder().build().unwrap();
+                client
+                    .request_capability_as(&identity, Domain::Database, Action::Read, &format!("db-{}", i), &context, Duration::from_secs(60))
+                    .await
+                    .unwrap()
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // The client-wide identity is untouched
+        assert_eq!(client.get_identity().await.unwrap().token(), own.token());
+
+        let held_a = client.list_capabilities_as(&tenant_a).await.unwrap();
+        let held_b = client.list_capabilities_as(&tenant_b).await.unwrap();
+        assert_eq!(held_a.len(), 5);
+        assert_eq!(held_b.len(), 5);
+        assert!(held_a.iter().all(|cap| cap.subject == "tenant-a"));
+        assert!(held_b.iter().all(|cap| cap.subject == "tenant-b"));
+        assert!(client.list_capabilities_as(&own).await.unwrap().is_empty());
+        assert!(client.list_capabilities_as(&Identity::new("opaque".to_string())).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cached_capabilities_scoped_to_identity() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(Config::default(), transport.clone(), Arc::new(NullSink)).await;
+        let context = Context::builder().build().unwrap();
+
+        // Opaque tokens carry no claims to scope by
+        client.set_identity(Identity::new("tenant-a-token".to_string())).await.unwrap();
+        let minted = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(client.list_capabilities().await.unwrap().len(), 1);
+
+        client.set_identity(Identity::new("tenant-b-token".to_string())).await.unwrap();
+        assert!(client.list_capabilities().await.unwrap().is_empty());
+        assert!(client.revoke_all().await.unwrap().is_empty());
+        let bundle = client.export_bundle(&[1u8; crate::crypto::SEAL_KEY_LEN], BundleOptions::default()).await.unwrap();
+        assert!(!String::from_utf8_lossy(&bundle).contains(&minted.id.to_string()));
+
+        // B holds only what it requested itself
+        let own = client
+            .request_capability(Domain::Database, Action::Read, "orders", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let held = client.list_capabilities().await.unwrap();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].id, own.id);
+
+        // A's capability survived B's revoke_all
+        client.set_identity(Identity::new("tenant-a-token".to_string())).await.unwrap();
+        let held = client.list_capabilities().await.unwrap();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].id, minted.id);
+
+        // close revokes every cached capability, whoever it was issued to
+        let delegated = client
+            .request_capability_as(&Identity::new("tenant-c-token".to_string()), Domain::Database, Action::Read, "orders", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        client.close().await.unwrap();
+        let revoked: Vec<uuid::Uuid> = transport.revoke_reasons().into_iter().map(|(id, _)| id).collect();
+        assert!(revoked.contains(&minted.id));
+        assert!(revoked.contains(&own.id));
+        assert!(revoked.contains(&delegated.id));
+    }
+
+    #[tokio::test]
+    async fn test_batch_falls_back_without_server_support() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        transport.set_features(Some(vec!["check".to_string()]));
+        let client = mock_client_with_transport(Config::default(), transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+
+        assert!(!client.supports(Feature::Batch).await);
+        assert!(client.supports(Feature::PolicyCheck).await);
+
+        let requests = ["users", "orders", ""]
+            .iter()
+            .map(|target| CapabilityRequest::new(
+                Domain::Database,
+                Action::Read,
+                target.to_string(),
+                Context::builder().build().unwrap().to_capability_context(),
+                Duration::from_secs(60),
+            ))
+            .collect();
+        let results = client.request_capabilities(requests).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().target, "users");
+        assert_eq!(results[1].as_ref().unwrap().target, "orders");
+        assert!(results[2].is_err());
+
+        // Servers that don't advertise features are assumed to support all
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        assert!(client.supports(Feature::Batch).await);
+    }
+
+    #[tokio::test]
+    async fn test_retried_request_reuses_idempotency_key() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(fast_retry_config(3), transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        // The first attempt is issued but its response never arrives
+        transport.lose_responses(1);
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let keys = transport.idempotency_keys();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0], keys[1]);
+        assert_eq!(transport.issued_count(), 1);
+        assert_eq!(client.list_capabilities().await.unwrap()[0].id, capability.id);
+
+        // A new logical request gets a new key
+        client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let keys = transport.idempotency_keys();
+        assert_ne!(keys[2], keys[0]);
+        assert_eq!(transport.issued_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_close_gracefully_waits_for_in_flight_requests() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        transport.set_latency(Duration::ZERO, Duration::from_millis(200));
+        let client = mock_client_with_transport(Config::default(), transport.clone(), Arc::new(NullSink)).await;
+
+        let capability = test_capability(Domain::Database);
+        let access = {
+            let client = client.clone();
+            let capability = capability.clone();
+            tokio::spawn(async move { client.access_with_capability::<serde_json::Value>(&capability).await })
         };
+        tokio::time::sleep(Duration::from_millis(20)).await;
 
-        // This will fail in tests without a real Vault, but we can test the structure
-        let result = Client::new(config).await;
-        assert!(result.is_err() || result.is_ok()); // Either way, the structure is valid
+        // Too short a drain leaves the client open
+        let err = client.close_gracefully(Duration::from_millis(10)).await.unwrap_err();
+        assert!(matches!(err, VaultError::Timeout(_)));
+        assert!(!access.is_finished());
+
+        // Nothing new starts while draining
+        let started = std::time::Instant::now();
+        let close = {
+            let client = client.clone();
+            tokio::spawn(async move { client.close_gracefully(Duration::from_secs(5)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let err = client.access_with_capability::<serde_json::Value>(&capability).await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::Internal(msg) if msg.contains("closing")));
+
+        close.await.unwrap().unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(100), "{:?}", started.elapsed());
+        assert!(access.await.unwrap().is_ok());
     }
 
     #[tokio::test]
-    async fn test_identity_management() {
-        // Create a mock client for testing
-        let config = Config::default();
+    async fn test_local_dev_issues_locally_verifiable_capabilities() {
+        let (client, public_key) = Client::local_dev_with_config(Config::default()).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(capability.validate_signature(&public_key).unwrap());
+
+        let refreshed = client.refresh_capability(capability.id, Duration::from_secs(120)).await.unwrap();
+        assert!(refreshed.validate_signature(&public_key).unwrap());
+
+        // Another local client's key doesn't verify it
+        let (_, other_key) = Client::local_dev_with_config(Config::default()).await.unwrap();
+        assert!(capability.validate_signature(&other_key).is_err());
+
+        let mut remote = Config::default();
+        remote.endpoint = "https://vault.example.com".to_string();
+        let err = Client::local_dev_with_config(remote).await.unwrap_err();
+        assert!(err.to_string().contains("localhost"), "{}", err);
+
+        assert!(is_loopback_endpoint("http://127.0.0.1:8200"));
+        assert!(is_loopback_endpoint("http://[::1]:8200"));
+        assert!(!is_loopback_endpoint("https://vault.example.com"));
+        assert!(!is_loopback_endpoint("https://localhost.example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_access_refreshing() {
+        let client = mock_client(Config::default(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        // Plenty left: used as is
+        let mut ample = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(300))
+            .await
+            .unwrap();
+        let expires_at = ample.expires_at;
+        let _: serde_json::Value = client
+            .access_refreshing(&mut ample, Duration::from_secs(30), Duration::from_secs(600))
+            .await
+            .unwrap();
+        assert_eq!(ample.expires_at, expires_at);
+
+        // Near expiry: refreshed in place, and in the cache
+        let mut expiring = client
+            .request_capability(Domain::Database, Action::Read, "orders", &context, Duration::from_secs(10))
+            .await
+            .unwrap();
+        let _: serde_json::Value = client
+            .access_refreshing(&mut expiring, Duration::from_secs(30), Duration::from_secs(600))
+            .await
+            .unwrap();
+        assert!(expiring.remaining_ttl().unwrap() > Duration::from_secs(300));
+        let cached = client.capabilities.read().await.get(&expiring.id).unwrap().clone();
+        assert_eq!(cached.expires_at, expiring.expires_at);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_with_timeout() {
         let transport = Arc::new(crate::transport::MockTransport::new());
-        
-        let client = Client {
-            config: Arc::new(config),
-            transport,
-            identity: Arc::new(RwLock::new(None)),
-            capabilities: Arc::new(RwLock::new(std::collections::HashMap::new())),
+        let client = mock_client_with_transport(Config::default(), transport.clone(), Arc::new(NullSink)).await;
+        let component = |name: &str, status| HealthDetail {
+            component: name.to_string(),
+            status,
+            message: None,
+            response_time_ms: Some(3),
         };
 
-        // Initially no identity
-        assert!(client.get_identity().await.is_none());
+        // Healthy, with the measured transport round trip added
+        transport.set_health(vec![component("storage", HealthStatusType::Healthy)]);
+        let health = client.health_check_with_timeout(Duration::from_secs(1)).await;
+        assert!(health.healthy);
+        assert_eq!(health.status, HealthStatusType::Healthy);
+        let round_trip = health.details.iter().find(|detail| detail.component == "transport").unwrap();
+        assert!(round_trip.response_time_ms.is_some());
 
-        // Set identity
-        let identity = Identity::new("test-token".to_string());
-        client.set_identity(identity.clone()).await.unwrap();
+        // One degraded component degrades the whole status
+        transport.set_health(vec![
+            component("storage", HealthStatusType::Healthy),
+            component("replication", HealthStatusType::Degraded),
+        ]);
+        let health = client.health_check_with_timeout(Duration::from_secs(1)).await;
+        assert!(health.healthy);
+        assert_eq!(health.status, HealthStatusType::Degraded);
 
-        // Get identity
-        let retrieved = client.get_identity().await;
-        assert!(retrieved.is_some());
-        assert_eq!(retrieved.unwrap().token(), identity.token());
+        // A slow server yields an unhealthy status instead of an error
+        transport.set_latency(Duration::ZERO, Duration::from_millis(200));
+        let started = std::time::Instant::now();
+        let health = client.health_check_with_timeout(Duration::from_millis(20)).await;
+        assert!(started.elapsed() < Duration::from_millis(150));
+        assert!(!health.healthy);
+        assert_eq!(health.status, HealthStatusType::Unhealthy);
+        assert!(health.details[0].message.as_deref().unwrap().contains("no response"));
+    }
+
+    #[test]
+    fn test_health_evaluate_thresholds() {
+        let component = |name: &str, status, response_time_ms| HealthDetail {
+            component: name.to_string(),
+            status,
+            message: None,
+            response_time_ms,
+        };
+        let health = HealthStatus {
+            healthy: true,
+            status: HealthStatusType::Healthy,
+            details: vec![
+                component("storage", HealthStatusType::Healthy, Some(40)),
+                component("replication", HealthStatusType::Healthy, Some(250)),
+                component("audit", HealthStatusType::Healthy, None),
+            ],
+            timestamp: chrono::Utc::now(),
+        };
+
+        // Every component within its threshold
+        let mut thresholds = HealthThresholds {
+            max_response_time: Some(Duration::from_millis(100)),
+            ..HealthThresholds::default()
+        };
+        thresholds.component_max_response_time.insert("replication".to_string(), Duration::from_millis(500));
+        let mut passing = health.clone();
+        assert!(passing.evaluate(&thresholds).is_empty());
+        assert!(passing.healthy);
+        assert_eq!(passing.status, HealthStatusType::Healthy);
+
+        // A component above the global threshold fails the whole status
+        let mut slow = health.clone();
+        let failures = slow.evaluate(&HealthThresholds {
+            max_response_time: Some(Duration::from_millis(100)),
+            ..HealthThresholds::default()
+        });
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].component, "replication");
+        assert!(failures[0].reason.contains("250ms"), "{}", failures[0].reason);
+        assert!(!slow.healthy);
+        assert_eq!(slow.status, HealthStatusType::Unhealthy);
+
+        // Unhealthy components fail even without thresholds
+        let mut broken = health;
+        broken.details.push(component("seal", HealthStatusType::Unhealthy, Some(1)));
+        let failures = broken.evaluate(&HealthThresholds::default());
+        assert_eq!(failures, vec![HealthFailure {
+            component: "seal".to_string(),
+            reason: "reported unhealthy".to_string(),
+        }]);
+        assert!(!broken.healthy);
+    }
+
+    #[tokio::test]
+    async fn test_bundle_handoff_to_fresh_client() {
+        let (parent, _) = Client::local_dev_with_config(Config::default()).await.unwrap();
+        let capability = parent
+            .request_capability(Domain::Database, Action::Read, "users", &Context::builder().build().unwrap(), Duration::from_secs(60))
+            .await
+            .unwrap();
+        let key = [42u8; crate::crypto::SEAL_KEY_LEN];
+        let bundle = parent
+            .export_bundle(&key, BundleOptions { include_identity: true, encrypt: true })
+            .await
+            .unwrap();
+
+        let child = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let trusted = ImportOptions { trust_unsigned: true };
+        assert_eq!(child.import_bundle(&bundle, &key, trusted).await.unwrap(), 1);
+        assert_eq!(child.get_identity().await.unwrap().token(), parent.get_identity().await.unwrap().token());
+        let held = child.list_capabilities().await.unwrap();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].id, capability.id);
+        let result: Result<serde_json::Value> = child.access_with_capability(&held[0]).await;
+        assert!(result.is_ok());
+
+        // Expired capabilities are dropped on import
+        let mut expired = test_capability(Domain::Ssh);
+        expired.expires_at = chrono::Utc::now() - chrono::Duration::seconds(1);
+        let contents = BundleContents { capabilities: vec![capability, expired], identity: None };
+        let stale = seal_bundle(&contents, &key, false).unwrap();
+        let fresh = mock_client(Config::default(), Arc::new(NullSink)).await;
+        assert_eq!(fresh.import_bundle(&stale, &key, trusted).await.unwrap(), 1);
+        assert!(fresh.get_identity().await.is_none());
+
+        let err = fresh.import_bundle(&bundle, &[1u8; crate::crypto::SEAL_KEY_LEN], trusted).await.unwrap_err();
+        assert!(matches!(err, VaultError::Crypto(crate::error::CryptoError::SignatureVerificationFailed)));
+    }
+
+    #[tokio::test]
+    async fn test_bundle_import_verifies_capability_signatures() {
+        use base64::Engine;
+
+        let (parent, public_key) = Client::local_dev_with_config(Config::default()).await.unwrap();
+        let capability = parent
+            .request_capability(Domain::Database, Action::Read, "users", &Context::builder().build().unwrap(), Duration::from_secs(60))
+            .await
+            .unwrap();
+        let key = [42u8; crate::crypto::SEAL_KEY_LEN];
+        let mut config = Config::default();
+        config.verification_key = Some(base64::engine::general_purpose::STANDARD.encode(&public_key));
+
+        let bundle = parent.export_bundle(&key, BundleOptions::default()).await.unwrap();
+        let child = mock_client(config.clone(), Arc::new(NullSink)).await;
+        assert_eq!(child.import_bundle(&bundle, &key, ImportOptions::default()).await.unwrap(), 1);
+
+        // Without a key to check them against, capabilities need explicit trust
+        let untrusting = mock_client(Config::default(), Arc::new(NullSink)).await;
+        let err = untrusting.import_bundle(&bundle, &key, ImportOptions::default()).await.unwrap_err();
+        assert!(matches!(err, VaultError::Config(crate::error::ConfigError::MissingField(field)) if field == "verification_key"));
+        assert!(untrusting.capabilities.read().await.is_empty());
+
+        // A capability widened by whoever holds the bundle key is refused, with its siblings
+        let mut widened = capability.clone();
+        widened.action = Action::Admin;
+        let contents = BundleContents { capabilities: vec![capability, widened], identity: None };
+        let forged = seal_bundle(&contents, &key, false).unwrap();
+        let child = mock_client(config, Arc::new(NullSink)).await;
+        let err = child.import_bundle(&forged, &key, ImportOptions::default()).await.unwrap_err();
+        assert!(matches!(err, VaultError::Crypto(crate::error::CryptoError::SignatureVerificationFailed)));
+        assert!(child.capabilities.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_reason_reaches_transport_and_audit() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let sink = Arc::new(CollectingSink::default());
+        let client = mock_client_with_transport(Config::default(), transport.clone(), sink.clone()).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        let compromised = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        client.revoke_capability_with_reason(compromised.id, RevokeReason::Compromised).await.unwrap();
+
+        let plain = client
+            .request_capability(Domain::Database, Action::Read, "orders", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        client.revoke_capability(plain.id).await.unwrap();
+
+        assert_eq!(transport.revoke_reasons(), vec![
+            (compromised.id, RevokeReason::Compromised),
+            (plain.id, RevokeReason::Unspecified),
+        ]);
+
+        let events = sink.events.lock().unwrap();
+        let revokes: Vec<_> = events.iter().filter(|event| event.operation == "revoke").collect();
+        assert_eq!(revokes.len(), 2);
+        assert_eq!(revokes[0].reason.as_deref(), Some("compromised"));
+        assert_eq!(revokes[1].reason, None);
+    }
+
+    #[tokio::test]
+    async fn test_access_rerequests_revoked_capability_once() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let mut config = Config::default();
+        config.rerequest_on_revoked = true;
+        let client = mock_client_with_transport(config, transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        transport.revoke_silently(capability.id);
+
+        let response: serde_json::Value = client.access_with_capability(&capability).await.unwrap();
+        let replacement_id: uuid::Uuid = serde_json::from_value(response["capability_id"].clone()).unwrap();
+        assert_ne!(replacement_id, capability.id);
+        assert_eq!(transport.issued_count(), 2);
+        let held = client.list_capabilities().await.unwrap();
+        assert_eq!(held.iter().map(|cap| cap.id).collect::<Vec<_>>(), vec![replacement_id]);
+
+        // The stale capability keeps resolving to its replacement
+        let response: serde_json::Value = client.access_with_capability(&capability).await.unwrap();
+        assert_eq!(response["capability_id"], serde_json::json!(replacement_id));
+        assert_eq!(transport.issued_count(), 2);
+
+        // ... which is itself replaced when revoked
+        transport.revoke_silently(replacement_id);
+        let response: serde_json::Value = client.access_with_capability(&capability).await.unwrap();
+        assert_ne!(response["capability_id"], serde_json::json!(replacement_id));
+        assert_eq!(transport.issued_count(), 3);
+
+        // Capabilities failing the local expiry check are not replaced
+        let mut expiring = client
+            .request_capability(Domain::Database, Action::Read, "orders", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        expiring.expires_at = chrono::Utc::now() - chrono::Duration::seconds(1);
+        let err = client.access_with_capability::<serde_json::Value>(&expiring).await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::Capability(crate::error::CapabilityError::Expired(_))), "{:?}", err);
+        assert_eq!(transport.issued_count(), 4);
+
+        // Without the policy the revocation surfaces
+        let client = mock_client_with_transport(Config::default(), transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        transport.revoke_silently(capability.id);
+        let err = client.access_with_capability::<serde_json::Value>(&capability).await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::Capability(crate::error::CapabilityError::Revoked(_))), "{:?}", err);
+        assert_eq!(transport.issued_count(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_builder_with_mock_transport_and_identity() {
+        let sink = Arc::new(CollectingSink::default());
+        let client = Client::builder()
+            .transport(Arc::new(crate::transport::MockTransport::new()))
+            .identity(Identity::new("preset-token".to_string()))
+            .audit_sink(sink.clone())
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(client.get_identity().await.unwrap().token(), "preset-token");
+        let context = Context::builder().build().unwrap();
+        client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(sink.events.lock().unwrap().len(), 1);
+
+        // Without a transport the config is validated before connecting
+        let mut config = Config::default();
+        config.endpoint = String::new();
+        assert!(Client::builder().config(config).build().await.is_err());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_request_timeout_override() {
+        let transport = Arc::new(crate::transport::MockTransport::new().with_timeouts(crate::config::TimeoutConfig {
+            connect: Duration::from_secs(1),
+            request: Duration::from_millis(100),
+            capability: Duration::from_secs(300),
+        }));
+        let client = mock_client_with_transport(fast_retry_config(0), transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+        let request = |options| {
+            client.request_capability_with_options(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60), options)
+        };
+
+        // A longer override lets a slow call through
+        transport.set_latency(Duration::ZERO, Duration::from_millis(150));
+        let err = request(RequestOptions::default()).await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::Timeout(d) if *d == Duration::from_millis(100)));
+        let capability = request(RequestOptions::with_timeout(Duration::from_millis(500))).await.unwrap();
+        let _: serde_json::Value = client
+            .access_with_options(&capability, RequestOptions::with_timeout(Duration::from_millis(500)))
+            .await
+            .unwrap();
+
+        // A shorter override fails a call the default would allow
+        transport.set_latency(Duration::ZERO, Duration::from_millis(50));
+        assert!(request(RequestOptions::default()).await.is_ok());
+        let err = client
+            .access_with_options::<serde_json::Value>(&capability, RequestOptions::with_timeout(Duration::from_millis(20)))
+            .await
+            .unwrap_err();
+        assert!(matches!(err.root(), VaultError::Timeout(d) if *d == Duration::from_millis(20)));
+
+        assert!(matches!(
+            request(RequestOptions::with_timeout(Duration::ZERO)).await,
+            Err(VaultError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_access_error_carries_context() {
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(fast_retry_config(0), transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        transport.fail_next(1);
+        let err = client.access_with_capability::<serde_json::Value>(&capability).await.unwrap_err();
+        let error_context = err.context().expect("access errors carry context");
+        assert_eq!(error_context.capability_id, Some(capability.id));
+        assert_eq!(error_context.target.as_deref(), Some("users"));
+        assert_eq!(error_context.domain, Some(Domain::Database));
+        assert_eq!(error_context.action, Some(Action::Read));
+        assert_eq!(error_context.endpoint.as_deref(), Some("http://localhost:8200"));
+        assert!(matches!(err.root(), VaultError::Transport(_)));
+        assert!(err.to_string().contains(&capability.id.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_watch_capability_events() {
+        use futures::StreamExt;
+
+        let transport = Arc::new(crate::transport::MockTransport::new());
+        let client = mock_client_with_transport(Config::default(), transport.clone(), Arc::new(NullSink)).await;
+        client.set_identity(Identity::new("test-token".to_string())).await.unwrap();
+        let context = Context::builder().build().unwrap();
+        async fn next<S: futures::Stream<Item = CapabilityEvent> + Unpin>(events: &mut S) -> Option<CapabilityEvent> {
+            tokio::time::timeout(Duration::from_secs(1), events.next()).await.expect("watch event")
+        }
+
+        // Revoked server-side, out from under the client
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "users", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let mut events = Box::pin(client.watch_capability_every(capability.id, Duration::from_millis(10)));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        client.refresh_capability(capability.id, Duration::from_secs(120)).await.unwrap();
+        let event = next(&mut events).await;
+        assert!(matches!(event, Some(CapabilityEvent::Refreshed(ref refreshed)) if refreshed.expires_at > capability.expires_at));
+
+        transport.revoke_capability(capability.id).await.unwrap();
+        assert!(matches!(next(&mut events).await, Some(CapabilityEvent::Revoked)));
+        assert!(next(&mut events).await.is_none());
+        assert!(client.list_capabilities().await.unwrap().is_empty());
+
+        // Revoked through the client
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "orders", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let mut events = Box::pin(client.watch_capability_every(capability.id, Duration::from_millis(10)));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        client.revoke_capability(capability.id).await.unwrap();
+        assert!(matches!(next(&mut events).await, Some(CapabilityEvent::Revoked)));
+
+        // Evicted locally but live on the server: nothing until the server revokes it
+        let capability = client
+            .request_capability(Domain::Database, Action::Read, "audit", &context, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let mut events = Box::pin(client.watch_capability_every(capability.id, Duration::from_millis(10)));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        client.capabilities.write().await.remove(&capability.id);
+        assert!(tokio::time::timeout(Duration::from_millis(50), events.next()).await.is_err());
+        transport.revoke_capability(capability.id).await.unwrap();
+        assert!(matches!(next(&mut events).await, Some(CapabilityEvent::Revoked)));
+    }
+
+    #[tokio::test]
+    async fn test_apply_config() {
+        let mut config = Config::default();
+        config.auth.method = AuthMethod::None;
+        config.server_hints.fetch = false;
+        let client = Client::new(config.clone()).await.unwrap();
+        let transport = client.transport();
+
+        // Retry settings apply without touching the transport
+        config.retry.max_retries = 7;
+        client.apply_config(config.clone()).await.unwrap();
+        assert_eq!(client.config().retry.max_retries, 7);
+        assert!(Arc::ptr_eq(&client.transport(), &transport));
+
+        // So do request and capability timeouts
+        config.timeouts.request = Duration::from_secs(3);
+        config.timeouts.capability = Duration::from_secs(120);
+        client.apply_config(config.clone()).await.unwrap();
+        assert_eq!(client.config().timeouts.request, Duration::from_secs(3));
+        assert!(Arc::ptr_eq(&client.transport(), &transport));
+
+        // A new endpoint gets a new transport
+        config.endpoint = "http://127.0.0.1:8300".to_string();
+        client.apply_config(config.clone()).await.unwrap();
+        assert_eq!(client.config().endpoint, "http://127.0.0.1:8300");
+        assert!(!Arc::ptr_eq(&client.transport(), &transport));
+
+        // Invalid configs leave the current one in place
+        let mut invalid = config.clone();
+        invalid.endpoint = String::new();
+        assert!(client.apply_config(invalid).await.is_err());
+        assert_eq!(client.config().endpoint, "http://127.0.0.1:8300");
+
+        // Injected transports cannot be rebuilt
+        let mocked = mock_client(config.clone(), Arc::new(NullSink)).await;
+        config.endpoint = "http://localhost:8400".to_string();
+        assert!(mocked.apply_config(config).await.is_err());
+    }
+}
@@ -1,3 +1,6 @@
+pub mod bundle;
 pub mod client;
 
-pub use client::Client;
\ No newline at end of file
+pub use bundle::{BundleOptions, ImportOptions};
+
+pub use client::{AccessResponse, CapabilityEvent, CapabilityGuard, Client, ClientBuilder, Feature, HealthDetail, HealthFailure, HealthStatus, HealthStatusType, HealthThresholds, NearExpiryCallback, PolicyDecision, RateLimitHint, RequestOptions, RevocationReceipt, RevocationStatus, RevokeReason, ServerHints, TtlHint, VaultStatus};
\ No newline at end of file
@@ -6,10 +6,13 @@
 //! 3. Configuration files
 //! 4. Default values
 
+use crate::capability::{Action, Domain};
 use crate::error::{ConfigError, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::time::Duration;
+use zeroize::Zeroizing;
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,16 +20,22 @@ pub struct Config {
     /// Vault endpoint URL
     pub endpoint: String,
     
-    /// Transport type (http, unix, mtls)
+    /// Enterprise namespace sent with every request; unset means the root namespace
+    #[serde(default)]
+    pub namespace: Option<String>,
+    
+    /// Transport type (http, unix, mtls, grpc)
     pub transport: TransportType,
     
     /// Authentication configuration
     pub auth: AuthConfig,
     
     /// Timeout configuration
+    #[serde(default)]
     pub timeouts: TimeoutConfig,
     
     /// Retry configuration
+    #[serde(default)]
     pub retry: RetryConfig,
     
     /// TLS configuration
@@ -37,10 +46,88 @@ pub struct Config {
     
     /// Cache configuration (disabled by default for security)
     pub cache: Option<CacheConfig>,
+    
+    /// Domains for which every access must carry a non-empty reason
+    #[serde(default)]
+    pub require_access_reason_for: HashSet<Domain>,
+    
+    /// Domain and action pairs whose requests must carry a non-empty justification
+    #[serde(default)]
+    pub require_justification_for: HashSet<(Domain, Action)>,
+    
+    /// TTL bounds for individual domains, overriding the global ones
+    ///
+    /// Domains not listed fall back to `timeouts.capability` and the
+    /// request validator's global bounds.
+    #[serde(default)]
+    pub domain_ttls: HashMap<Domain, DomainTtlPolicy>,
+    
+    /// Handling of server-pushed client configuration hints
+    #[serde(default)]
+    pub server_hints: ServerHintsConfig,
+
+    /// Explicit HTTP proxy
+    ///
+    /// When set, it replaces any `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY`
+    /// environment settings; when unset, those variables still apply.
+    #[serde(default)]
+    pub proxy: Option<ProxyConfig>,
+
+    /// Longest a capability may live in total, from issue to its last expiry
+    ///
+    /// Refreshes that would keep a cached capability alive beyond this are
+    /// rejected client-side. Unset means only the per-request TTL bounds apply.
+    #[serde(default, with = "humantime_serde")]
+    pub max_capability_lifetime: Option<Duration>,
+
+    /// Issuer's Ed25519 public key (raw 32 bytes, standard base64)
+    ///
+    /// When set, capabilities that arrive without a server round trip, such
+    /// as those in an imported bundle, must carry a valid signature under it.
+    #[serde(default)]
+    pub verification_key: Option<String>,
+
+    /// Least lifetime a capability must have left to be used for an access
+    ///
+    /// Accesses with less remaining fail early with
+    /// `CapabilityError::Expired` rather than racing expiry on the wire.
+    /// Zero (the default) only rejects capabilities that have already expired.
+    #[serde(default, with = "humantime_serde")]
+    pub min_remaining_ttl: Duration,
+
+    /// Compression of request and response bodies (off when unset)
+    #[serde(default)]
+    pub compression: Option<CompressionConfig>,
+
+    /// Resend requests refused by an HA standby node to the active node
+    ///
+    /// Covers 307/308 redirects and standby error bodies. Requests are only
+    /// resent, credentials included, to the endpoint's own origin or one of
+    /// `cluster_addresses`. When disabled, standby error bodies are reported
+    /// as errors and 307/308 are handled like other redirects, which are
+    /// followed within the origin of the request only.
+    #[serde(default = "default_follow_redirects_to_active")]
+    pub follow_redirects_to_active: bool,
+
+    /// Base URLs of the other nodes in the HA cluster, e.g. `https://vault-2:8200`
+    ///
+    /// A standby may only send requests on to these nodes; any other active
+    /// node it names is refused with `TransportError::Protocol`.
+    #[serde(default)]
+    pub cluster_addresses: Vec<String>,
+
+    /// Replace a capability that the server reports revoked or expired, once
+    ///
+    /// `Client::access_with_capability` then re-requests an equivalent
+    /// capability from the request that originally issued it and retries the
+    /// access with it. Only capabilities requested under the client's own
+    /// identity are replaced.
+    #[serde(default)]
+    pub rerequest_on_revoked: bool,
 }
 
 /// Transport type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TransportType {
     /// HTTP/HTTPS transport
@@ -49,10 +136,12 @@ pub enum TransportType {
     Unix,
     /// mTLS transport
     Mtls,
+    /// gRPC transport
+    Grpc,
 }
 
 /// Authentication configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AuthConfig {
     /// Authentication method
     pub method: AuthMethod,
@@ -60,6 +149,12 @@ pub struct AuthConfig {
     /// Token file path (if applicable)
     pub token_file: Option<PathBuf>,
     
+    /// Token given inline, typically from `VAULT_TOKEN`
+    ///
+    /// Used only when `token_file` is unset. Never serialized.
+    #[serde(default, skip_serializing)]
+    pub token: Option<Zeroizing<String>>,
+    
     /// Certificate file path (if applicable)
     pub cert_file: Option<PathBuf>,
     
@@ -68,10 +163,65 @@ pub struct AuthConfig {
     
     /// CA certificate file path
     pub ca_file: Option<PathBuf>,
+    
+    /// File holding a key for signing request bodies with HMAC-SHA256
+    #[serde(default)]
+    pub hmac_key_file: Option<PathBuf>,
+    
+    /// Refresh a workload token this long before it expires
+    #[serde(default = "default_token_refresh_skew", with = "humantime_serde")]
+    pub token_refresh_skew: Duration,
+
+    /// What to do when a requested TTL outlives the identity token
+    #[serde(default)]
+    pub ttl_policy: TtlPolicy,
+}
+
+impl std::fmt::Debug for AuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthConfig")
+            .field("method", &self.method)
+            .field("token_file", &self.token_file)
+            .field("token", &self.token.as_ref().map(|_| "***"))
+            .field("cert_file", &self.cert_file)
+            .field("key_file", &self.key_file)
+            .field("ca_file", &self.ca_file)
+            .field("hmac_key_file", &self.hmac_key_file)
+            .field("token_refresh_skew", &self.token_refresh_skew)
+            .field("ttl_policy", &self.ttl_policy)
+            .finish()
+    }
+}
+
+/// Handling of capability TTLs longer than the identity's remaining lifetime
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TtlPolicy {
+    /// Shorten the TTL to end when the identity token expires
+    #[default]
+    Clamp,
+    /// Refuse the request
+    Reject,
+}
+
+/// TTL bounds applied to capability requests in one domain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainTtlPolicy {
+    /// TTL suggested by `Client::default_ttl` for this domain
+    #[serde(default, with = "humantime_serde")]
+    pub default_ttl: Option<Duration>,
+    
+    /// Longest TTL a request in this domain may ask for
+    #[serde(with = "humantime_serde")]
+    pub max_ttl: Duration,
+    
+    /// Whether longer requests are shortened to `max_ttl` or refused
+    #[serde(default)]
+    pub on_exceed: TtlPolicy,
 }
 
 /// Authentication method
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AuthMethod {
     /// Token-based authentication
@@ -85,36 +235,76 @@ pub enum AuthMethod {
 }
 
 /// Timeout configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Durations are written in human-readable form, e.g. `"10s"`, `"1m"`, `"250ms"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TimeoutConfig {
     /// Connection timeout
+    #[serde(with = "humantime_serde")]
     pub connect: Duration,
     
     /// Request timeout
+    #[serde(with = "humantime_serde")]
     pub request: Duration,
     
     /// Capability timeout
+    #[serde(with = "humantime_serde")]
     pub capability: Duration,
 }
 
 /// Retry configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RetryConfig {
     /// Maximum number of retries
     pub max_retries: u32,
     
     /// Base delay between retries
+    #[serde(with = "humantime_serde")]
     pub base_delay: Duration,
     
     /// Maximum delay between retries
+    #[serde(with = "humantime_serde")]
     pub max_delay: Duration,
     
     /// Exponential backoff multiplier
     pub backoff_multiplier: f64,
+    
+    /// How retry delays are randomized
+    pub jitter: JitterStrategy,
+}
+
+/// Randomization applied to retry delays
+///
+/// `backoff` below is [`RetryConfig::backoff_delay`] for the attempt and
+/// `random(a, b)` a uniform draw from `[a, b)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JitterStrategy {
+    /// `backoff`, unrandomized
+    None,
+    /// `random(0, backoff)`
+    #[default]
+    Full,
+    /// `backoff / 2 + random(0, backoff / 2)`
+    Equal,
+    /// `min(max_delay, random(base_delay, previous * 3))`, where `previous`
+    /// is the last delay and starts at `base_delay`
+    Decorrelated,
+}
+
+/// Retry delays for one operation, see [`RetryConfig::backoff`]
+#[derive(Debug, Clone)]
+pub struct Backoff<R = rand::rngs::StdRng> {
+    retry: RetryConfig,
+    rng: R,
+    attempt: u32,
+    previous: Duration,
 }
 
 /// TLS configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TlsConfig {
     /// Verify server certificate
     pub verify_cert: bool,
@@ -122,16 +312,97 @@ pub struct TlsConfig {
     /// Server name indication
     pub server_name: Option<String>,
     
-    /// Minimum TLS version
+    /// Minimum TLS version, one of [`TLS_VERSIONS`]
     pub min_version: Option<String>,
     
-    /// Maximum TLS version
+    /// Maximum TLS version, one of [`TLS_VERSIONS`]
     pub max_version: Option<String>,
     
-    /// Cipher suites
+    /// Cipher suites, named as in [`TLS_CIPHER_SUITES`]
     pub cipher_suites: Option<Vec<String>>,
 }
 
+/// How long a watched config file must be quiet before it is re-read
+///
+/// Editors and deploy tools often write a file in several steps; waiting
+/// for the burst to end avoids loading a half-written file.
+const CONFIG_SETTLE_DELAY: Duration = Duration::from_millis(50);
+
+/// Warn about client certificates expiring within this window
+const CERT_EXPIRY_WARNING: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// TLS versions accepted in `tls.min_version` and `tls.max_version`, oldest first
+pub const TLS_VERSIONS: &[&str] = &["1.0", "1.1", "1.2", "1.3"];
+
+/// Cipher suites accepted in `tls.cipher_suites`, using rustls naming
+pub const TLS_CIPHER_SUITES: &[&str] = &[
+    "TLS13_AES_256_GCM_SHA384",
+    "TLS13_AES_128_GCM_SHA256",
+    "TLS13_CHACHA20_POLY1305_SHA256",
+    "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+    "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+    "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+    "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+    "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+    "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+];
+
+/// Check a trimmed token is a bearer token as RFC 6750 defines it
+///
+/// Catches a token file or `VAULT_TOKEN` holding something else, such as a
+/// whole JSON response or two tokens on separate lines.
+pub(crate) fn check_bearer_token(token: &str) -> std::result::Result<(), &'static str> {
+    if token.is_empty() {
+        return Err("must not be empty");
+    }
+    let body = token.trim_end_matches('=');
+    if body.is_empty() || !body.bytes().all(|b| b.is_ascii_alphanumeric() || b"-._~+/".contains(&b)) {
+        return Err("must be a single bearer token of letters, digits and -._~+/, optionally padded with =");
+    }
+    Ok(())
+}
+
+/// Position of a TLS version string in [`TLS_VERSIONS`]
+fn tls_version_rank(field: &str, version: &str) -> Result<usize> {
+    TLS_VERSIONS.iter().position(|known| *known == version.trim()).ok_or_else(|| {
+        ConfigError::InvalidValue(
+            field.to_string(),
+            format!("unsupported TLS version '{}', expected one of {}", version, TLS_VERSIONS.join(", ")),
+        )
+        .into()
+    })
+}
+
+/// HTTP proxy configuration
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// Proxy URL, e.g. `http://proxy.internal:3128`
+    pub url: String,
+
+    /// Basic-auth username
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Basic-auth password
+    #[serde(default)]
+    pub password: Option<String>,
+
+    /// Hosts, domains and CIDR ranges reached directly, bypassing the proxy
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+impl std::fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyConfig")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .field("no_proxy", &self.no_proxy)
+            .finish()
+    }
+}
+
 /// Logging configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoggingConfig {
@@ -146,7 +417,7 @@ pub struct LoggingConfig {
 }
 
 /// Log format
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LogFormat {
     /// JSON format
@@ -156,6 +427,13 @@ pub enum LogFormat {
 }
 
 /// Cache configuration (security note: disabled by default)
+///
+/// `max_size` bounds the capability cache. When `enabled`, read and list
+/// access responses are also kept in memory for up to `ttl` and served
+/// without contacting the server. That trades freshness and server-side
+/// visibility for latency: a secret rotated or a capability revoked on the
+/// server can still be served from the cache until the entry expires, and
+/// cache hits are audited locally only. Nothing is written to disk.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheConfig {
     /// Enable in-memory cache
@@ -165,13 +443,214 @@ pub struct CacheConfig {
     pub max_size: usize,
     
     /// Cache TTL
+    #[serde(with = "humantime_serde")]
     pub ttl: Duration,
 }
 
+/// Body compression for the HTTP transport
+///
+/// Request bodies of at least `min_size` bytes are sent compressed with
+/// `algorithm`; smaller ones are not worth the overhead. Responses are
+/// requested with `Accept-Encoding: gzip` and decompressed transparently.
+/// Requires the `compression` feature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompressionConfig {
+    /// Encoding of compressed request bodies
+    pub algorithm: CompressionAlgorithm,
+    
+    /// Smallest request body to compress, in bytes
+    pub min_size: usize,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: CompressionAlgorithm::default(),
+            min_size: 1024,
+        }
+    }
+}
+
+/// Request body compression algorithm
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    /// gzip
+    #[default]
+    Gzip,
+}
+
+impl CompressionAlgorithm {
+    /// `Content-Encoding` value for bodies compressed with this algorithm
+    pub fn content_encoding(&self) -> &'static str {
+        match self {
+            CompressionAlgorithm::Gzip => "gzip",
+        }
+    }
+}
+
+/// Server hint configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerHintsConfig {
+    /// Fetch hints from the server at all
+    ///
+    /// On by default, fetching them when the client is built. Turn off to
+    /// spare short-lived clients the extra round trip.
+    pub fetch: bool,
+
+    /// Apply hints (default TTLs, domain catalog, client-side quota) automatically
+    pub auto_apply: bool,
+    
+    /// How often hints are re-fetched (`None` fetches only at startup)
+    #[serde(with = "humantime_serde")]
+    pub refresh_interval: Option<Duration>,
+}
+
+/// Configuration file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// TOML
+    Toml,
+    /// YAML
+    Yaml,
+    /// JSON
+    Json,
+}
+
+impl ConfigFormat {
+    /// Detect the format from a file extension
+    pub fn from_path(path: &std::path::Path) -> Result<Self> {
+        let extension = path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase);
+
+        match extension.as_deref() {
+            Some("toml") => Ok(ConfigFormat::Toml),
+            Some("yaml") | Some("yml") => Ok(ConfigFormat::Yaml),
+            Some("json") => Ok(ConfigFormat::Json),
+            _ => Err(ConfigError::ParseError(format!(
+                "unsupported config file {} (supported: .toml, .yaml, .yml, .json)",
+                path.display()
+            )).into()),
+        }
+    }
+}
+
+/// Partial configuration holding only the values a source explicitly set
+///
+/// Used to layer environment variables over a file without guessing which
+/// fields were meant as overrides.
+#[derive(Clone, Default)]
+pub struct ConfigOverlay {
+    /// Vault endpoint URL
+    pub endpoint: Option<String>,
+    
+    /// Transport type
+    pub transport: Option<TransportType>,
+    
+    /// Authentication method
+    pub auth_method: Option<AuthMethod>,
+    
+    /// Token file path
+    pub token_file: Option<PathBuf>,
+    
+    /// Inline token
+    pub token: Option<Zeroizing<String>>,
+    
+    /// Certificate file path
+    pub cert_file: Option<PathBuf>,
+    
+    /// Private key file path
+    pub key_file: Option<PathBuf>,
+    
+    /// CA certificate file path
+    pub ca_file: Option<PathBuf>,
+    
+    /// Log level
+    pub log_level: Option<String>,
+    
+    /// Enterprise namespace
+    pub namespace: Option<String>,
+}
+
+impl std::fmt::Debug for ConfigOverlay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigOverlay")
+            .field("endpoint", &self.endpoint)
+            .field("transport", &self.transport)
+            .field("auth_method", &self.auth_method)
+            .field("token_file", &self.token_file)
+            .field("token", &self.token.as_ref().map(|_| "***"))
+            .field("cert_file", &self.cert_file)
+            .field("key_file", &self.key_file)
+            .field("ca_file", &self.ca_file)
+            .field("log_level", &self.log_level)
+            .field("namespace", &self.namespace)
+            .finish()
+    }
+}
+
+impl ConfigOverlay {
+    /// Collect the `VAULT_*` environment variables that are set
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|key| std::env::var(key).ok())
+    }
+
+    /// Collect overrides from a variable lookup (e.g. the environment)
+    pub fn from_vars<F>(lookup: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let transport = match lookup("VAULT_TRANSPORT") {
+            Some(transport) => Some(match transport.to_lowercase().as_str() {
+                "http" => TransportType::Http,
+                "unix" => TransportType::Unix,
+                "mtls" => TransportType::Mtls,
+                "grpc" => TransportType::Grpc,
+                _ => return Err(ConfigError::InvalidValue(
+                    "transport".to_string(),
+                    transport,
+                ).into()),
+            }),
+            None => None,
+        };
+
+        let auth_method = match lookup("VAULT_AUTH_METHOD") {
+            Some(auth_method) => Some(match auth_method.to_lowercase().as_str() {
+                "token" => AuthMethod::Token,
+                "certificate" => AuthMethod::Certificate,
+                "workload" => AuthMethod::Workload,
+                "none" => AuthMethod::None,
+                _ => return Err(ConfigError::InvalidValue(
+                    "auth_method".to_string(),
+                    auth_method,
+                ).into()),
+            }),
+            None => None,
+        };
+
+        Ok(Self {
+            endpoint: lookup("VAULT_ENDPOINT"),
+            transport,
+            auth_method,
+            token_file: lookup("VAULT_TOKEN_FILE").map(PathBuf::from),
+            token: lookup("VAULT_TOKEN").map(Zeroizing::new),
+            cert_file: lookup("VAULT_CERT_FILE").map(PathBuf::from),
+            key_file: lookup("VAULT_KEY_FILE").map(PathBuf::from),
+            ca_file: lookup("VAULT_CA_FILE").map(PathBuf::from),
+            log_level: lookup("VAULT_LOG_LEVEL"),
+            namespace: lookup("VAULT_NAMESPACE"),
+        })
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             endpoint: "http://localhost:8200".to_string(),
+            namespace: None,
             transport: TransportType::Http,
             auth: AuthConfig::default(),
             timeouts: TimeoutConfig::default(),
@@ -179,6 +658,28 @@ impl Default for Config {
             tls: None,
             logging: LoggingConfig::default(),
             cache: None, // Disabled by default for security
+            require_access_reason_for: HashSet::new(),
+            require_justification_for: HashSet::new(),
+            domain_ttls: HashMap::new(),
+            server_hints: ServerHintsConfig::default(),
+            proxy: None,
+            max_capability_lifetime: None,
+            verification_key: None,
+            min_remaining_ttl: Duration::ZERO,
+            compression: None,
+            follow_redirects_to_active: true,
+            cluster_addresses: Vec::new(),
+            rerequest_on_revoked: false,
+        }
+    }
+}
+
+impl Default for ServerHintsConfig {
+    fn default() -> Self {
+        Self {
+            fetch: true,
+            auto_apply: false,
+            refresh_interval: Some(Duration::from_secs(300)),
         }
     }
 }
@@ -188,13 +689,25 @@ impl Default for AuthConfig {
         Self {
             method: AuthMethod::Token,
             token_file: None,
+            token: None,
             cert_file: None,
             key_file: None,
             ca_file: None,
+            hmac_key_file: None,
+            token_refresh_skew: default_token_refresh_skew(),
+            ttl_policy: TtlPolicy::default(),
         }
     }
 }
 
+fn default_token_refresh_skew() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_follow_redirects_to_active() -> bool {
+    true
+}
+
 impl Default for TimeoutConfig {
     fn default() -> Self {
         Self {
@@ -212,8 +725,67 @@ impl Default for RetryConfig {
             base_delay: Duration::from_millis(100),
             max_delay: Duration::from_secs(30),
             backoff_multiplier: 2.0,
+            jitter: JitterStrategy::default(),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Backoff delay before retry number `attempt` (0-based), without jitter
+    ///
+    /// Computed as `base_delay * backoff_multiplier^attempt`, capped at `max_delay`.
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let factor = self.backoff_multiplier.max(1.0).powi(attempt.min(i32::MAX as u32) as i32);
+        let delay = self.base_delay.as_secs_f64() * factor;
+        Duration::from_secs_f64(delay.min(self.max_delay.as_secs_f64()))
+    }
+
+    /// Delays to sleep between the attempts of one operation, with jitter
+    pub fn backoff(&self) -> Backoff {
+        use rand::SeedableRng;
+
+        self.backoff_with_rng(rand::rngs::StdRng::from_entropy())
+    }
+
+    /// Like [`RetryConfig::backoff`], drawing randomness from `rng`
+    pub fn backoff_with_rng<R: rand::RngCore>(&self, rng: R) -> Backoff<R> {
+        Backoff {
+            retry: self.clone(),
+            rng,
+            attempt: 0,
+            previous: self.base_delay,
+        }
+    }
+}
+
+impl<R: rand::RngCore> Backoff<R> {
+    /// Delay before the next retry
+    pub fn next_delay(&mut self) -> Duration {
+        let backoff = self.retry.backoff_delay(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+
+        match self.retry.jitter {
+            JitterStrategy::None => backoff,
+            JitterStrategy::Full => self.random_below(backoff),
+            JitterStrategy::Equal => backoff / 2 + self.random_below(backoff - backoff / 2),
+            JitterStrategy::Decorrelated => {
+                let base = self.retry.base_delay;
+                let upper = self.previous.saturating_mul(3).max(base);
+                let delay = (base + self.random_below(upper - base)).min(self.retry.max_delay);
+                self.previous = delay;
+                delay
+            }
         }
     }
+
+    /// Uniform draw from `[0, limit)`
+    fn random_below(&mut self, limit: Duration) -> Duration {
+        // 53 random bits as a fraction of 2^53, as for a uniform f64,
+        // applied in integer nanoseconds so results are exact
+        let fraction = (self.rng.next_u64() >> 11) as u128;
+        let nanos = (limit.as_nanos() * fraction) >> 53;
+        Duration::from_nanos(nanos.min(u64::MAX as u128) as u64)
+    }
 }
 
 impl Default for LoggingConfig {
@@ -226,116 +798,272 @@ impl Default for LoggingConfig {
     }
 }
 
+impl LoggingConfig {
+    /// Install a global `tracing` subscriber for this configuration
+    ///
+    /// `level` is an `EnvFilter` directive such as `info` or
+    /// `aether_vault=debug`. Client operation spans are written in `format`
+    /// when they close, with their recorded outcome. Fails if a global
+    /// subscriber is already installed; applications bringing their own
+    /// subscriber receive the same spans without calling this.
+    pub fn init_tracing(&self) -> Result<()> {
+        use tracing_subscriber::fmt::format::FmtSpan;
+
+        let filter = self.env_filter()?;
+        let builder = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_span_events(FmtSpan::CLOSE);
+        let installed = match self.format {
+            LogFormat::Json => builder.json().try_init(),
+            LogFormat::Text => builder.try_init(),
+        };
+        installed.map_err(|e| ConfigError::InvalidValue("logging".to_string(), e.to_string()).into())
+    }
+
+    /// Parse `level` as a tracing filter
+    fn env_filter(&self) -> Result<tracing_subscriber::EnvFilter> {
+        tracing_subscriber::EnvFilter::try_new(&self.level).map_err(|e| ConfigError::InvalidValue(
+            "logging.level".to_string(),
+            format!("'{}' is not a valid filter: {}", self.level, e),
+        ).into())
+    }
+}
+
 impl Config {
     /// Create configuration from environment variables
     pub fn from_env() -> Result<Self> {
         let mut config = Self::default();
+        config.merge(ConfigOverlay::from_env()?);
+        Ok(config)
+    }
 
-        // Override with environment variables
-        if let Ok(endpoint) = std::env::var("VAULT_ENDPOINT") {
-            config.endpoint = endpoint;
-        }
-
-        if let Ok(transport) = std::env::var("VAULT_TRANSPORT") {
-            config.transport = match transport.to_lowercase().as_str() {
-                "http" => TransportType::Http,
-                "unix" => TransportType::Unix,
-                "mtls" => TransportType::Mtls,
-                _ => return Err(ConfigError::InvalidValue(
-                    "transport".to_string(),
-                    transport,
-                ).into()),
-            };
-        }
+    /// Load configuration from file
+    ///
+    /// The format is chosen from the extension: `.toml`, `.yaml`/`.yml` or `.json`.
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path)?;
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::FileNotFound(e.to_string()))?;
 
-        if let Ok(auth_method) = std::env::var("VAULT_AUTH_METHOD") {
-            config.auth.method = match auth_method.to_lowercase().as_str() {
-                "token" => AuthMethod::Token,
-                "certificate" => AuthMethod::Certificate,
-                "workload" => AuthMethod::Workload,
-                "none" => AuthMethod::None,
-                _ => return Err(ConfigError::InvalidValue(
-                    "auth_method".to_string(),
-                    auth_method,
-                ).into()),
-            };
-        }
+        Self::from_str_with_format(&content, format)
+    }
 
-        if let Ok(token_file) = std::env::var("VAULT_TOKEN_FILE") {
-            config.auth.token_file = Some(PathBuf::from(token_file));
-        }
+    /// Parse configuration from a string in the given format
+    pub fn from_str_with_format(content: &str, format: ConfigFormat) -> Result<Self> {
+        let parsed = match format {
+            ConfigFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+            ConfigFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+        };
 
-        if let Ok(cert_file) = std::env::var("VAULT_CERT_FILE") {
-            config.auth.cert_file = Some(PathBuf::from(cert_file));
-        }
+        parsed.map_err(|e| ConfigError::ParseError(format!("{:?} config: {}", format, e)).into())
+    }
 
-        if let Ok(key_file) = std::env::var("VAULT_KEY_FILE") {
-            config.auth.key_file = Some(PathBuf::from(key_file));
-        }
+    /// Render the configuration as TOML, with secrets redacted
+    pub fn to_toml(&self) -> Result<String> {
+        self.to_string_with_format(ConfigFormat::Toml)
+    }
 
-        if let Ok(ca_file) = std::env::var("VAULT_CA_FILE") {
-            config.auth.ca_file = Some(PathBuf::from(ca_file));
-        }
+    /// Render the configuration as YAML, with secrets redacted
+    pub fn to_yaml(&self) -> Result<String> {
+        self.to_string_with_format(ConfigFormat::Yaml)
+    }
 
-        if let Ok(log_level) = std::env::var("VAULT_LOG_LEVEL") {
-            config.logging.level = log_level;
-        }
+    /// Render the configuration in the given format, with secrets redacted
+    ///
+    /// Meant for inspecting the effective configuration after merging file
+    /// and environment. File paths are shown as configured but never read,
+    /// and the proxy password is replaced with `***`. Durations use the
+    /// same human-readable form the parser accepts.
+    pub fn to_string_with_format(&self, format: ConfigFormat) -> Result<String> {
+        let redacted = self.redacted();
+        let rendered = match format {
+            ConfigFormat::Toml => toml::to_string(&redacted).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => serde_yaml::to_string(&redacted).map_err(|e| e.to_string()),
+            ConfigFormat::Json => serde_json::to_string_pretty(&redacted).map_err(|e| e.to_string()),
+        };
 
-        Ok(config)
+        rendered.map_err(|e| ConfigError::ParseError(format!("cannot render {:?} config: {}", format, e)).into())
     }
 
-    /// Load configuration from file
-    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| ConfigError::FileNotFound(e.to_string()))?;
-
-        toml::from_str(&content)
-            .map_err(|e| ConfigError::ParseError(e.to_string()).into())
+    /// Copy of the configuration with secret values masked
+    fn redacted(&self) -> Config {
+        let mut config = self.clone();
+        if let Some(password) = config.proxy.as_mut().and_then(|proxy| proxy.password.as_mut()) {
+            *password = "***".to_string();
+        }
+        config
     }
 
     /// Load configuration with multiple sources (file + env)
     pub fn load_with_file<P: AsRef<std::path::Path>>(file_path: P) -> Result<Self> {
         let mut config = Self::from_file(file_path)?;
         
-        // Override with environment variables
-        let env_config = Self::from_env()?;
-        config.merge(env_config);
+        // Override with environment variables that are actually set
+        config.merge(ConfigOverlay::from_env()?);
 
         Ok(config)
     }
 
-    /// Merge another configuration, with other taking precedence
-    pub fn merge(&mut self, other: Config) {
-        if other.endpoint != Config::default().endpoint {
-            self.endpoint = other.endpoint;
+    /// Load a config file and keep watching it for changes
+    ///
+    /// The file is loaded as by [`Config::load_with_file`] and validated.
+    /// A background task then watches its directory through the platform's
+    /// file notifications, so files renamed over it are seen too, and
+    /// re-reads it once writes have been quiet for 50ms. Each changed, valid
+    /// config is published on the returned channel. A changed file that
+    /// fails to parse or validate is logged and skipped, so receivers keep
+    /// the last good config. The task stops once every receiver is dropped.
+    /// Must be called inside a tokio runtime.
+    pub fn watch<P: AsRef<std::path::Path>>(path: P) -> Result<(Self, tokio::sync::watch::Receiver<Self>)> {
+        Self::watch_with(path.as_ref(), |key| std::env::var(key).ok())
+    }
+
+    /// Watch `path`, overlaying variables from `lookup`
+    fn watch_with<F>(
+        path: &std::path::Path,
+        lookup: F,
+    ) -> Result<(Self, tokio::sync::watch::Receiver<Self>)>
+    where
+        F: Fn(&str) -> Option<String> + Send + 'static,
+    {
+        let format = ConfigFormat::from_path(path)?;
+        let load = move |contents: &[u8]| -> Result<Self> {
+            let content = std::str::from_utf8(contents)
+                .map_err(|e| ConfigError::ParseError(format!("config is not UTF-8: {}", e)))?;
+            let mut config = Self::from_str_with_format(content, format)?;
+            config.merge(ConfigOverlay::from_vars(&lookup)?);
+            config.validate()?;
+            Ok(config)
+        };
+
+        let read = |path: &std::path::Path| std::fs::read(path).map_err(|e| ConfigError::FileNotFound(e.to_string()));
+        let mut last = read(path)?;
+        let config = load(&last)?;
+
+        let (changed_tx, mut changed_rx) = tokio::sync::mpsc::unbounded_channel();
+        let file_name = path.file_name().map(|name| name.to_os_string());
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let relevant = match &event {
+                Ok(event) => event.paths.iter().any(|changed| changed.file_name() == file_name.as_deref()),
+                Err(_) => true,
+            };
+            if relevant {
+                let _ = changed_tx.send(());
+            }
+        }).map_err(|e| ConfigError::InvalidValue(path.display().to_string(), format!("cannot watch: {}", e)))?;
+        let directory = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(std::path::Path::new("."));
+        watcher
+            .watch(directory, notify::RecursiveMode::NonRecursive)
+            .map_err(|e| ConfigError::InvalidValue(path.display().to_string(), format!("cannot watch: {}", e)))?;
+
+        let (tx, rx) = tokio::sync::watch::channel(config.clone());
+        let path = path.to_path_buf();
+        tokio::spawn(async move {
+            // Dropping the watcher stops the notifications
+            let _watcher = watcher;
+            loop {
+                tokio::select! {
+                    changed = changed_rx.recv() => {
+                        if changed.is_none() {
+                            break;
+                        }
+                    }
+                    _ = tx.closed() => break,
+                }
+                loop {
+                    tokio::time::sleep(CONFIG_SETTLE_DELAY).await;
+                    let mut settled = true;
+                    while changed_rx.try_recv().is_ok() {
+                        settled = false;
+                    }
+                    if settled {
+                        break;
+                    }
+                }
+
+                let contents = match read(&path) {
+                    Ok(contents) => contents,
+                    Err(e) => {
+                        tracing::warn!("Cannot re-read configuration {}: {}", path.display(), e);
+                        continue;
+                    }
+                };
+                if contents == last {
+                    continue;
+                }
+
+                match load(&contents) {
+                    Ok(config) => {
+                        tracing::info!("Reloaded configuration from {}", path.display());
+                        tx.send_replace(config);
+                    }
+                    Err(e) => tracing::error!("Keeping previous configuration, {} is invalid: {}", path.display(), e),
+                }
+                last = contents;
+            }
+        });
+
+        Ok((config, rx))
+    }
+
+    /// Apply an overlay; every field present in it takes precedence
+    pub fn merge(&mut self, overlay: ConfigOverlay) {
+        if let Some(endpoint) = overlay.endpoint {
+            self.endpoint = endpoint;
+        }
+        
+        if let Some(transport) = overlay.transport {
+            self.transport = transport;
         }
         
-        if !matches!(other.transport, TransportType::Http) {
-            self.transport = other.transport;
+        if let Some(method) = overlay.auth_method {
+            self.auth.method = method;
         }
         
-        if !matches!(other.auth.method, AuthMethod::Token) {
-            self.auth.method = other.auth.method;
+        if let Some(token_file) = overlay.token_file {
+            self.auth.token_file = Some(token_file);
         }
         
-        if other.auth.token_file.is_some() {
-            self.auth.token_file = other.auth.token_file;
+        if let Some(token) = overlay.token {
+            self.auth.token = Some(token);
         }
         
-        if other.auth.cert_file.is_some() {
-            self.auth.cert_file = other.auth.cert_file;
+        if let Some(cert_file) = overlay.cert_file {
+            self.auth.cert_file = Some(cert_file);
         }
         
-        if other.auth.key_file.is_some() {
-            self.auth.key_file = other.auth.key_file;
+        if let Some(key_file) = overlay.key_file {
+            self.auth.key_file = Some(key_file);
         }
         
-        if other.auth.ca_file.is_some() {
-            self.auth.ca_file = other.auth.ca_file;
+        if let Some(ca_file) = overlay.ca_file {
+            self.auth.ca_file = Some(ca_file);
         }
         
-        if other.logging.level != "info" {
-            self.logging.level = other.logging.level;
+        if let Some(level) = overlay.log_level {
+            self.logging.level = level;
+        }
+        
+        if let Some(namespace) = overlay.namespace {
+            self.namespace = Some(namespace);
+        }
+    }
+
+    /// Decoded `verification_key`, if set
+    pub fn verification_key_bytes(&self) -> Result<Option<Vec<u8>>> {
+        use base64::Engine;
+
+        let Some(encoded) = &self.verification_key else {
+            return Ok(None);
+        };
+        match base64::engine::general_purpose::STANDARD.decode(encoded.trim()) {
+            Ok(key) if key.len() == 32 => Ok(Some(key)),
+            _ => Err(ConfigError::InvalidValue(
+                "verification_key".to_string(),
+                "must be a base64-encoded 32-byte Ed25519 public key".to_string(),
+            ).into()),
         }
     }
 
@@ -346,6 +1074,20 @@ impl Config {
             return Err(ConfigError::MissingField("endpoint".to_string()).into());
         }
 
+        if self.namespace.as_ref().map_or(false, |namespace| namespace.trim().is_empty()) {
+            return Err(ConfigError::InvalidValue(
+                "namespace".to_string(),
+                "must not be empty when set".to_string(),
+            ).into());
+        }
+
+        if let Some(address) = self.cluster_addresses.iter().find(|address| reqwest::Url::parse(address).is_err()) {
+            return Err(ConfigError::InvalidValue(
+                "cluster_addresses".to_string(),
+                format!("{} is not a valid URL", address),
+            ).into());
+        }
+
         // Validate transport-specific requirements
         match self.transport {
             TransportType::Http => {
@@ -356,6 +1098,14 @@ impl Config {
                     ).into());
                 }
             }
+            TransportType::Grpc => {
+                if !self.endpoint.starts_with("http") {
+                    return Err(ConfigError::InvalidValue(
+                        "endpoint".to_string(),
+                        "must start with http/https for gRPC transport".to_string(),
+                    ).into());
+                }
+            }
             TransportType::Unix => {
                 if self.auth.cert_file.is_some() || self.auth.key_file.is_some() {
                     return Err(ConfigError::InvalidValue(
@@ -376,11 +1126,16 @@ impl Config {
         // Validate authentication
         match self.auth.method {
             AuthMethod::Token => {
-                if self.auth.token_file.is_none() {
+                if self.auth.token_file.is_none() && self.auth.token.is_none() {
                     return Err(ConfigError::MissingField(
-                        "token_file required for token auth".to_string(),
+                        "token_file or token (VAULT_TOKEN) required for token auth".to_string(),
                     ).into());
                 }
+                if let (None, Some(token)) = (&self.auth.token_file, &self.auth.token) {
+                    check_bearer_token(token.trim()).map_err(|reason| {
+                        ConfigError::InvalidValue("auth.token".to_string(), reason.to_string())
+                    })?;
+                }
             }
             AuthMethod::Certificate => {
                 if self.auth.cert_file.is_none() || self.auth.key_file.is_none() {
@@ -403,15 +1158,114 @@ impl Config {
             }
         }
 
+        if let Some(proxy) = &self.proxy {
+            let url = reqwest::Url::parse(&proxy.url).map_err(|e| ConfigError::InvalidValue(
+                "proxy.url".to_string(),
+                format!("'{}' is not a valid URL: {}", proxy.url, e),
+            ))?;
+            if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+                return Err(ConfigError::InvalidValue(
+                    "proxy.url".to_string(),
+                    format!("'{}' must be an http or https URL with a host", proxy.url),
+                ).into());
+            }
+            if proxy.password.is_some() && proxy.username.is_none() {
+                return Err(ConfigError::MissingField("proxy.username required with proxy.password".to_string()).into());
+            }
+        }
+
+        self.logging.env_filter()?;
+
+        self.check_client_certificate();
+
+        if let Some(tls) = &self.tls {
+            let min = tls.min_version.as_deref().map(|v| tls_version_rank("tls.min_version", v)).transpose()?;
+            let max = tls.max_version.as_deref().map(|v| tls_version_rank("tls.max_version", v)).transpose()?;
+            if let (Some(min), Some(max)) = (min, max) {
+                if min > max {
+                    return Err(ConfigError::InvalidValue(
+                        "tls.min_version".to_string(),
+                        format!("TLS {} is newer than max_version {}", TLS_VERSIONS[min], TLS_VERSIONS[max]),
+                    ).into());
+                }
+            }
+            for suite in tls.cipher_suites.iter().flatten() {
+                if !TLS_CIPHER_SUITES.contains(&suite.as_str()) {
+                    return Err(ConfigError::InvalidValue(
+                        "tls.cipher_suites".to_string(),
+                        format!("unknown cipher suite '{}'", suite),
+                    ).into());
+                }
+            }
+        }
+
+        if self.max_capability_lifetime.map_or(false, |lifetime| lifetime < crate::capability::CapabilityRequest::MIN_TTL) {
+            return Err(ConfigError::InvalidValue(
+                "max_capability_lifetime".to_string(),
+                format!("must be at least {:?}", crate::capability::CapabilityRequest::MIN_TTL),
+            ).into());
+        }
+
+        self.verification_key_bytes()?;
+
+        for (domain, policy) in &self.domain_ttls {
+            let bounds = crate::capability::CapabilityRequest::MIN_TTL..=crate::capability::CapabilityRequest::MAX_TTL;
+            if !bounds.contains(&policy.max_ttl) {
+                return Err(ConfigError::InvalidValue(
+                    format!("domain_ttls.{}.max_ttl", domain),
+                    format!("must be between {:?} and {:?}", bounds.start(), bounds.end()),
+                ).into());
+            }
+            if let Some(default_ttl) = policy.default_ttl {
+                if default_ttl < *bounds.start() || default_ttl > policy.max_ttl {
+                    return Err(ConfigError::InvalidValue(
+                        format!("domain_ttls.{}.default_ttl", domain),
+                        format!("must be between {:?} and max_ttl {:?}", bounds.start(), policy.max_ttl),
+                    ).into());
+                }
+            }
+        }
+
+        if self.cache.as_ref().map_or(false, |cache| cache.max_size == 0) {
+            return Err(ConfigError::InvalidValue(
+                "cache.max_size".to_string(),
+                "must be positive".to_string(),
+            ).into());
+        }
+
         Ok(())
     }
 
+    /// Warn when the configured client certificate has expired or expires soon
+    ///
+    /// Unreadable certificates are left for the transport to report.
+    fn check_client_certificate(&self) {
+        let Some(cert_file) = &self.auth.cert_file else { return };
+        let Ok(pem) = std::fs::read(cert_file) else { return };
+
+        match crate::crypto::parse_certificate(&pem) {
+            Ok(info) if info.is_expired() => tracing::warn!(
+                "Client certificate {} expired at {}",
+                cert_file.display(),
+                info.not_after
+            ),
+            Ok(info) if info.expires_within(CERT_EXPIRY_WARNING) => tracing::warn!(
+                "Client certificate {} expires soon, at {}",
+                cert_file.display(),
+                info.not_after
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Client certificate {} could not be parsed: {}", cert_file.display(), e),
+        }
+    }
+
     /// Get the effective endpoint URL
     pub fn endpoint_url(&self) -> String {
         match self.transport {
             TransportType::Http => self.endpoint.clone(),
             TransportType::Unix => format!("unix:{}", self.endpoint),
             TransportType::Mtls => self.endpoint.clone(),
+            TransportType::Grpc => self.endpoint.clone(),
         }
     }
 }
@@ -420,7 +1274,7 @@ impl Config {
 mod tests {
     use super::*;
     use std::env;
-    use tempfile::NamedTempFile;
+    use std::io::Write;
 
     #[test]
     fn test_default_config() {
@@ -448,6 +1302,61 @@ mod tests {
         env::remove_var("VAULT_AUTH_METHOD");
     }
 
+    #[test]
+    fn test_retry_backoff_delay() {
+        let retry = RetryConfig {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+            backoff_multiplier: 2.0,
+            jitter: JitterStrategy::None,
+        };
+
+        assert_eq!(retry.backoff_delay(0), Duration::from_millis(100));
+        assert_eq!(retry.backoff_delay(1), Duration::from_millis(200));
+        assert_eq!(retry.backoff_delay(2), Duration::from_millis(400));
+        assert_eq!(retry.backoff_delay(3), Duration::from_millis(500)); // capped
+        assert_eq!(retry.backoff_delay(100), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_retry_jitter_strategies() {
+        let sequence = |jitter: JitterStrategy| {
+            let retry = RetryConfig {
+                max_retries: 4,
+                base_delay: Duration::from_millis(100),
+                max_delay: Duration::from_secs(10),
+                backoff_multiplier: 2.0,
+                jitter,
+            };
+            // Draws 0.25, 0.5, 0.75, 0.0 of the available range
+            let mut backoff = retry.backoff_with_rng(rand::rngs::mock::StepRng::new(1 << 62, 1 << 62));
+            (0..4).map(|_| backoff.next_delay().as_micros()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(sequence(JitterStrategy::None), vec![100_000, 200_000, 400_000, 800_000]);
+        assert_eq!(sequence(JitterStrategy::Full), vec![25_000, 100_000, 300_000, 0]);
+        assert_eq!(sequence(JitterStrategy::Equal), vec![62_500, 150_000, 350_000, 400_000]);
+        // Each draw spans [base, previous * 3): [100, 300), [100, 450), [100, 825), [100, 1931.25)
+        assert_eq!(sequence(JitterStrategy::Decorrelated), vec![150_000, 275_000, 643_750, 100_000]);
+        assert_eq!(RetryConfig::default().jitter, JitterStrategy::Full);
+    }
+
+    #[test]
+    fn test_decorrelated_jitter_capped() {
+        let retry = RetryConfig {
+            max_retries: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(250),
+            backoff_multiplier: 2.0,
+            jitter: JitterStrategy::Decorrelated,
+        };
+        let mut backoff = retry.backoff_with_rng(rand::rngs::mock::StepRng::new(u64::MAX, 0));
+        let delays: Vec<_> = (0..5).map(|_| backoff.next_delay()).collect();
+        assert!(delays.iter().all(|delay| *delay <= Duration::from_millis(250)));
+        assert_eq!(delays[4], Duration::from_millis(250));
+    }
+
     #[test]
     fn test_config_validation() {
         let mut config = Config::default();
@@ -485,7 +1394,7 @@ audit = true
 format = "json"
 "#;
 
-        let mut temp_file = NamedTempFile::new().unwrap();
+        let mut temp_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
         temp_file.write_all(config_content.as_bytes()).unwrap();
         
         let config = Config::from_file(temp_file.path()).unwrap();
@@ -493,4 +1402,386 @@ format = "json"
         assert_eq!(config.logging.level, "debug");
         assert_eq!(config.timeouts.connect, Duration::from_secs(5));
     }
-}
\ No newline at end of file
+
+    fn overlay_from(vars: &[(&str, &str)]) -> ConfigOverlay {
+        let vars: std::collections::HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        ConfigOverlay::from_vars(|key| vars.get(key).cloned()).unwrap()
+    }
+
+    #[test]
+    fn test_merge_env_log_level_info_overrides_file() {
+        let mut config = Config::default();
+        config.logging.level = "debug".to_string();
+
+        config.merge(overlay_from(&[("VAULT_LOG_LEVEL", "info")]));
+        assert_eq!(config.logging.level, "info");
+
+        // Unset variables leave file values alone
+        config.logging.level = "debug".to_string();
+        config.merge(overlay_from(&[]));
+        assert_eq!(config.logging.level, "debug");
+    }
+
+    #[test]
+    fn test_namespace_from_env_and_validation() {
+        let mut config = Config::default();
+        config.auth.method = AuthMethod::None;
+        config.merge(overlay_from(&[("VAULT_NAMESPACE", "tenant-a/prod")]));
+        assert_eq!(config.namespace.as_deref(), Some("tenant-a/prod"));
+        assert!(config.validate().is_ok());
+
+        config.namespace = Some("  ".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("namespace"), "{}", err);
+    }
+
+    #[test]
+    fn test_merge_env_flips_transport_both_ways() {
+        let mut config = Config::default();
+        config.transport = TransportType::Mtls;
+        config.merge(overlay_from(&[("VAULT_TRANSPORT", "http")]));
+        assert!(matches!(config.transport, TransportType::Http));
+
+        config.merge(overlay_from(&[("VAULT_TRANSPORT", "mtls"), ("VAULT_AUTH_METHOD", "token")]));
+        assert!(matches!(config.transport, TransportType::Mtls));
+        assert!(matches!(config.auth.method, AuthMethod::Token));
+
+        config.merge(overlay_from(&[("VAULT_TRANSPORT", "grpc")]));
+        assert!(matches!(config.transport, TransportType::Grpc));
+
+        assert!(ConfigOverlay::from_vars(|_| Some("bogus".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_config_formats_parse_identically() {
+        let toml_content = r#"
+endpoint = "https://vault.example.com"
+transport = "mtls"
+
+[auth]
+method = "certificate"
+cert_file = "/etc/vault/client.pem"
+key_file = "/etc/vault/client.key"
+
+[timeouts]
+connect = "5s"
+request = "10s"
+capability = "5m"
+
+[retry]
+max_retries = 2
+base_delay = "100ms"
+max_delay = "30s"
+backoff_multiplier = 2.0
+
+[logging]
+level = "debug"
+audit = true
+format = "json"
+"#;
+
+        let yaml_content = r#"
+endpoint: https://vault.example.com
+transport: mtls
+auth:
+  method: certificate
+  cert_file: /etc/vault/client.pem
+  key_file: /etc/vault/client.key
+timeouts:
+  connect: 5s
+  request: 10s
+  capability: 5m
+retry:
+  max_retries: 2
+  base_delay: 100ms
+  max_delay: 30s
+  backoff_multiplier: 2.0
+logging:
+  level: debug
+  audit: true
+  format: json
+"#;
+
+        let json_content = r#"{
+  "endpoint": "https://vault.example.com",
+  "transport": "mtls",
+  "auth": {
+    "method": "certificate",
+    "cert_file": "/etc/vault/client.pem",
+    "key_file": "/etc/vault/client.key"
+  },
+  "timeouts": {
+    "connect": "5s",
+    "request": "10s",
+    "capability": "5m"
+  },
+  "retry": {
+    "max_retries": 2,
+    "base_delay": "100ms",
+    "max_delay": "30s",
+    "backoff_multiplier": 2.0
+  },
+  "logging": { "level": "debug", "audit": true, "format": "json" }
+}"#;
+
+        let parsed: Vec<serde_json::Value> = [
+            (toml_content, ConfigFormat::Toml),
+            (yaml_content, ConfigFormat::Yaml),
+            (json_content, ConfigFormat::Json),
+        ]
+        .iter()
+        .map(|(content, format)| {
+            let config = Config::from_str_with_format(content, *format).unwrap();
+            serde_json::to_value(config).unwrap()
+        })
+        .collect();
+
+        assert_eq!(parsed[0], parsed[1]);
+        assert_eq!(parsed[1], parsed[2]);
+        assert_eq!(parsed[0]["logging"]["level"], "debug");
+    }
+
+    #[test]
+    fn test_config_format_from_extension() {
+        use std::path::Path;
+
+        assert_eq!(ConfigFormat::from_path(Path::new("vault.yml")).unwrap(), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path(Path::new("vault.YAML")).unwrap(), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path(Path::new("vault.json")).unwrap(), ConfigFormat::Json);
+
+        let err = Config::from_file("vault.ini").unwrap_err();
+        assert!(err.to_string().contains(".toml, .yaml, .yml, .json"));
+    }
+
+    #[test]
+    fn test_human_readable_durations() {
+        let timeouts: TimeoutConfig = toml::from_str(r#"
+connect = "250ms"
+request = "10s"
+capability = "1m"
+"#).unwrap();
+        assert_eq!(timeouts.connect, Duration::from_millis(250));
+        assert_eq!(timeouts.request, Duration::from_secs(10));
+        assert_eq!(timeouts.capability, Duration::from_secs(60));
+
+        let retry: RetryConfig = toml::from_str(r#"
+base_delay = "1s 500ms"
+max_delay = "2h"
+"#).unwrap();
+        assert_eq!(retry.base_delay, Duration::from_millis(1500));
+        assert_eq!(retry.max_delay, Duration::from_secs(2 * 60 * 60));
+        assert_eq!(retry.max_retries, RetryConfig::default().max_retries);
+
+        assert!(toml::from_str::<TimeoutConfig>(r#"connect = "soon""#).is_err());
+    }
+
+    #[test]
+    fn test_durations_round_trip_in_human_form() {
+        let serialized = serde_json::to_value(TimeoutConfig::default()).unwrap();
+        assert_eq!(serialized["connect"], "10s");
+        assert_eq!(serialized["capability"], "5m");
+
+        let cache = CacheConfig {
+            enabled: true,
+            max_size: 16,
+            ttl: Duration::from_millis(1500),
+        };
+        let round_tripped: CacheConfig =
+            serde_json::from_str(&serde_json::to_string(&cache).unwrap()).unwrap();
+        assert_eq!(round_tripped.ttl, cache.ttl);
+    }
+
+    #[test]
+    fn test_proxy_validation() {
+        let mut config = Config::default();
+        config.auth.method = AuthMethod::None;
+        config.proxy = Some(ProxyConfig {
+            url: "http://proxy.internal:3128".to_string(),
+            username: Some("svc".to_string()),
+            password: Some("hunter2".to_string()),
+            no_proxy: vec!["localhost".to_string(), "10.0.0.0/8".to_string()],
+        });
+        assert!(config.validate().is_ok());
+        assert!(!format!("{:?}", config).contains("hunter2"));
+
+        for url in ["not a url", "ftp://proxy.internal", "socks5://proxy.internal:1080"] {
+            config.proxy.as_mut().unwrap().url = url.to_string();
+            let err = config.validate().unwrap_err();
+            assert!(matches!(err, crate::error::VaultError::Config(ConfigError::InvalidValue(ref field, _)) if field == "proxy.url"));
+        }
+    }
+
+    #[test]
+    fn test_tls_validation() {
+        let mut config = Config::default();
+        config.auth.method = AuthMethod::None;
+        config.tls = Some(TlsConfig {
+            verify_cert: true,
+            server_name: None,
+            min_version: Some("1.2".to_string()),
+            max_version: Some("1.3".to_string()),
+            cipher_suites: Some(vec!["TLS13_AES_256_GCM_SHA384".to_string()]),
+        });
+        assert!(config.validate().is_ok());
+
+        let tls = config.tls.as_mut().unwrap();
+        tls.min_version = Some("TLSv1.2".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("tls.min_version"));
+        assert!(err.to_string().contains("TLSv1.2"));
+
+        let tls = config.tls.as_mut().unwrap();
+        tls.min_version = Some("1.3".to_string());
+        tls.max_version = Some("1.2".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, crate::error::VaultError::Config(ConfigError::InvalidValue(ref field, _)) if field == "tls.min_version"));
+
+        let tls = config.tls.as_mut().unwrap();
+        tls.max_version = None;
+        tls.cipher_suites = Some(vec!["RC4-MD5".to_string()]);
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("RC4-MD5"));
+    }
+
+    #[test]
+    fn test_config_renders_round_trip() {
+        let config = Config::default();
+
+        let toml = config.to_toml().unwrap();
+        assert!(toml.contains(r#"connect = "10s""#));
+        let parsed = Config::from_str_with_format(&toml, ConfigFormat::Toml).unwrap();
+        assert_eq!(parsed.endpoint, config.endpoint);
+        assert_eq!(parsed.timeouts.connect, config.timeouts.connect);
+        assert_eq!(parsed.timeouts.capability, config.timeouts.capability);
+        assert_eq!(parsed.retry.base_delay, config.retry.base_delay);
+        assert_eq!(parsed.auth.token_refresh_skew, config.auth.token_refresh_skew);
+        assert_eq!(parsed.to_toml().unwrap(), toml);
+
+        let yaml = config.to_yaml().unwrap();
+        let parsed = Config::from_str_with_format(&yaml, ConfigFormat::Yaml).unwrap();
+        assert_eq!(parsed.to_toml().unwrap(), toml);
+    }
+
+    #[test]
+    fn test_config_render_redacts_secrets() {
+        let mut config = Config::default();
+        config.auth.token_file = Some(PathBuf::from("/etc/vault/token"));
+        config.proxy = Some(ProxyConfig {
+            url: "http://proxy.internal:3128".to_string(),
+            username: Some("svc".to_string()),
+            password: Some("hunter2".to_string()),
+            no_proxy: Vec::new(),
+        });
+
+        for rendered in [config.to_toml().unwrap(), config.to_yaml().unwrap()] {
+            assert!(rendered.contains("/etc/vault/token"));
+            assert!(rendered.contains("***"));
+            assert!(!rendered.contains("hunter2"));
+        }
+        assert_eq!(config.proxy.unwrap().password.as_deref(), Some("hunter2"));
+    }
+
+    #[tokio::test]
+    async fn test_watch_publishes_reloaded_config() {
+        let file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        let write_endpoint = |endpoint: &str| {
+            let content = format!(
+                "endpoint = \"{}\"\ntransport = \"http\"\n\n[auth]\nmethod = \"none\"\n\n[logging]\nlevel = \"info\"\naudit = false\nformat = \"json\"\n",
+                endpoint
+            );
+            std::fs::write(file.path(), content).unwrap();
+        };
+        write_endpoint("http://localhost:8200");
+
+        let (config, mut rx) = Config::watch_with(file.path(), |_| None).unwrap();
+        assert_eq!(config.endpoint, "http://localhost:8200");
+
+        write_endpoint("http://127.0.0.1:8300");
+        tokio::time::timeout(Duration::from_secs(2), rx.changed()).await.unwrap().unwrap();
+        assert_eq!(rx.borrow_and_update().endpoint, "http://127.0.0.1:8300");
+
+        // Unauthenticated access to a remote endpoint fails validation
+        write_endpoint("https://vault.example.com");
+        assert!(tokio::time::timeout(Duration::from_millis(100), rx.changed()).await.is_err());
+        assert_eq!(rx.borrow().endpoint, "http://127.0.0.1:8300");
+    }
+
+    #[test]
+    fn test_logging_level_validation() {
+        let mut config = Config::default();
+        config.auth.method = AuthMethod::None;
+        config.logging.level = "aether_vault=debug,warn".to_string();
+        assert!(config.validate().is_ok());
+
+        config.logging.level = "aether_vault=loud".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("logging.level"), "{}", err);
+    }
+
+    #[test]
+    fn test_token_sources() {
+        let mut config = Config::default();
+        assert!(config.validate().is_err());
+
+        // Env only
+        let overlay = overlay_from(&[("VAULT_TOKEN", "s.env-token")]);
+        assert!(!format!("{:?}", overlay).contains("s.env-token"));
+        config.merge(overlay);
+        assert_eq!(config.auth.token.as_ref().map(|token| token.as_str()), Some("s.env-token"));
+        assert!(config.validate().is_ok());
+        assert!(!format!("{:?}", config).contains("s.env-token"));
+        assert!(!serde_json::to_string(&config).unwrap().contains("s.env-token"));
+
+        config.auth.token = Some(Zeroizing::new(" ".to_string()));
+        assert!(config.validate().is_err());
+        config.auth.token = Some(Zeroizing::new("{\"token\": \"s.env-token\"}".to_string()));
+        assert!(config.validate().is_err());
+        config.auth.token = Some(Zeroizing::new("s.one\ns.two".to_string()));
+        assert!(config.validate().is_err());
+        config.auth.token = Some(Zeroizing::new("aGVsbG8=\n".to_string()));
+        assert!(config.validate().is_ok());
+
+        // File only
+        config.auth.token = None;
+        config.merge(overlay_from(&[("VAULT_TOKEN_FILE", "/etc/vault/token")]));
+        assert!(config.validate().is_ok());
+
+        // Both present; the file wins, so a blank env token is not an error
+        config.merge(overlay_from(&[("VAULT_TOKEN", " ")]));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_domain_ttl_validation() {
+        let mut config = Config::default();
+        config.auth.method = AuthMethod::None;
+        config.domain_ttls.insert(Domain::Ssh, DomainTtlPolicy {
+            default_ttl: Some(Duration::from_secs(60)),
+            max_ttl: Duration::from_secs(300),
+            on_exceed: TtlPolicy::Reject,
+        });
+        assert!(config.validate().is_ok());
+
+        config.domain_ttls.get_mut(&Domain::Ssh).unwrap().default_ttl = Some(Duration::from_secs(600));
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("domain_ttls.ssh.default_ttl"), "{}", err);
+
+        config.domain_ttls.get_mut(&Domain::Ssh).unwrap().default_ttl = None;
+        config.domain_ttls.get_mut(&Domain::Ssh).unwrap().max_ttl = Duration::from_secs(2 * 86400);
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("domain_ttls.ssh.max_ttl"), "{}", err);
+
+        let parsed: HashMap<Domain, DomainTtlPolicy> = toml::from_str(r#"
+            [tls]
+            max_ttl = "8h"
+            on_exceed = "reject"
+        "#).unwrap();
+        let tls = &parsed[&Domain::Tls];
+        assert_eq!(tls.max_ttl, Duration::from_secs(8 * 3600));
+        assert_eq!(tls.on_exceed, TtlPolicy::Reject);
+        assert_eq!(tls.default_ttl, None);
+    }
+}
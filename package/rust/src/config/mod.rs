@@ -1,3 +1,7 @@
 pub mod config;
 
-pub use config::Config;
\ No newline at end of file
+pub use config::{
+    AuthConfig, AuthMethod, Backoff, CacheConfig, CompressionAlgorithm, CompressionConfig, Config, ConfigFormat, DomainTtlPolicy, ConfigOverlay, JitterStrategy, LogFormat,
+    LoggingConfig, RetryConfig, ServerHintsConfig, TimeoutConfig, TlsConfig, TransportType,
+    ProxyConfig, TtlPolicy, TLS_CIPHER_SUITES, TLS_VERSIONS,
+};
\ No newline at end of file
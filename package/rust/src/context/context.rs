@@ -0,0 +1,285 @@
+//! Execution context modeling for Aether Vault.
+//!
+//! A context describes where a workload runs (service, environment,
+//! namespace, instance) and is turned into the [`CapabilityContext`]
+//! constraints of a capability request.
+
+use crate::capability::{CapabilityContext, TimeWindow, UsageLimits};
+use crate::error::{Result, VaultError};
+use std::collections::HashSet;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+/// Namespace file mounted into Kubernetes pods
+pub const KUBERNETES_NAMESPACE_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/namespace";
+
+/// Execution context of the requesting workload
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    /// Service name
+    service: Option<String>,
+
+    /// Deployment environment
+    environment: Option<String>,
+
+    /// Namespace the workload runs in
+    namespace: Option<String>,
+
+    /// Instance (pod or host) name
+    instance: Option<String>,
+
+    /// Addresses the capability may be used from
+    ip_constraints: Vec<String>,
+
+    /// Time window the capability may be used in
+    time_window: Option<TimeWindow>,
+
+    /// Limits on how often the capability may be used
+    usage_limits: Option<UsageLimits>,
+}
+
+/// Builder for [`Context`]
+#[derive(Debug, Clone, Default)]
+pub struct ContextBuilder {
+    service: Option<String>,
+    environment: Option<String>,
+    namespace: Option<String>,
+    instance: Option<String>,
+    ip_constraints: Vec<String>,
+    time_window: Option<TimeWindow>,
+    usage_limits: Option<UsageLimits>,
+}
+
+impl Context {
+    /// Start building a context
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::default()
+    }
+
+    /// Detect the context from the process environment
+    ///
+    /// Reads the service from `SERVICE_NAME` or `K_SERVICE`, the environment
+    /// from `DEPLOY_ENV` or `ENVIRONMENT`, the instance from `HOSTNAME`, and
+    /// the namespace from the Kubernetes service-account mount. Anything not
+    /// found is left unset. Not available on `wasm32`, which has neither.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_env() -> Self {
+        Self::from_sources(|name| std::env::var(name).ok(), Path::new(KUBERNETES_NAMESPACE_PATH))
+    }
+
+    /// Detect the context from a variable lookup and namespace file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_sources<F>(lookup: F, namespace_path: &Path) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let non_empty = |value: String| {
+            let value = value.trim().to_string();
+            (!value.is_empty()).then_some(value)
+        };
+        let first = |names: &[&str]| names.iter().find_map(|name| lookup(name).and_then(non_empty));
+
+        Self {
+            service: first(&["SERVICE_NAME", "K_SERVICE"]),
+            environment: first(&["DEPLOY_ENV", "ENVIRONMENT"]),
+            namespace: std::fs::read_to_string(namespace_path).ok().and_then(non_empty),
+            instance: first(&["HOSTNAME"]),
+            ..Self::default()
+        }
+    }
+
+    /// Get the service name
+    pub fn service(&self) -> Option<&str> {
+        self.service.as_deref()
+    }
+
+    /// Get the deployment environment
+    pub fn environment(&self) -> Option<&str> {
+        self.environment.as_deref()
+    }
+
+    /// Get the namespace
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Get the instance name
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+
+    /// Convert into the constraints sent with a capability request
+    pub fn to_capability_context(&self) -> CapabilityContext {
+        CapabilityContext {
+            services: self.service.clone().map(|s| HashSet::from([s])),
+            environments: self.environment.clone().map(|e| HashSet::from([e])),
+            namespaces: self.namespace.clone().map(|n| HashSet::from([n])),
+            ip_constraints: (!self.ip_constraints.is_empty()).then(|| self.ip_constraints.clone()),
+            time_window: self.time_window.clone(),
+            usage_limits: self.usage_limits.clone(),
+        }
+    }
+}
+
+impl ContextBuilder {
+    /// Set the service name
+    pub fn service(mut self, service: impl Into<String>) -> Self {
+        self.service = Some(service.into());
+        self
+    }
+
+    /// Set the deployment environment
+    pub fn environment(mut self, environment: impl Into<String>) -> Self {
+        self.environment = Some(environment.into());
+        self
+    }
+
+    /// Set the namespace
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Set the instance name
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Add an IP address constraint (may be called repeatedly)
+    pub fn ip_constraint(mut self, ip: impl Into<String>) -> Self {
+        self.ip_constraints.push(ip.into());
+        self
+    }
+
+    /// Restrict use to a time window
+    pub fn time_window(mut self, time_window: TimeWindow) -> Self {
+        self.time_window = Some(time_window);
+        self
+    }
+
+    /// Limit how often the capability may be used
+    pub fn usage_limits(mut self, usage_limits: UsageLimits) -> Self {
+        self.usage_limits = Some(usage_limits);
+        self
+    }
+
+    /// Build the context
+    pub fn build(self) -> Result<Context> {
+        for (field, value) in [
+            ("service", &self.service),
+            ("environment", &self.environment),
+            ("namespace", &self.namespace),
+            ("instance", &self.instance),
+        ] {
+            if value.as_deref().map_or(false, |v| v.trim().is_empty()) {
+                return Err(VaultError::Validation(format!("context {} must not be empty", field)));
+            }
+        }
+        if self.ip_constraints.iter().any(|ip| ip.trim().is_empty()) {
+            return Err(VaultError::Validation("context ip_constraint must not be empty".to_string()));
+        }
+
+        Ok(Context {
+            service: self.service,
+            environment: self.environment,
+            namespace: self.namespace,
+            instance: self.instance,
+            ip_constraints: self.ip_constraints,
+            time_window: self.time_window,
+            usage_limits: self.usage_limits,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_to_capability_context() {
+        let context = Context::builder()
+            .service("my-app")
+            .environment("production")
+            .build()
+            .unwrap();
+
+        let capability_context = context.to_capability_context();
+        assert!(capability_context.services.unwrap().contains("my-app"));
+        assert!(capability_context.environments.unwrap().contains("production"));
+        assert!(capability_context.namespaces.is_none());
+
+        assert!(Context::builder().service(" ").build().is_err());
+    }
+
+    #[test]
+    fn test_context_from_sources() {
+        let vars = std::collections::HashMap::from([
+            ("K_SERVICE", "billing"),
+            ("ENVIRONMENT", "staging"),
+            ("DEPLOY_ENV", "production"),
+            ("HOSTNAME", "billing-7d9f8-abcde"),
+        ]);
+        let lookup = |name: &str| vars.get(name).map(|v| v.to_string());
+
+        let mut namespace_file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut namespace_file, b"payments\n").unwrap();
+
+        let context = Context::from_sources(lookup, namespace_file.path());
+        assert_eq!(context.service(), Some("billing"));
+        assert_eq!(context.environment(), Some("production"));
+        assert_eq!(context.namespace(), Some("payments"));
+        assert_eq!(context.instance(), Some("billing-7d9f8-abcde"));
+        assert!(context.to_capability_context().namespaces.unwrap().contains("payments"));
+
+        // Missing sources leave fields unset
+        let empty = Context::from_sources(|_| None, Path::new("/nonexistent/namespace"));
+        assert!(empty.service().is_none());
+        assert!(empty.namespace().is_none());
+        assert!(empty.instance().is_none());
+    }
+
+    #[test]
+    fn test_context_builder_constraints() {
+        let start = chrono::Utc::now();
+        let window = TimeWindow {
+            start,
+            end: start + chrono::Duration::hours(8),
+            days_of_week: Some(vec![1, 2, 3, 4, 5]),
+            tz: Some("Europe/Paris".to_string()),
+        };
+        let limits = UsageLimits {
+            max_uses: Some(10),
+            ..UsageLimits::default()
+        };
+
+        let context = Context::builder()
+            .service("api")
+            .namespace("production")
+            .ip_constraint("10.0.0.12")
+            .ip_constraint("10.0.0.13")
+            .time_window(window)
+            .usage_limits(limits)
+            .build()
+            .unwrap();
+
+        let capability_context = context.to_capability_context();
+        assert_eq!(capability_context.namespaces, Some(HashSet::from(["production".to_string()])));
+        assert_eq!(
+            capability_context.ip_constraints,
+            Some(vec!["10.0.0.12".to_string(), "10.0.0.13".to_string()])
+        );
+        let time_window = capability_context.time_window.unwrap();
+        assert_eq!(time_window.start, start);
+        assert_eq!(time_window.days_of_week, Some(vec![1, 2, 3, 4, 5]));
+        assert_eq!(capability_context.usage_limits.unwrap().max_uses, Some(10));
+
+        // Unset constraints stay unset
+        let bare = Context::builder().build().unwrap().to_capability_context();
+        assert!(bare.ip_constraints.is_none());
+        assert!(bare.time_window.is_none());
+        assert!(bare.usage_limits.is_none());
+
+        assert!(Context::builder().ip_constraint("").build().is_err());
+    }
+}
@@ -0,0 +1,376 @@
+//! Cryptographic primitives for Aether Vault.
+//!
+//! Thin wrappers over `ring`; the SDK never implements its own crypto.
+
+use crate::capability::Capability;
+use crate::error::{CryptoError, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use ring::hmac;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::SecureRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+/// AES-256-GCM key length in bytes
+pub const SEAL_KEY_LEN: usize = 32;
+
+/// Load an Ed25519 key pair from a raw 32-byte seed or a PKCS#8 document
+fn ed25519_key_pair(signing_key: &[u8]) -> Result<Ed25519KeyPair> {
+    let key_pair = if signing_key.len() == 32 {
+        Ed25519KeyPair::from_seed_unchecked(signing_key)
+    } else {
+        Ed25519KeyPair::from_pkcs8(signing_key)
+    };
+
+    key_pair.map_err(|e| CryptoError::InvalidKeyFormat(
+        format!("invalid Ed25519 signing key: {}", e)
+    ).into())
+}
+
+/// Sign `message` with an Ed25519 seed or PKCS#8 key
+pub(crate) fn ed25519_sign(signing_key: &[u8], message: &[u8]) -> Result<Vec<u8>> {
+    let key_pair = ed25519_key_pair(signing_key)?;
+    Ok(key_pair.sign(message).as_ref().to_vec())
+}
+
+/// Verify an Ed25519 signature against a raw 32-byte public key
+pub(crate) fn ed25519_verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<()> {
+    Ok(crate::portable::verify_ed25519(public_key, message, signature)?)
+}
+
+/// Sign a capability with an Ed25519 private key
+///
+/// The signature covers [`Capability::signing_payload`], the same bytes
+/// [`Capability::validate_signature`] verifies, and is stored in
+/// `cap.signature`. `signing_key` is either a raw 32-byte seed or a PKCS#8
+/// document.
+pub fn sign_capability(cap: &mut Capability, signing_key: &[u8]) -> Result<()> {
+    cap.signature = ed25519_sign(signing_key, &cap.signing_payload())?;
+    Ok(())
+}
+
+/// Derive the raw 32-byte Ed25519 public key for a signing key
+pub fn public_key_for(signing_key: &[u8]) -> Result<Vec<u8>> {
+    let key_pair = ed25519_key_pair(signing_key)?;
+    Ok(key_pair.public_key().as_ref().to_vec())
+}
+
+/// HMAC-SHA256 tag of `message` under `key`
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+    hmac::sign(&key, message).as_ref().to_vec()
+}
+
+/// Check an HMAC-SHA256 tag in constant time
+///
+/// The comparison always covers the full tag, so the time taken does not
+/// reveal how many leading bytes of a forged tag were correct.
+pub fn verify_hmac(key: &[u8], message: &[u8], tag: &[u8]) -> bool {
+    constant_time_eq(&hmac_sha256(key, message), tag)
+}
+
+/// Compare two secrets without revealing where they differ
+///
+/// Equal-length inputs are compared in full, so timing does not depend on
+/// their contents. Inputs of different lengths return `false` straight
+/// away; that only reveals the lengths differ, which is not secret for
+/// fixed-size values such as tags and signatures.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    ring::constant_time::verify_slices_are_equal(a, b).is_ok()
+}
+
+/// Fields of an X.509 certificate relevant to identity and expiry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateInfo {
+    /// Full subject distinguished name
+    pub subject: String,
+
+    /// Subject common name, if present
+    pub common_name: Option<String>,
+
+    /// Subject alternative names (DNS names, IP addresses, URIs, emails)
+    pub sans: Vec<String>,
+
+    /// Start of the validity period
+    pub not_before: DateTime<Utc>,
+
+    /// End of the validity period
+    pub not_after: DateTime<Utc>,
+
+    /// SHA-256 fingerprint of the DER encoding, as colon-separated hex
+    pub fingerprint: String,
+}
+
+impl CertificateInfo {
+    /// Whether the certificate's validity period has ended
+    pub fn is_expired(&self) -> bool {
+        self.not_after <= Utc::now()
+    }
+
+    /// Whether the certificate expires within `window` from now
+    pub fn expires_within(&self, window: std::time::Duration) -> bool {
+        chrono::Duration::from_std(window).map_or(true, |window| self.not_after <= Utc::now() + window)
+    }
+}
+
+/// Parse the first certificate in a PEM document
+pub fn parse_certificate(pem: &[u8]) -> Result<CertificateInfo> {
+    use x509_parser::extensions::GeneralName;
+
+    let invalid = |e: &dyn std::fmt::Display| CryptoError::InvalidCertificate(e.to_string());
+
+    let (_, pem) = x509_parser::pem::parse_x509_pem(pem).map_err(|e| invalid(&e))?;
+    let cert = pem.parse_x509().map_err(|e| invalid(&e))?;
+
+    let common_name = cert
+        .subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(str::to_string);
+
+    let mut sans = Vec::new();
+    if let Some(extension) = cert.subject_alternative_name().map_err(|e| invalid(&e))? {
+        for name in &extension.value.general_names {
+            match name {
+                GeneralName::DNSName(name) | GeneralName::URI(name) | GeneralName::RFC822Name(name) => {
+                    sans.push(name.to_string())
+                }
+                GeneralName::IPAddress(bytes) => match bytes.len() {
+                    4 => sans.push(std::net::Ipv4Addr::from(<[u8; 4]>::try_from(*bytes).unwrap()).to_string()),
+                    16 => sans.push(std::net::Ipv6Addr::from(<[u8; 16]>::try_from(*bytes).unwrap()).to_string()),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    let timestamp = |time: x509_parser::time::ASN1Time| {
+        Utc.timestamp_opt(time.timestamp(), 0)
+            .single()
+            .ok_or_else(|| CryptoError::InvalidCertificate(format!("validity time out of range: {}", time)))
+    };
+
+    let digest = ring::digest::digest(&ring::digest::SHA256, &pem.contents);
+    let fingerprint = digest.as_ref().iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":");
+
+    Ok(CertificateInfo {
+        subject: cert.subject().to_string(),
+        common_name,
+        sans,
+        not_before: timestamp(cert.validity().not_before)?,
+        not_after: timestamp(cert.validity().not_after)?,
+        fingerprint,
+    })
+}
+
+/// Load an AES-256-GCM key, rejecting keys of the wrong length
+fn aes_256_gcm_key(key: &[u8]) -> Result<LessSafeKey> {
+    if key.len() != SEAL_KEY_LEN {
+        return Err(CryptoError::InvalidKeyFormat(format!(
+            "expected {}-byte AES-256 key, got {} bytes",
+            SEAL_KEY_LEN,
+            key.len()
+        )).into());
+    }
+
+    let key = UnboundKey::new(&AES_256_GCM, key)
+        .map_err(|_| CryptoError::InvalidKeyFormat("invalid AES-256 key".to_string()))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// Encrypt and authenticate `plaintext` with AES-256-GCM
+///
+/// `aad` is authenticated but not encrypted. The output is a random 96-bit
+/// nonce followed by the ciphertext and tag; pass it unchanged to [`open`].
+pub fn seal(plaintext: &[u8], key: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    let key = aes_256_gcm_key(key)?;
+
+    let mut nonce = [0u8; NONCE_LEN];
+    ring::rand::SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| CryptoError::EncryptionFailed("failed to generate nonce".to_string()))?;
+
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(aad), &mut in_out)
+        .map_err(|_| CryptoError::EncryptionFailed("AES-256-GCM seal failed".to_string()))?;
+
+    let mut sealed = Vec::with_capacity(NONCE_LEN + in_out.len());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&in_out);
+    Ok(sealed)
+}
+
+/// Decrypt and verify the output of [`seal`]
+///
+/// Fails with `CryptoError::DecryptionFailed` if the ciphertext, nonce or
+/// `aad` were altered, or the key is not the one it was sealed with.
+pub fn open(ciphertext: &[u8], key: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    let key = aes_256_gcm_key(key)?;
+
+    if ciphertext.len() < NONCE_LEN + AES_256_GCM.tag_len() {
+        return Err(CryptoError::DecryptionFailed("ciphertext is too short".to_string()).into());
+    }
+    let (nonce, sealed) = ciphertext.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| CryptoError::DecryptionFailed("invalid nonce".to_string()))?;
+
+    let mut in_out = sealed.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .map_err(|_| CryptoError::DecryptionFailed("authentication failed".to_string()))?;
+    Ok(plaintext.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::{Action, CapabilityContext, Domain};
+    use crate::error::VaultError;
+
+    fn test_capability() -> Capability {
+        Capability::new(
+            Domain::Database,
+            Action::Read,
+            "users".to_string(),
+            CapabilityContext {
+                environments: None,
+                services: None,
+                namespaces: None,
+                ip_constraints: None,
+                time_window: None,
+                usage_limits: None,
+            },
+            std::time::Duration::from_secs(300),
+            "issuer".to_string(),
+            "subject".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_sign_and_verify_pkcs8() {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let public_key = public_key_for(pkcs8.as_ref()).unwrap();
+
+        let mut capability = test_capability();
+        sign_capability(&mut capability, pkcs8.as_ref()).unwrap();
+        assert!(capability.validate_signature(&public_key).unwrap());
+    }
+
+    #[test]
+    fn test_sign_and_verify_seed() {
+        let seed = [7u8; 32];
+        let public_key = public_key_for(&seed).unwrap();
+
+        let mut capability = test_capability();
+        sign_capability(&mut capability, &seed).unwrap();
+        assert!(capability.validate_signature(&public_key).unwrap());
+
+        // A different key must not verify
+        let other = public_key_for(&[8u8; 32]).unwrap();
+        assert!(capability.validate_signature(&other).is_err());
+    }
+
+    #[test]
+    fn test_sign_rejects_malformed_key() {
+        let mut capability = test_capability();
+        assert!(matches!(
+            sign_capability(&mut capability, b"not a key"),
+            Err(VaultError::Crypto(CryptoError::InvalidKeyFormat(_)))
+        ));
+    }
+
+    #[test]
+    fn test_seal_open_round_trip() {
+        let key = [3u8; 32];
+        let sealed = seal(b"db-password", &key, b"capability-id").unwrap();
+        assert_eq!(sealed.len(), NONCE_LEN + b"db-password".len() + AES_256_GCM.tag_len());
+        assert_eq!(open(&sealed, &key, b"capability-id").unwrap(), b"db-password");
+
+        // Nonces are random, so sealing twice differs
+        assert_ne!(seal(b"db-password", &key, b"capability-id").unwrap(), sealed);
+        assert_eq!(open(&seal(b"", &key, b"").unwrap(), &key, b"").unwrap(), b"");
+    }
+
+    #[test]
+    fn test_open_rejects_tampering() {
+        let key = [3u8; 32];
+        let sealed = seal(b"db-password", &key, b"capability-id").unwrap();
+
+        let mut tampered = sealed.clone();
+        tampered[NONCE_LEN] ^= 0x01;
+        assert!(matches!(open(&tampered, &key, b"capability-id"), Err(VaultError::Crypto(CryptoError::DecryptionFailed(_)))));
+
+        assert!(open(&sealed, &key, b"other-capability").is_err());
+        assert!(open(&sealed, &[4u8; 32], b"capability-id").is_err());
+        assert!(open(&sealed[..NONCE_LEN], &key, b"capability-id").is_err());
+    }
+
+    #[test]
+    fn test_seal_rejects_wrong_key_length() {
+        assert!(matches!(seal(b"data", &[0u8; 16], b""), Err(VaultError::Crypto(CryptoError::InvalidKeyFormat(_)))));
+        assert!(matches!(open(&[0u8; 64], &[0u8; 31], b""), Err(VaultError::Crypto(CryptoError::InvalidKeyFormat(_)))));
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        let tag = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            tag.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert!(verify_hmac(b"Jefe", b"what do ya want for nothing?", &tag));
+        assert!(!verify_hmac(b"Jefe", b"what do ya want for something?", &tag));
+        assert!(!verify_hmac(b"other", b"what do ya want for nothing?", &tag));
+    }
+
+    #[test]
+    fn test_verify_hmac_checks_whole_tag() {
+        let tag = hmac_sha256(b"key", b"message");
+
+        // A mismatch anywhere fails, not only in a prefix that is compared first
+        for position in [0, tag.len() / 2, tag.len() - 1] {
+            let mut forged = tag.clone();
+            forged[position] ^= 0x80;
+            assert!(!verify_hmac(b"key", b"message", &forged));
+        }
+        assert!(!verify_hmac(b"key", b"message", &tag[..tag.len() - 1]));
+        assert!(!verify_hmac(b"key", b"message", &[]));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token-value", b"token-value"));
+        assert!(constant_time_eq(b"", b""));
+
+        // Differences at the start and at the end are both reported
+        assert!(!constant_time_eq(b"token-value", b"Token-value"));
+        assert!(!constant_time_eq(b"token-value", b"token-valuE"));
+        assert!(!constant_time_eq(b"token-value", b"token-valu"));
+        assert!(!constant_time_eq(b"", b"x"));
+    }
+
+    #[test]
+    fn test_parse_certificate() {
+        let info = parse_certificate(include_bytes!("testdata/client.pem")).unwrap();
+        assert_eq!(info.common_name.as_deref(), Some("api.prod.svc"));
+        assert!(info.subject.contains("O=Sky Genesis Enterprise"));
+        assert_eq!(info.sans, vec!["api.prod.svc", "api", "10.0.0.7", "spiffe://prod/ns/prod/sa/api"]);
+        assert_eq!(info.not_before, Utc.with_ymd_and_hms(2026, 10, 16, 0, 38, 8).unwrap());
+        assert_eq!(info.not_after, Utc.with_ymd_and_hms(2036, 10, 13, 0, 38, 8).unwrap());
+        assert_eq!(
+            info.fingerprint,
+            "8A:24:F9:96:CB:96:94:0B:F2:BF:55:F6:8B:40:A7:54:3A:FE:2F:A0:FF:24:E3:63:FD:DF:C3:0E:75:70:8D:99"
+        );
+        assert!(info.expires_within(std::time::Duration::from_secs(20 * 365 * 24 * 3600)));
+    }
+
+    #[test]
+    fn test_parse_certificate_rejects_garbage() {
+        assert!(matches!(parse_certificate(b"not a certificate"), Err(VaultError::Crypto(CryptoError::InvalidCertificate(_)))));
+        let truncated = b"-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n";
+        assert!(matches!(parse_certificate(truncated), Err(VaultError::Crypto(CryptoError::InvalidCertificate(_)))));
+    }
+}
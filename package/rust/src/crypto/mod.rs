@@ -1,3 +1,3 @@
 pub mod crypto;
 
-pub use crypto::{Crypto, KeyManager};
\ No newline at end of file
+pub use crypto::{constant_time_eq, hmac_sha256, open, parse_certificate, CertificateInfo, public_key_for, seal, sign_capability, verify_hmac, SEAL_KEY_LEN};
\ No newline at end of file
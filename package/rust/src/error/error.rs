@@ -3,12 +3,32 @@
 //! This module provides comprehensive error handling with specific
 //! error types for different failure scenarios.
 
+use crate::capability::{Action, Capability, CapabilityRequest, Domain};
+use std::fmt;
 use thiserror::Error;
 
 /// Result type alias for convenience
 pub type Result<T> = std::result::Result<T, VaultError>;
 
+/// Underlying cause kept on wrapped errors
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
 /// Main error type for all Vault operations
+///
+/// Errors from client operations usually arrive wrapped in
+/// `VaultError::Request` (tagged with the request id) or
+/// `VaultError::WithContext`. This is a breaking change from matching the
+/// variants directly: match on [`VaultError::root`], or on
+/// [`VaultError::into_root`] to take the cause by value.
+///
+/// ```
+/// use aether_vault::error::{CapabilityError, VaultError};
+///
+/// let err = VaultError::from(CapabilityError::NotFound(uuid::Uuid::nil()))
+///     .with_request_id(uuid::Uuid::new_v4());
+/// assert!(!matches!(err, VaultError::Capability(_)));
+/// assert!(matches!(err.root(), VaultError::Capability(CapabilityError::NotFound(_))));
+/// ```
 #[derive(Error, Debug)]
 pub enum VaultError {
     /// Authentication failed
@@ -74,6 +94,100 @@ pub enum VaultError {
     /// TOML parsing errors
     #[error("TOML error: {0}")]
     Toml(#[from] toml::de::Error),
+
+    /// Error from a transport call, tagged with the call's request id
+    #[error("{source} (request id {request_id})")]
+    Request {
+        /// Id sent to the server as `X-Request-Id`
+        request_id: uuid::Uuid,
+        /// Underlying error
+        source: Box<VaultError>,
+    },
+
+    /// Error annotated with the operation that produced it
+    #[error("{source} [{context}]")]
+    WithContext {
+        /// Operation details
+        context: ErrorContext,
+        /// Underlying error
+        source: Box<VaultError>,
+    },
+}
+
+/// Operational details attached to an error
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorContext {
+    /// Capability used
+    pub capability_id: Option<uuid::Uuid>,
+
+    /// Target resource
+    pub target: Option<String>,
+
+    /// Capability domain
+    pub domain: Option<Domain>,
+
+    /// Capability action
+    pub action: Option<Action>,
+
+    /// Vault endpoint called
+    pub endpoint: Option<String>,
+
+    /// Id of the failing transport call
+    pub request_id: Option<uuid::Uuid>,
+}
+
+impl ErrorContext {
+    /// Context of an operation using `capability`
+    pub fn for_capability(capability: &Capability) -> Self {
+        Self {
+            capability_id: Some(capability.id),
+            target: Some(capability.target.clone()),
+            domain: Some(capability.domain.clone()),
+            action: Some(capability.action.clone()),
+            ..Self::default()
+        }
+    }
+
+    /// Context of a capability request
+    pub fn for_request(request: &CapabilityRequest) -> Self {
+        Self {
+            target: Some(request.target.clone()),
+            domain: Some(request.domain.clone()),
+            action: Some(request.action.clone()),
+            ..Self::default()
+        }
+    }
+
+    /// Set the endpoint
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(id) = self.capability_id {
+            parts.push(format!("capability={}", id));
+        }
+        match (&self.domain, &self.action) {
+            (Some(domain), Some(action)) => parts.push(format!("op={}:{}", domain, action)),
+            (Some(domain), None) => parts.push(format!("domain={}", domain)),
+            (None, Some(action)) => parts.push(format!("action={}", action)),
+            (None, None) => {}
+        }
+        if let Some(target) = &self.target {
+            parts.push(format!("target={}", target));
+        }
+        if let Some(endpoint) = &self.endpoint {
+            parts.push(format!("endpoint={}", endpoint));
+        }
+        if let Some(id) = self.request_id {
+            parts.push(format!("request_id={}", id));
+        }
+        write!(f, "{}", parts.join(" "))
+    }
 }
 
 /// Capability-specific errors
@@ -119,6 +233,10 @@ pub enum IdentityError {
     #[error("Identity token expired at {0}")]
     TokenExpired(chrono::DateTime<chrono::Utc>),
 
+    /// Identity token expires before the requested capability would
+    #[error("Identity token expires at {0}, before the requested capability")]
+    TokenExpiresBeforeCapability(chrono::DateTime<chrono::Utc>),
+
     /// Missing identity
     #[error("No identity provided")]
     MissingIdentity,
@@ -144,8 +262,24 @@ pub enum TransportError {
     Tls(String),
 
     /// HTTP errors
-    #[error("HTTP error: {0}")]
-    Http(String),
+    #[error("HTTP error: {message}")]
+    Http {
+        /// Description of the failure
+        message: String,
+        /// Underlying error, if any
+        #[source]
+        source: Option<BoxError>,
+    },
+
+    /// Response that could not be read or decoded
+    #[error("Invalid response: {message}")]
+    InvalidResponse {
+        /// Description of the failure
+        message: String,
+        /// Underlying error, if any
+        #[source]
+        source: Option<BoxError>,
+    },
 
     /// Protocol errors
     #[error("Protocol error: {0}")]
@@ -158,6 +292,64 @@ pub enum TransportError {
     /// Connection timeout
     #[error("Connection timeout")]
     ConnectionTimeout,
+
+    /// Request refused with a 4xx status other than 401, 403, 408, 425 and 429
+    ///
+    /// Not retryable: the same request would be refused again.
+    #[error("Request rejected with HTTP {status}: {message}")]
+    Rejected {
+        /// HTTP status code
+        status: u16,
+        /// Response body
+        message: String,
+    },
+}
+
+impl TransportError {
+    /// HTTP error caused by `source`
+    pub fn http<E>(source: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        TransportError::Http {
+            message: source.to_string(),
+            source: Some(Box::new(source)),
+        }
+    }
+
+    /// Error for an unsuccessful HTTP response other than 429
+    ///
+    /// 401 becomes `VaultError::AuthenticationFailed`, 403
+    /// `VaultError::AccessDenied` and any other 4xx `TransportError::Rejected`,
+    /// matching how the gRPC transport maps its status codes. 408 (Request
+    /// Timeout) and 425 (Too Early) stay a retryable `TransportError::Http`,
+    /// since the same request may succeed once resent.
+    pub fn from_status(status: u16, body: String) -> VaultError {
+        match status {
+            401 => VaultError::AuthenticationFailed(body),
+            403 => VaultError::AccessDenied(body),
+            408 | 425 => TransportError::Http {
+                message: format!("HTTP {}: {}", status, body),
+                source: None,
+            }.into(),
+            400..=499 => TransportError::Rejected { status, message: body }.into(),
+            _ => TransportError::Http {
+                message: format!("HTTP {}: {}", status, body),
+                source: None,
+            }.into(),
+        }
+    }
+
+    /// Invalid response caused by `source`
+    pub fn invalid_response<E>(source: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        TransportError::InvalidResponse {
+            message: source.to_string(),
+            source: Some(Box::new(source)),
+        }
+    }
 }
 
 /// Cryptographic errors
@@ -213,9 +405,75 @@ pub enum ConfigError {
 }
 
 impl VaultError {
+    /// Tag the error with the id of the transport call that produced it
+    pub fn with_request_id(self, request_id: uuid::Uuid) -> Self {
+        match self {
+            VaultError::Request { .. } => self,
+            VaultError::WithContext { mut context, source } => {
+                context.request_id.get_or_insert(request_id);
+                VaultError::WithContext { context, source }
+            }
+            other => VaultError::Request {
+                request_id,
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// Attach operation details, absorbing any request id tag
+    ///
+    /// An error that already carries context keeps it.
+    pub fn contextualized(self, mut context: ErrorContext) -> Self {
+        match self {
+            VaultError::WithContext { .. } => self,
+            VaultError::Request { request_id, source } => {
+                context.request_id.get_or_insert(request_id);
+                VaultError::WithContext { context, source }
+            }
+            other => VaultError::WithContext {
+                context,
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// Operation details attached to the error, if any
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            VaultError::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Id of the transport call that produced the error, if known
+    pub fn request_id(&self) -> Option<uuid::Uuid> {
+        match self {
+            VaultError::Request { request_id, .. } => Some(*request_id),
+            VaultError::WithContext { context, source } => context.request_id.or_else(|| source.request_id()),
+            _ => None,
+        }
+    }
+
+    /// The error without its request id tag or context
+    pub fn root(&self) -> &VaultError {
+        match self {
+            VaultError::Request { source, .. } | VaultError::WithContext { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// [`VaultError::root`] by value, dropping the request id tag and context
+    pub fn into_root(self) -> VaultError {
+        match self {
+            VaultError::Request { source, .. } | VaultError::WithContext { source, .. } => source.into_root(),
+            other => other,
+        }
+    }
+
     /// Check if this is a retryable error
     pub fn is_retryable(&self) -> bool {
-        match self {
+        match self.root() {
+            VaultError::Transport(TransportError::Rejected { .. }) => false,
             VaultError::Transport(_) => true,
             VaultError::Timeout(_) => true,
             VaultError::RateLimit(_) => true,
@@ -226,17 +484,17 @@ impl VaultError {
 
     /// Check if this is an authentication error
     pub fn is_authentication_error(&self) -> bool {
-        matches!(self, VaultError::AuthenticationFailed(_))
+        matches!(self.root(), VaultError::AuthenticationFailed(_))
     }
 
     /// Check if this is an authorization error
     pub fn is_authorization_error(&self) -> bool {
-        matches!(self, VaultError::AccessDenied(_))
+        matches!(self.root(), VaultError::AccessDenied(_))
     }
 
     /// Get error code for logging/monitoring
     pub fn error_code(&self) -> &'static str {
-        match self {
+        match self.root() {
             VaultError::AuthenticationFailed(_) => "AUTH_FAILED",
             VaultError::AccessDenied(_) => "ACCESS_DENIED",
             VaultError::Capability(_) => "CAPABILITY_ERROR",
@@ -253,6 +511,19 @@ impl VaultError {
             VaultError::Io(_) => "IO_ERROR",
             VaultError::Json(_) => "JSON_ERROR",
             VaultError::Toml(_) => "TOML_ERROR",
+            VaultError::Request { source, .. } | VaultError::WithContext { source, .. } => source.error_code(),
+        }
+    }
+}
+
+impl From<crate::portable::CoreError> for VaultError {
+    fn from(e: crate::portable::CoreError) -> Self {
+        use crate::portable::CoreError;
+        match e {
+            CoreError::InvalidKeyLength(_) => CryptoError::InvalidKeyFormat(e.to_string()).into(),
+            CoreError::SignatureVerificationFailed => CryptoError::SignatureVerificationFailed.into(),
+            CoreError::InvalidDomain(name) => CapabilityError::InvalidDomain(name).into(),
+            CoreError::InvalidAction(name) => CapabilityError::InvalidAction(name).into(),
         }
     }
 }
@@ -277,4 +548,83 @@ mod tests {
         let non_retryable = VaultError::AccessDenied("test".to_string());
         assert!(!non_retryable.is_retryable());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_http_status_mapping() {
+        assert!(TransportError::from_status(401, "bad token".to_string()).is_authentication_error());
+        assert!(TransportError::from_status(403, "nope".to_string()).is_authorization_error());
+        for status in [400, 401, 403, 404, 409] {
+            assert!(!TransportError::from_status(status, String::new()).is_retryable(), "{}", status);
+        }
+        assert!(matches!(
+            TransportError::from_status(404, "gone".to_string()),
+            VaultError::Transport(TransportError::Rejected { status: 404, .. })
+        ));
+        for status in [408, 425, 503] {
+            assert!(TransportError::from_status(status, String::new()).is_retryable(), "{}", status);
+        }
+    }
+
+    #[test]
+    fn test_request_id_tagging() {
+        let request_id = uuid::Uuid::new_v4();
+        let err = VaultError::RateLimit(std::time::Duration::from_secs(1)).with_request_id(request_id);
+        assert_eq!(err.request_id(), Some(request_id));
+        assert_eq!(err.error_code(), "RATE_LIMIT");
+        assert!(err.is_retryable());
+        assert!(matches!(err.root(), VaultError::RateLimit(_)));
+        assert!(err.to_string().contains(&request_id.to_string()));
+        assert!(matches!(
+            VaultError::RateLimit(std::time::Duration::from_secs(1)).with_request_id(request_id).into_root(),
+            VaultError::RateLimit(_)
+        ));
+
+        // Tagging twice keeps the original id
+        let err = err.with_request_id(uuid::Uuid::new_v4());
+        assert_eq!(err.request_id(), Some(request_id));
+    }
+
+    #[test]
+    fn test_error_context() {
+        let request_id = uuid::Uuid::new_v4();
+        let context = ErrorContext {
+            capability_id: Some(uuid::Uuid::nil()),
+            target: Some("users".to_string()),
+            domain: Some(Domain::Database),
+            action: Some(Action::Read),
+            ..ErrorContext::default()
+        }
+        .with_endpoint("https://vault.internal");
+
+        let err = VaultError::AccessDenied("denied".to_string())
+            .with_request_id(request_id)
+            .contextualized(context);
+        assert_eq!(err.request_id(), Some(request_id));
+        assert_eq!(err.error_code(), "ACCESS_DENIED");
+        assert!(err.is_authorization_error());
+        assert!(matches!(err.root(), VaultError::AccessDenied(_)));
+        assert_eq!(err.context().unwrap().target.as_deref(), Some("users"));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Access denied: denied [capability={} op=database:read target=users endpoint=https://vault.internal request_id={}]",
+                uuid::Uuid::nil(),
+                request_id
+            )
+        );
+    }
+
+    #[test]
+    fn test_transport_error_source_chain() {
+        use std::error::Error as _;
+
+        let cause = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let err: VaultError = TransportError::invalid_response(cause).into();
+        assert!(err.to_string().starts_with("Transport error: Invalid response: EOF"));
+        let transport = err.source().expect("transport error is the source");
+        assert!(transport.source().unwrap().downcast_ref::<serde_json::Error>().is_some());
+
+        let plain = TransportError::Http { message: "HTTP 500".to_string(), source: None };
+        assert!(plain.source().is_none());
+    }
+}
@@ -1,3 +1,6 @@
 pub mod error;
 
-pub use error::{VaultError, Result};
\ No newline at end of file
+pub use error::{
+    CapabilityError, ConfigError, CryptoError, ErrorContext, IdentityError, Result, TransportError,
+    VaultError,
+};
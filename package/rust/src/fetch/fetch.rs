@@ -0,0 +1,119 @@
+//! Browser transport for `wasm32-unknown-unknown` builds.
+//!
+//! Uses `reqwest`'s `fetch` backend, so requests go through the browser
+//! and its CORS rules. Meant for frontends talking to a Vault proxy; the
+//! full `Client` needs tokio and is not available on wasm.
+
+use crate::capability::{Capability, CapabilityRequest};
+use crate::error::{Result, TransportError, VaultError};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+/// HTTP transport over the browser's `fetch` API
+///
+/// Speaks the same wire protocol as the native `HttpTransport`, without
+/// retries, compression or request signing.
+#[derive(Clone)]
+pub struct FetchTransport {
+    endpoint: String,
+    client: reqwest::Client,
+    token: Option<Zeroizing<String>>,
+}
+
+impl std::fmt::Debug for FetchTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FetchTransport")
+            .field("endpoint", &self.endpoint)
+            .field("token", &self.token.as_ref().map(|_| "***"))
+            .finish()
+    }
+}
+
+impl FetchTransport {
+    /// Create a transport for the Vault (or proxy) at `endpoint`
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+            token: None,
+        }
+    }
+
+    /// Send `token` as the bearer token on every request
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(Zeroizing::new(token.into()));
+        self
+    }
+
+    /// Request a capability, presenting `identity_token`
+    pub async fn request_capability(&self, identity_token: &str, request: &CapabilityRequest) -> Result<Capability> {
+        let mut req_builder = self.post("/v1/capabilities")
+            .header("X-Vault-Identity", identity_token);
+        if let Some(key) = request.idempotency_key {
+            req_builder = req_builder.header("Idempotency-Key", key.to_string());
+        }
+        self.send(req_builder.json(request)).await
+    }
+
+    /// Extend a capability's TTL
+    pub async fn refresh_capability(
+        &self,
+        identity_token: &str,
+        capability_id: uuid::Uuid,
+        new_ttl: Duration,
+    ) -> Result<Capability> {
+        let req_builder = self.post(&format!("/v1/capabilities/{}/refresh", capability_id))
+            .header("X-Vault-Identity", identity_token)
+            .json(&RefreshBody { ttl: new_ttl });
+        self.send(req_builder).await
+    }
+
+    /// Revoke a capability
+    pub async fn revoke_capability(&self, capability_id: uuid::Uuid) -> Result<()> {
+        let response = self.post(&format!("/v1/capabilities/{}/revoke", capability_id))
+            .send()
+            .await
+            .map_err(TransportError::http)?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_from_response(response).await)
+        }
+    }
+
+    /// Access a resource using a capability
+    pub async fn access_with_capability<T: DeserializeOwned>(&self, capability: &Capability) -> Result<T> {
+        self.send(self.post("/v1/access").json(capability)).await
+    }
+
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        let req_builder = self.client.post(format!("{}{}", self.endpoint, path));
+        match &self.token {
+            Some(token) => req_builder.bearer_auth(token.as_str()),
+            None => req_builder,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, req_builder: reqwest::RequestBuilder) -> Result<T> {
+        let response = req_builder.send().await.map_err(TransportError::http)?;
+        if response.status().is_success() {
+            Ok(response.json().await.map_err(TransportError::invalid_response)?)
+        } else {
+            Err(error_from_response(response).await)
+        }
+    }
+}
+
+/// Body of a capability refresh request
+#[derive(serde::Serialize)]
+struct RefreshBody {
+    #[serde(rename = "ttl_seconds", with = "crate::capability::ttl_seconds")]
+    ttl: Duration,
+}
+
+async fn error_from_response(response: reqwest::Response) -> VaultError {
+    let status = response.status();
+    let error_text = response.text().await.unwrap_or_default();
+    TransportError::from_status(status.as_u16(), error_text)
+}
@@ -0,0 +1,3 @@
+pub mod fetch;
+
+pub use fetch::FetchTransport;
@@ -0,0 +1,502 @@
+//! Runtime identity management for Aether Vault.
+//!
+//! An identity is the credential a workload presents to Vault when
+//! requesting capabilities. Identities are held in memory only.
+
+use crate::error::{IdentityError, Result};
+use base64::Engine;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Projected service-account token mounted into Kubernetes pods
+pub const KUBERNETES_TOKEN_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+/// AWS instance metadata service, queried with IMDSv2 session tokens
+pub const AWS_IMDS_ENDPOINT: &str = "http://169.254.169.254";
+
+/// How long to wait on the instance metadata service before giving up
+const IMDS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Lifetime requested for IMDSv2 session tokens, in seconds
+const IMDS_SESSION_TTL_SECS: u32 = 21600;
+
+/// How often a mounted token file is checked for rotation
+const ROTATION_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Runtime identity presented to Vault
+///
+/// The token is wiped from memory when the identity is dropped and is
+/// never included in `Debug` output.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Identity {
+    /// Identity token
+    token: String,
+}
+
+/// Workload identity obtained from the runtime platform
+///
+/// Clones share the same token, so a rotation observed by one is seen by all.
+#[derive(Debug, Clone)]
+pub struct WorkloadIdentity {
+    /// Platform the identity was obtained from
+    provider: String,
+
+    /// Current token and its claims
+    state: Arc<RwLock<WorkloadToken>>,
+
+    /// File the token is read from, if any
+    token_path: Option<PathBuf>,
+}
+
+/// Token currently held by a workload identity
+#[derive(Debug, Clone)]
+struct WorkloadToken {
+    identity: Identity,
+    audience: Vec<String>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// JWT claims used to describe a workload token
+#[derive(Debug, Deserialize)]
+struct JwtClaims {
+    exp: Option<i64>,
+    #[serde(default)]
+    sub: Option<String>,
+    #[serde(default)]
+    aud: Option<Audience>,
+}
+
+/// The `aud` claim may be a single string or a list
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Identity {
+    /// Create a new identity from a token
+    pub fn new(token: String) -> Self {
+        Self { token }
+    }
+
+    /// Get the identity token
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Expiry from the token's JWT `exp` claim, if it has one
+    ///
+    /// The claim is read without verifying the token; Vault remains the
+    /// authority on whether the token is acceptable.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        let exp = jwt_claims(&self.token).ok()?.exp?;
+        Utc.timestamp_opt(exp, 0).single()
+    }
+
+    /// Subject from the token's JWT `sub` claim, if it has one
+    ///
+    /// Like [`Identity::expires_at`], the claim is read without verification.
+    pub fn subject(&self) -> Option<String> {
+        jwt_claims(&self.token).ok()?.sub
+    }
+
+    /// Hex SHA-256 digest of the token
+    ///
+    /// Tells identities apart without keeping the token itself, e.g. to tag
+    /// the capabilities requested under it.
+    pub fn fingerprint(&self) -> String {
+        ring::digest::digest(&ring::digest::SHA256, self.token.as_bytes())
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Whether the token carries an expiry that has passed
+    pub fn is_expired(&self) -> bool {
+        self.expires_at().map_or(false, |expires_at| expires_at <= Utc::now())
+    }
+}
+
+impl std::fmt::Debug for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Identity").field("token", &"***").finish()
+    }
+}
+
+impl Zeroize for Identity {
+    fn zeroize(&mut self) {
+        self.token.zeroize();
+    }
+}
+
+impl Drop for Identity {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Identity {}
+
+impl WorkloadIdentity {
+    /// Create a new workload identity
+    pub fn new(provider: String, identity: Identity) -> Self {
+        Self {
+            provider,
+            state: Arc::new(RwLock::new(WorkloadToken {
+                identity,
+                audience: Vec::new(),
+                expires_at: None,
+            })),
+            token_path: None,
+        }
+    }
+
+    /// Load the pod's projected Kubernetes service-account token
+    ///
+    /// When called inside a tokio runtime, the token file is re-read
+    /// periodically so rotations by the kubelet are picked up; see
+    /// [`WorkloadIdentity::from_kubernetes_token_file`].
+    pub fn from_kubernetes() -> Result<Self> {
+        Self::from_kubernetes_token_file(KUBERNETES_TOKEN_PATH)
+    }
+
+    /// Load a Kubernetes service-account token from `path`
+    ///
+    /// Fails with `IdentityError::InvalidWorkload` if the file is missing or
+    /// does not contain a JWT. Inside a tokio runtime a background task
+    /// reloads the file every 30 seconds until the identity is dropped.
+    pub fn from_kubernetes_token_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_token_file("kubernetes", path.as_ref())
+    }
+
+    /// Load an AWS workload identity
+    ///
+    /// Uses the web identity token named by `AWS_WEB_IDENTITY_TOKEN_FILE`
+    /// (as injected by EKS IRSA), falling back to the signed instance
+    /// identity document from IMDSv2. Fails with
+    /// `IdentityError::InvalidWorkload` if neither source is available.
+    ///
+    /// The token is presented to Vault as is; it is not exchanged with AWS
+    /// STS, so `AWS_ROLE_ARN` is not used. Vault maps the token's subject
+    /// to a role itself.
+    pub async fn from_aws() -> Result<Self> {
+        Self::aws_from_sources(|name| std::env::var(name).ok(), AWS_IMDS_ENDPOINT).await
+    }
+
+    /// Load an AWS web identity token from `path`
+    ///
+    /// The token file is watched for rotation the same way as
+    /// [`WorkloadIdentity::from_kubernetes_token_file`].
+    pub fn from_aws_web_identity_token_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_token_file("aws", path.as_ref())
+    }
+
+    /// Resolve an AWS identity from the given environment lookup and IMDS endpoint
+    async fn aws_from_sources<F>(lookup: F, imds_endpoint: &str) -> Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if let Some(path) = lookup("AWS_WEB_IDENTITY_TOKEN_FILE").filter(|path| !path.is_empty()) {
+            return Self::from_aws_web_identity_token_file(path);
+        }
+
+        let document = imds_identity_document(imds_endpoint).await.map_err(|e| {
+            IdentityError::InvalidWorkload(format!(
+                "AWS_WEB_IDENTITY_TOKEN_FILE is not set and instance metadata is unavailable: {}",
+                e
+            ))
+        })?;
+
+        Ok(Self::new("aws".to_string(), Identity::new(document)))
+    }
+
+    /// Load a JWT token file and watch it for rotation
+    fn from_token_file(provider: &str, path: &Path) -> Result<Self> {
+        let identity = Self {
+            provider: provider.to_string(),
+            state: Arc::new(RwLock::new(read_token_file(path)?)),
+            token_path: Some(path.to_path_buf()),
+        };
+
+        if tokio::runtime::Handle::try_current().is_ok() {
+            identity.watch_rotation(ROTATION_CHECK_INTERVAL);
+        }
+
+        Ok(identity)
+    }
+
+    /// Re-read the token file, returning whether the token changed
+    pub fn reload(&self) -> Result<bool> {
+        let Some(path) = &self.token_path else {
+            return Ok(false);
+        };
+
+        reload_token(&self.state, path)
+    }
+
+    /// Poll the token file for rotation until every clone is dropped
+    fn watch_rotation(&self, interval: Duration) {
+        let Some(path) = self.token_path.clone() else {
+            return;
+        };
+        let state = Arc::downgrade(&self.state);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(state) = state.upgrade() else { break };
+                if let Err(e) = reload_token(&state, &path) {
+                    tracing::warn!("Failed to reload workload token: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Get the provider name
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    /// Get the current identity
+    pub fn identity(&self) -> Identity {
+        self.state.read().unwrap_or_else(PoisonError::into_inner).identity.clone()
+    }
+
+    /// Audiences the token was issued for
+    pub fn audience(&self) -> Vec<String> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner).audience.clone()
+    }
+
+    /// Token expiry, if the token carries one
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner).expires_at
+    }
+}
+
+/// Replace the held token if the file's contents changed
+fn reload_token(state: &RwLock<WorkloadToken>, path: &Path) -> Result<bool> {
+    let fresh = read_token_file(path)?;
+    let mut state = state.write().unwrap_or_else(PoisonError::into_inner);
+    if crate::crypto::constant_time_eq(state.identity.token().as_bytes(), fresh.identity.token().as_bytes()) {
+        return Ok(false);
+    }
+
+    tracing::debug!("Workload token at {} rotated", path.display());
+    *state = fresh;
+    Ok(true)
+}
+
+/// Fetch the PKCS#7-signed instance identity document using IMDSv2
+async fn imds_identity_document(endpoint: &str) -> std::result::Result<String, reqwest::Error> {
+    let endpoint = endpoint.trim_end_matches('/');
+    let http = reqwest::Client::builder().timeout(IMDS_TIMEOUT).no_proxy().build()?;
+
+    let session = http
+        .put(format!("{}/latest/api/token", endpoint))
+        .header("X-aws-ec2-metadata-token-ttl-seconds", IMDS_SESSION_TTL_SECS.to_string())
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await
+        .map(Zeroizing::new)?;
+
+    let document = http
+        .get(format!("{}/latest/dynamic/instance-identity/pkcs7", endpoint))
+        .header("X-aws-ec2-metadata-token", session.as_str())
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await
+        .map(Zeroizing::new)?;
+
+    // Vault expects the signature without the line breaks IMDS inserts
+    Ok(document.split_whitespace().collect())
+}
+
+/// Read and parse a JWT token file
+fn read_token_file(path: &Path) -> Result<WorkloadToken> {
+    let contents = std::fs::read_to_string(path).map(Zeroizing::new).map_err(|e| {
+        IdentityError::InvalidWorkload(format!("cannot read token {}: {}", path.display(), e))
+    })?;
+    let token = contents.trim().to_string();
+
+    let claims = jwt_claims(&token)?;
+    let expires_at = match claims.exp {
+        Some(exp) => Some(Utc.timestamp_opt(exp, 0).single().ok_or_else(|| {
+            IdentityError::InvalidWorkload(format!("invalid exp claim {}", exp))
+        })?),
+        None => None,
+    };
+    let audience = match claims.aud {
+        Some(Audience::One(aud)) => vec![aud],
+        Some(Audience::Many(aud)) => aud,
+        None => Vec::new(),
+    };
+
+    Ok(WorkloadToken {
+        identity: Identity::new(token),
+        audience,
+        expires_at,
+    })
+}
+
+/// Decode the (unverified) claims segment of a JWT
+fn jwt_claims(token: &str) -> Result<JwtClaims> {
+    let mut segments = token.split('.');
+    let payload = match (segments.next(), segments.next(), segments.next(), segments.next()) {
+        (Some(_), Some(payload), Some(_), None) => payload,
+        _ => return Err(IdentityError::InvalidWorkload("token is not a JWT".to_string()).into()),
+    };
+
+    let payload = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .map_err(|e| IdentityError::InvalidWorkload(format!("invalid JWT payload encoding: {}", e)))?;
+
+    serde_json::from_slice(&payload)
+        .map_err(|e| IdentityError::InvalidWorkload(format!("invalid JWT claims: {}", e)).into())
+}
+
+/// Unsigned JWT carrying `claims`, for tests
+#[cfg(test)]
+pub(crate) fn test_jwt(claims: serde_json::Value) -> String {
+    let encode = |s: &str| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(s);
+    format!("{}.{}.{}", encode(r#"{"alg":"none"}"#), encode(&claims.to_string()), encode("sig"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+
+    #[test]
+    fn test_identity_token() {
+        let identity = Identity::new("test-token".to_string());
+        assert_eq!(identity.token(), "test-token");
+    }
+
+    #[test]
+    fn test_kubernetes_token_file() {
+        let mut token_file = tempfile::NamedTempFile::new().unwrap();
+        let token = test_jwt(json!({"aud": ["vault"], "exp": 1900000000, "sub": "system:serviceaccount:prod:api"}));
+        writeln!(token_file, "{}", token).unwrap();
+
+        let workload = WorkloadIdentity::from_kubernetes_token_file(token_file.path()).unwrap();
+        assert_eq!(workload.provider(), "kubernetes");
+        assert_eq!(workload.identity().token(), token);
+        assert_eq!(workload.audience(), vec!["vault".to_string()]);
+        assert_eq!(workload.expires_at(), Utc.timestamp_opt(1900000000, 0).single());
+
+        // Rotation is picked up on reload
+        let rotated = test_jwt(json!({"aud": "vault", "exp": 1900003600}));
+        std::fs::write(token_file.path(), &rotated).unwrap();
+        assert!(workload.reload().unwrap());
+        assert_eq!(workload.identity().token(), rotated);
+        assert_eq!(workload.expires_at(), Utc.timestamp_opt(1900003600, 0).single());
+        assert!(!workload.reload().unwrap());
+    }
+
+    #[test]
+    fn test_kubernetes_token_invalid() {
+        let err = WorkloadIdentity::from_kubernetes_token_file("/nonexistent/token").unwrap_err();
+        assert!(err.to_string().contains("Invalid workload identity"));
+
+        let mut token_file = tempfile::NamedTempFile::new().unwrap();
+        write!(token_file, "not-a-jwt").unwrap();
+        assert!(WorkloadIdentity::from_kubernetes_token_file(token_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_identity_expiry() {
+        let past = Identity::new(test_jwt(json!({"exp": 1000000000})));
+        assert_eq!(past.expires_at(), Utc.timestamp_opt(1000000000, 0).single());
+        assert!(past.is_expired());
+
+        let future = Identity::new(test_jwt(json!({"exp": Utc::now().timestamp() + 3600})));
+        assert!(future.expires_at().is_some());
+        assert!(!future.is_expired());
+
+        // Opaque tokens carry no expiry
+        let opaque = Identity::new("test-token".to_string());
+        assert!(opaque.expires_at().is_none());
+        assert!(!opaque.is_expired());
+    }
+
+    #[test]
+    fn test_identity_secret_handling() {
+        fn assert_zeroize_on_drop<T: Zeroize + ZeroizeOnDrop>() {}
+        assert_zeroize_on_drop::<Identity>();
+
+        let mut identity = Identity::new("super-secret-token".to_string());
+        assert_eq!(format!("{:?}", identity), r#"Identity { token: "***" }"#);
+
+        let workload = WorkloadIdentity::new("kubernetes".to_string(), identity.clone());
+        assert!(!format!("{:?}", workload).contains("super-secret-token"));
+
+        identity.zeroize();
+        assert_eq!(identity.token(), "");
+    }
+
+    #[tokio::test]
+    async fn test_aws_web_identity_token_file() {
+        let mut token_file = tempfile::NamedTempFile::new().unwrap();
+        let token = test_jwt(json!({"aud": "sts.amazonaws.com", "exp": 1900000000, "sub": "system:serviceaccount:prod:api"}));
+        writeln!(token_file, "{}", token).unwrap();
+
+        let path = token_file.path().to_string_lossy().to_string();
+        let lookup = |name: &str| match name {
+            "AWS_WEB_IDENTITY_TOKEN_FILE" => Some(path.clone()),
+            "AWS_ROLE_ARN" => Some("arn:aws:iam::123456789012:role/api".to_string()),
+            _ => None,
+        };
+
+        let workload = WorkloadIdentity::aws_from_sources(lookup, "http://127.0.0.1:1").await.unwrap();
+        assert_eq!(workload.provider(), "aws");
+        assert_eq!(workload.identity().token(), token);
+        assert_eq!(workload.audience(), vec!["sts.amazonaws.com".to_string()]);
+        assert_eq!(workload.expires_at(), Utc.timestamp_opt(1900000000, 0).single());
+
+        let rotated = test_jwt(json!({"aud": "sts.amazonaws.com", "exp": 1900003600}));
+        std::fs::write(token_file.path(), &rotated).unwrap();
+        assert!(workload.reload().unwrap());
+        assert_eq!(workload.expires_at(), Utc.timestamp_opt(1900003600, 0).single());
+    }
+
+    #[tokio::test]
+    async fn test_aws_instance_metadata() {
+        let mut server = mockito::Server::new_async().await;
+        let session = server
+            .mock("PUT", "/latest/api/token")
+            .match_header("X-aws-ec2-metadata-token-ttl-seconds", "21600")
+            .with_body("imds-session")
+            .create_async()
+            .await;
+        let document = server
+            .mock("GET", "/latest/dynamic/instance-identity/pkcs7")
+            .match_header("X-aws-ec2-metadata-token", "imds-session")
+            .with_body("MIAGCSqGSIb3\nDQEHAqCAMIAC")
+            .create_async()
+            .await;
+
+        let workload = WorkloadIdentity::aws_from_sources(|_| None, &server.url()).await.unwrap();
+        assert_eq!(workload.provider(), "aws");
+        assert_eq!(workload.identity().token(), "MIAGCSqGSIb3DQEHAqCAMIAC");
+        assert!(!workload.reload().unwrap());
+        session.assert_async().await;
+        document.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_aws_no_source() {
+        let err = WorkloadIdentity::aws_from_sources(|_| None, "http://127.0.0.1:1").await.unwrap_err();
+        assert!(matches!(err, crate::error::VaultError::Identity(IdentityError::InvalidWorkload(_))));
+    }
+}
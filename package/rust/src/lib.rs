@@ -50,25 +50,95 @@
 //! - [`transport`]: Network abstraction layer
 //! - [`crypto`]: Cryptographic primitives (standard only)
 //! - [`audit`]: Automatic audit logging
+//! - [`transform`]: Secret payload post-processing
+//! - [`clock`]: Injectable time sources
+//! - [`cache`]: In-memory capability caching
+//! - [`metrics`]: Metrics hooks
 //! - [`error`]: Strong error typing
 //! - [`config`]: Configuration management
+//! - [`portable`]: `no_std` validation logic
+//!
+//! ## Feature flags
+//!
+//! - `std` (default): the capability, context, crypto and cache types;
+//!   without it the crate is `no_std` + `alloc` and provides only the
+//!   [`portable`] module
+//! - `client` (default): the async [`Client`] with its identity, transport,
+//!   audit and config modules, on tokio and reqwest
+//! - `compression` (default): gzip request and response bodies for the HTTP
+//!   transport (`Config::compression`), on flate2
+//! - `transport-http` (default): the HTTP transport; it shares the client's
+//!   reqwest stack, so it adds no dependencies of its own
+//! - `transport-unix`, `transport-mtls`, `transport-grpc` (default): the Unix
+//!   socket, mTLS and gRPC (tonic) transports; `connect` rejects a transport
+//!   whose feature is off
+//! - `transport-fetch`: `FetchTransport` over the browser `fetch` API, for
+//!   `wasm32-unknown-unknown` builds only
+//! - `otel`: propagate W3C trace context from `tracing-opentelemetry` spans
+//! - `metrics`: a recorder backed by the `metrics` crate
+//! - `full`: every native transport
+//!
+//! ## WebAssembly
+//!
+//! On `wasm32` only the runtime-independent modules are built: [`capability`],
+//! [`context`], [`error`], [`crypto`], [`clock`], [`cache`], [`transform`]
+//! and [`metrics`], without `Context::from_env`. The client, identity,
+//! transport, audit and config modules need tokio and the filesystem and are
+//! left out; enable `transport-fetch` to talk to Vault from the browser.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod portable;
+
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
 pub mod client;
+#[cfg(feature = "std")]
 pub mod capability;
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
 pub mod identity;
+#[cfg(feature = "std")]
 pub mod context;
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
 pub mod transport;
+#[cfg(all(target_arch = "wasm32", feature = "transport-fetch"))]
+pub mod fetch;
+#[cfg(feature = "std")]
 pub mod crypto;
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
 pub mod audit;
+#[cfg(feature = "std")]
+pub mod transform;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "std")]
 pub mod error;
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
 pub mod config;
 
 // Re-export main types for convenience
-pub use client::Client;
-pub use capability::{Capability, CapabilityRequest, Domain, Action};
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+pub use client::{AccessResponse, CapabilityEvent, CapabilityGuard, Client, ClientBuilder};
+#[cfg(feature = "std")]
+pub use capability::{
+    Action, Capability, CapabilityRequest, DatabaseCapability, Domain, ResourcePath, SshCapability, TlsCapability,
+};
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
 pub use identity::{Identity, WorkloadIdentity};
+#[cfg(feature = "std")]
 pub use context::{Context, ContextBuilder};
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+pub use transport::TransportPool;
+#[cfg(all(target_arch = "wasm32", feature = "transport-fetch"))]
+pub use fetch::FetchTransport;
+#[cfg(feature = "std")]
 pub use error::{VaultError, Result};
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
 pub use config::Config;
 
 /// SDK version
@@ -0,0 +1,107 @@
+//! Metrics hooks for Aether Vault operations.
+//!
+//! The client reports operation counts, latencies, errors, retries and
+//! response cache lookups to a [`Recorder`]. Nothing is recorded by
+//! default; with the `metrics` feature, [`MetricsRecorder`] forwards every
+//! measurement to the `metrics` crate facade, and from there to exporters
+//! such as Prometheus.
+
+use std::time::Duration;
+
+/// Destination for client metrics
+///
+/// Every method has a no-op default, so a recorder only implements what it
+/// exports. Operations are named as in audit events (`request`, `access`,
+/// `revoke`, `refresh`, `bootstrap`) and errors are labelled with
+/// [`crate::error::VaultError::error_code`].
+pub trait Recorder: Send + Sync {
+    /// An operation completed, successfully or not
+    fn record_request(&self, _operation: &'static str) {}
+
+    /// Time an operation took, including retries
+    fn record_latency(&self, _operation: &'static str, _latency: Duration) {}
+
+    /// An operation failed
+    fn record_error(&self, _operation: &'static str, _error_code: &'static str) {}
+
+    /// A transport call failed with a retryable error and will be retried
+    fn record_retry(&self, _error_code: &'static str) {}
+
+    /// An access was answered from the response cache
+    fn record_cache_hit(&self) {}
+
+    /// A cacheable access had to go to the server
+    fn record_cache_miss(&self) {}
+}
+
+/// Recorder discarding every measurement
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopRecorder;
+
+impl Recorder for NoopRecorder {}
+
+/// Recorder forwarding to the `metrics` crate
+///
+/// Emits `vault_requests_total`, `vault_errors_total` and
+/// `vault_retries_total` counters, the `vault_request_duration_seconds`
+/// histogram, and `vault_cache_hits_total`/`vault_cache_misses_total`.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsRecorder;
+
+#[cfg(feature = "metrics")]
+impl Recorder for MetricsRecorder {
+    fn record_request(&self, operation: &'static str) {
+        ::metrics::counter!("vault_requests_total", "operation" => operation).increment(1);
+    }
+
+    fn record_latency(&self, operation: &'static str, latency: Duration) {
+        ::metrics::histogram!("vault_request_duration_seconds", "operation" => operation)
+            .record(latency.as_secs_f64());
+    }
+
+    fn record_error(&self, operation: &'static str, error_code: &'static str) {
+        ::metrics::counter!("vault_errors_total", "operation" => operation, "code" => error_code).increment(1);
+    }
+
+    fn record_retry(&self, error_code: &'static str) {
+        ::metrics::counter!("vault_retries_total", "code" => error_code).increment(1);
+    }
+
+    fn record_cache_hit(&self) {
+        ::metrics::counter!("vault_cache_hits_total").increment(1);
+    }
+
+    fn record_cache_miss(&self) {
+        ::metrics::counter!("vault_cache_misses_total").increment(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Default)]
+    struct Errors(AtomicU32);
+
+    impl Recorder for Errors {
+        fn record_error(&self, _operation: &'static str, _error_code: &'static str) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_recorder_defaults_are_no_ops() {
+        let errors = Errors::default();
+        let recorder: &dyn Recorder = &errors;
+        recorder.record_request("access");
+        recorder.record_latency("access", Duration::from_millis(5));
+        recorder.record_cache_hit();
+        recorder.record_error("access", "ACCESS_DENIED");
+        assert_eq!(errors.0.load(Ordering::SeqCst), 1);
+
+        let noop: &dyn Recorder = &NoopRecorder;
+        noop.record_retry("TIMEOUT");
+    }
+}
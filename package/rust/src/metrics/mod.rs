@@ -0,0 +1,5 @@
+pub mod metrics;
+
+#[cfg(feature = "metrics")]
+pub use metrics::MetricsRecorder;
+pub use metrics::{NoopRecorder, Recorder};
@@ -0,0 +1,67 @@
+//! Expiry, usage and context constraint checks.
+//!
+//! Instants are any ordered type, such as `chrono::DateTime<Utc>` or Unix
+//! seconds as `i64`, as long as every argument of one call uses the same.
+
+/// Whether a capability expiring at `expires_at` has expired at `now`
+///
+/// The expiry instant itself is still valid.
+pub fn is_expired<T: PartialOrd>(now: T, expires_at: T) -> bool {
+    now > expires_at
+}
+
+/// Whether `now` falls within `start..=end`
+pub fn in_window<T: PartialOrd>(now: T, start: T, end: T) -> bool {
+    now >= start && now <= end
+}
+
+/// Whether `day` (0=Sunday, 6=Saturday) is one of `allowed_days`
+///
+/// Any day is allowed when `allowed_days` is absent.
+pub fn day_allowed(allowed_days: Option<&[u8]>, day: u8) -> bool {
+    allowed_days.map_or(true, |days| days.contains(&day))
+}
+
+/// Whether another use fits under an absolute `max_uses` limit
+pub fn within_use_limit(current_uses: u32, max_uses: Option<u32>) -> bool {
+    max_uses.map_or(true, |max_uses| current_uses < max_uses)
+}
+
+/// Whether `value` satisfies an optional allow-list
+///
+/// An absent list allows everything; a present one, even empty, allows only
+/// its members.
+pub fn context_allows<'a>(allowed: Option<impl IntoIterator<Item = &'a str>>, value: &str) -> bool {
+    allowed.map_or(true, |allowed| allowed.into_iter().any(|member| member == value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiry_and_window() {
+        assert!(!is_expired(10i64, 10));
+        assert!(is_expired(11i64, 10));
+        assert!(in_window(5i64, 5, 10));
+        assert!(in_window(10i64, 5, 10));
+        assert!(!in_window(11i64, 5, 10));
+        assert!(!in_window(4i64, 5, 10));
+    }
+
+    #[test]
+    fn test_limits_and_allow_lists() {
+        assert!(day_allowed(None, 3));
+        assert!(day_allowed(Some(&[1, 3]), 3));
+        assert!(!day_allowed(Some(&[1, 3]), 0));
+
+        assert!(within_use_limit(2, None));
+        assert!(within_use_limit(2, Some(3)));
+        assert!(!within_use_limit(3, Some(3)));
+
+        assert!(context_allows(None::<[&str; 0]>, "prod"));
+        assert!(context_allows(Some(["prod", "staging"]), "prod"));
+        assert!(!context_allows(Some(["staging"]), "prod"));
+        assert!(!context_allows(Some([""; 0]), "prod"));
+    }
+}
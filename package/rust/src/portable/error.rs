@@ -0,0 +1,33 @@
+//! Errors from the `no_std` validation core.
+
+use alloc::string::String;
+use ::core::fmt;
+
+/// Failure of a core validation check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreError {
+    /// Public key of the wrong length, carrying the length given
+    InvalidKeyLength(usize),
+    /// Signature does not verify under the public key
+    SignatureVerificationFailed,
+    /// Unknown domain name
+    InvalidDomain(String),
+    /// Unknown action name
+    InvalidAction(String),
+}
+
+impl fmt::Display for CoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoreError::InvalidKeyLength(len) => {
+                write!(f, "expected 32-byte Ed25519 public key, got {} bytes", len)
+            }
+            CoreError::SignatureVerificationFailed => write!(f, "Signature verification failed"),
+            CoreError::InvalidDomain(name) => write!(f, "Invalid domain: {}", name),
+            CoreError::InvalidAction(name) => write!(f, "Invalid action: {}", name),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CoreError {}
@@ -0,0 +1,60 @@
+//! IP address and CIDR constraint checks.
+
+use ::core::net::IpAddr;
+
+/// Parse an IP constraint into its network address and prefix length
+///
+/// A bare address is a single-host block. Returns `None` for anything that
+/// is not an address or a CIDR block with a prefix fitting its family.
+pub fn parse_ip_constraint(constraint: &str) -> Option<(IpAddr, u32)> {
+    let (address, prefix) = match constraint.trim().split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix.parse::<u32>().ok()?)),
+        None => (constraint.trim(), None),
+    };
+    let network = address.parse::<IpAddr>().ok()?;
+    let bits = if network.is_ipv4() { 32 } else { 128 };
+    match prefix {
+        Some(prefix) if prefix > bits => None,
+        prefix => Some((network, prefix.unwrap_or(bits))),
+    }
+}
+
+/// Whether `ip` matches an IP constraint, either an address or a CIDR block
+pub fn ip_allowed(constraint: &str, ip: IpAddr) -> bool {
+    let Some((network, prefix)) = parse_ip_constraint(constraint) else {
+        return false;
+    };
+
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            prefix_matches(u32::from(network) as u128, u32::from(ip) as u128, prefix, 32)
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            prefix_matches(u128::from(network), u128::from(ip), prefix, 128)
+        }
+        _ => false,
+    }
+}
+
+fn prefix_matches(network: u128, ip: u128, prefix: u32, bits: u32) -> bool {
+    let shift = bits - prefix;
+    shift >= bits || network >> shift == ip >> shift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_allowed() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        assert!(ip_allowed("10.0.0.0/8", ip("10.1.2.3")));
+        assert!(!ip_allowed("10.0.0.0/8", ip("11.0.0.1")));
+        assert!(ip_allowed("2001:db8::/32", ip("2001:db8::7")));
+        assert!(!ip_allowed("2001:db8::/32", ip("10.0.0.1")));
+        assert!(ip_allowed("0.0.0.0/0", ip("192.0.2.1")));
+        assert!(!ip_allowed("10.0.0/24", ip("10.0.0.1")));
+        assert_eq!(parse_ip_constraint("10.0.0.1"), Some((ip("10.0.0.1"), 32)));
+        assert_eq!(parse_ip_constraint("10.0.0.0/33"), None);
+    }
+}
@@ -0,0 +1,34 @@
+//! Validation logic that needs only `core` and `alloc`.
+//!
+//! Builds with default features off (`no_std` + `alloc`), so constrained
+//! agents can verify capabilities without the async client. The std types
+//! in [`crate::capability`] and [`crate::crypto`] delegate here.
+//!
+//! Available without `std`:
+//!
+//! - [`Domain`] and [`Action`]: parsing, display and action implication
+//! - [`signing_payload`], [`encode_list`] and [`encode_set`]: the canonical
+//!   bytes a capability signature covers
+//! - [`verify_ed25519`]: Ed25519 verification against a raw public key
+//! - [`is_expired`], [`in_window`], [`day_allowed`], [`within_use_limit`] and
+//!   [`context_allows`]: the expiry, time window, usage and context checks
+//! - [`parse_ip_constraint`] and [`ip_allowed`]: IP and CIDR constraint checks
+//! - [`CoreError`]: their error type
+//!
+//! `Capability` and its context types hold `chrono` timestamps and std
+//! collections, so they still require `std`; without it, feed their fields
+//! to these functions.
+
+pub mod check;
+pub mod error;
+pub mod ip;
+pub mod name;
+pub mod signature;
+
+pub use check::{context_allows, day_allowed, in_window, is_expired, within_use_limit};
+pub use error::CoreError;
+pub use ip::{ip_allowed, parse_ip_constraint};
+pub use name::{Action, Domain};
+pub use signature::{
+    encode_list, encode_set, signing_payload, verify_ed25519, SIGNING_DOMAIN_SEPARATOR, SIGNING_DOMAIN_SEPARATOR_V1,
+};
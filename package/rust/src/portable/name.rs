@@ -0,0 +1,217 @@
+//! Capability domains and actions.
+
+use super::CoreError;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use ::core::fmt;
+
+/// Access domains
+///
+/// Serialized as its [`Domain::parse`] string form, e.g. `"custom:x"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Domain {
+    /// Database access
+    Database,
+    /// TLS certificates
+    Tls,
+    /// SMTP access
+    Smtp,
+    /// IMAP access
+    Imap,
+    /// Docker registry
+    Docker,
+    /// Git repositories
+    Git,
+    /// File system access
+    Filesystem,
+    /// Cloud provider access
+    Cloud,
+    /// API access
+    Api,
+    /// SSH access
+    Ssh,
+    /// Custom domain
+    Custom(String),
+}
+
+impl fmt::Display for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Domain::Database => write!(f, "database"),
+            Domain::Tls => write!(f, "tls"),
+            Domain::Smtp => write!(f, "smtp"),
+            Domain::Imap => write!(f, "imap"),
+            Domain::Docker => write!(f, "docker"),
+            Domain::Git => write!(f, "git"),
+            Domain::Filesystem => write!(f, "filesystem"),
+            Domain::Cloud => write!(f, "cloud"),
+            Domain::Api => write!(f, "api"),
+            Domain::Ssh => write!(f, "ssh"),
+            Domain::Custom(name) => write!(f, "custom:{}", name),
+        }
+    }
+}
+
+/// Access actions
+///
+/// Serialized as its [`Action::parse`] string form, e.g. `"custom:x"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Read access
+    Read,
+    /// Write access
+    Write,
+    /// Delete access
+    Delete,
+    /// Execute access
+    Execute,
+    /// List access
+    List,
+    /// Admin access
+    Admin,
+    /// Create access
+    Create,
+    /// Update access
+    Update,
+    /// Custom action
+    Custom(String),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Read => write!(f, "read"),
+            Action::Write => write!(f, "write"),
+            Action::Delete => write!(f, "delete"),
+            Action::Execute => write!(f, "execute"),
+            Action::List => write!(f, "list"),
+            Action::Admin => write!(f, "admin"),
+            Action::Create => write!(f, "create"),
+            Action::Update => write!(f, "update"),
+            Action::Custom(name) => write!(f, "custom:{}", name),
+        }
+    }
+}
+
+/// Name after a case-insensitive `custom:` prefix, with its case preserved
+fn custom_name(s: &str) -> Option<&str> {
+    let prefix = s.get(..7)?;
+    prefix.eq_ignore_ascii_case("custom:").then(|| &s[7..])
+}
+
+impl Domain {
+    /// Parse domain from string
+    pub fn parse(s: &str) -> Result<Self, CoreError> {
+        match s.to_lowercase().as_str() {
+            "database" => Ok(Domain::Database),
+            "tls" => Ok(Domain::Tls),
+            "smtp" => Ok(Domain::Smtp),
+            "imap" => Ok(Domain::Imap),
+            "docker" => Ok(Domain::Docker),
+            "git" => Ok(Domain::Git),
+            "filesystem" => Ok(Domain::Filesystem),
+            "cloud" => Ok(Domain::Cloud),
+            "api" => Ok(Domain::Api),
+            "ssh" => Ok(Domain::Ssh),
+            _ => match custom_name(s) {
+                Some(name) => Ok(Domain::Custom(name.to_string())),
+                None => Err(CoreError::InvalidDomain(s.to_string())),
+            },
+        }
+    }
+
+    /// Get all standard domains
+    pub fn standard_domains() -> Vec<&'static str> {
+        vec![
+            "database", "tls", "smtp", "imap", "docker",
+            "git", "filesystem", "cloud", "api", "ssh"
+        ]
+    }
+}
+
+impl Action {
+    /// Parse action from string
+    pub fn parse(s: &str) -> Result<Self, CoreError> {
+        match s.to_lowercase().as_str() {
+            "read" => Ok(Action::Read),
+            "write" => Ok(Action::Write),
+            "delete" => Ok(Action::Delete),
+            "execute" => Ok(Action::Execute),
+            "list" => Ok(Action::List),
+            "admin" => Ok(Action::Admin),
+            "create" => Ok(Action::Create),
+            "update" => Ok(Action::Update),
+            _ => match custom_name(s) {
+                Some(name) => Ok(Action::Custom(name.to_string())),
+                None => Err(CoreError::InvalidAction(s.to_string())),
+            },
+        }
+    }
+
+    /// Get all standard actions
+    pub fn standard_actions() -> Vec<&'static str> {
+        vec![
+            "read", "write", "delete", "execute", "list",
+            "admin", "create", "update"
+        ]
+    }
+
+    /// Actions directly granted by holding `self` (see [`Action::implies`])
+    fn directly_implies(&self) -> &'static [Action] {
+        match self {
+            Action::Admin => &[
+                Action::Create,
+                Action::Update,
+                Action::Delete,
+                Action::Write,
+                Action::Read,
+                Action::List,
+                Action::Execute,
+            ],
+            Action::Write => &[Action::Create, Action::Update],
+            Action::Update => &[Action::Read],
+            Action::Create
+            | Action::Delete
+            | Action::Read
+            | Action::List
+            | Action::Execute
+            | Action::Custom(_) => &[],
+        }
+    }
+
+    /// Whether holding `self` also grants `other`
+    ///
+    /// Every action implies itself, and implication is transitive over this
+    /// table of direct grants (so `Write` implies `Read` through `Update`).
+    /// Custom actions imply only themselves.
+    ///
+    /// | Action    | Directly implies                                        |
+    /// |-----------|---------------------------------------------------------|
+    /// | `Admin`   | Create, Update, Delete, Write, Read, List, Execute      |
+    /// | `Write`   | Create, Update                                          |
+    /// | `Update`  | Read                                                    |
+    /// | `Create`  | —                                                       |
+    /// | `Delete`  | —                                                       |
+    /// | `Read`    | —                                                       |
+    /// | `List`    | —                                                       |
+    /// | `Execute` | —                                                       |
+    pub fn implies(&self, other: &Action) -> bool {
+        self == other || self.directly_implies().iter().any(|granted| granted.implies(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_without_std() {
+        assert_eq!(Domain::parse("SSH"), Ok(Domain::Ssh));
+        assert_eq!(Domain::parse("Custom:Edge"), Ok(Domain::Custom("Edge".to_string())));
+        assert_eq!(Domain::parse("bogus"), Err(CoreError::InvalidDomain("bogus".to_string())));
+        assert_eq!(Action::parse("bogus"), Err(CoreError::InvalidAction("bogus".to_string())));
+        assert!(Action::Admin.implies(&Action::Read));
+        assert!(!Action::Read.implies(&Action::Admin));
+    }
+}
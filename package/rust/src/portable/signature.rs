@@ -0,0 +1,120 @@
+//! Capability signing payloads and Ed25519 verification.
+
+use super::CoreError;
+use alloc::vec::Vec;
+
+/// Prefix of the signing payload of version 1 capabilities
+pub const SIGNING_DOMAIN_SEPARATOR_V1: &[u8] = b"AETHER-VAULT-CAPABILITY-V1";
+
+/// Prefix of the signing payload of capabilities from version 2 on
+pub const SIGNING_DOMAIN_SEPARATOR: &[u8] = b"AETHER-VAULT-CAPABILITY-V2";
+
+/// Canonical bytes covered by the signature of a capability of wire format `version`
+///
+/// For version 1, [`SIGNING_DOMAIN_SEPARATOR_V1`] then each field as a
+/// big-endian `u32` length and its bytes. From version 2 on,
+/// [`SIGNING_DOMAIN_SEPARATOR`] and `version` as a big-endian `u16`, then
+/// the fields the same way. Field order and formats are documented on
+/// `Capability::signing_payload`.
+pub fn signing_payload<'a>(version: u16, fields: impl IntoIterator<Item = &'a [u8]>) -> Vec<u8> {
+    let mut payload = if version <= 1 {
+        SIGNING_DOMAIN_SEPARATOR_V1.to_vec()
+    } else {
+        let mut payload = SIGNING_DOMAIN_SEPARATOR.to_vec();
+        payload.extend_from_slice(&version.to_be_bytes());
+        payload
+    };
+    for field in fields {
+        push_prefixed(&mut payload, field);
+    }
+    payload
+}
+
+/// Canonical encoding of an optional list, for use as one payload field
+///
+/// Empty when absent. Otherwise the item count as a big-endian `u32`, then
+/// each item length-prefixed like a field, in the order given.
+pub fn encode_list<'a>(items: Option<impl IntoIterator<Item = &'a [u8]>>) -> Vec<u8> {
+    let Some(items) = items else {
+        return Vec::new();
+    };
+    let mut count = 0u32;
+    let mut body = Vec::new();
+    for item in items {
+        push_prefixed(&mut body, item);
+        count += 1;
+    }
+    let mut encoded = count.to_be_bytes().to_vec();
+    encoded.extend_from_slice(&body);
+    encoded
+}
+
+/// Canonical encoding of an optional set of strings
+///
+/// Like [`encode_list`] with the members sorted and deduplicated, so the
+/// encoding does not depend on iteration order.
+pub fn encode_set<'a>(members: Option<impl IntoIterator<Item = &'a str>>) -> Vec<u8> {
+    encode_list(members.map(|members| {
+        let mut members: Vec<&str> = members.into_iter().collect();
+        members.sort_unstable();
+        members.dedup();
+        members.into_iter().map(str::as_bytes)
+    }))
+}
+
+fn push_prefixed(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// Verify an Ed25519 signature against a raw 32-byte public key
+pub fn verify_ed25519(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<(), CoreError> {
+    if public_key.len() != 32 {
+        return Err(CoreError::InvalidKeyLength(public_key.len()));
+    }
+
+    let key = ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key);
+    key.verify(message, signature)
+        .map_err(|_| CoreError::SignatureVerificationFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    #[test]
+    fn test_signing_payload_and_verify() {
+        let payload = signing_payload(2, [&b"ab"[..], b""]);
+        let mut expected = SIGNING_DOMAIN_SEPARATOR.to_vec();
+        expected.extend_from_slice(&[0, 2, 0, 0, 0, 2, b'a', b'b', 0, 0, 0, 0]);
+        assert_eq!(payload, expected);
+
+        // Version 1 keeps its own separator and carries no version
+        let mut expected = SIGNING_DOMAIN_SEPARATOR_V1.to_vec();
+        expected.extend_from_slice(&[0, 0, 0, 2, b'a', b'b', 0, 0, 0, 0]);
+        assert_eq!(signing_payload(1, [&b"ab"[..], b""]), expected);
+
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&[3u8; 32]).unwrap();
+        let signature = key_pair.sign(&payload);
+        let public_key = key_pair.public_key().as_ref();
+        assert_eq!(verify_ed25519(public_key, &payload, signature.as_ref()), Ok(()));
+        assert_eq!(
+            verify_ed25519(public_key, &signing_payload(2, [&b"ab"[..], b"c"]), signature.as_ref()),
+            Err(CoreError::SignatureVerificationFailed)
+        );
+        assert_eq!(
+            verify_ed25519(&public_key[..31], &payload, signature.as_ref()),
+            Err(CoreError::InvalidKeyLength(31))
+        );
+    }
+
+    #[test]
+    fn test_encode_set_is_canonical() {
+        assert!(encode_set(None::<[&str; 0]>).is_empty());
+        assert_eq!(encode_set(Some([""; 0])), [0, 0, 0, 0]);
+        assert_eq!(encode_set(Some(["b", "a", "b"])), encode_set(Some(["a", "b"])));
+        assert_eq!(encode_set(Some(["a", "b"])), [0, 0, 0, 2, 0, 0, 0, 1, b'a', 0, 0, 0, 1, b'b']);
+        assert_ne!(encode_list(Some([&b"b"[..], b"a"])), encode_list(Some([&b"a"[..], b"b"])));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod transform;
+
+pub use transform::{
+    Base64Decode, JsonPointer, SecretBytes, SecretTransform, Template, TransformPipeline,
+};
\ No newline at end of file
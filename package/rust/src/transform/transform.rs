@@ -0,0 +1,153 @@
+//! Post-processing of retrieved secret payloads.
+//!
+//! Transforms turn the raw access payload into the form an application
+//! needs (decode, extract, render). Intermediate buffers are wrapped in
+//! [`Zeroizing`] so they are wiped as soon as each stage completes.
+
+use crate::error::{Result, VaultError};
+use base64::Engine;
+use zeroize::Zeroizing;
+
+/// Secret payload buffer that is wiped on drop
+pub type SecretBytes = Zeroizing<Vec<u8>>;
+
+/// A single transformation step over a secret payload
+pub trait SecretTransform: Send + Sync {
+    /// Transform the payload
+    fn apply(&self, input: SecretBytes) -> Result<SecretBytes>;
+}
+
+/// Ordered sequence of transforms applied one after another
+#[derive(Default)]
+pub struct TransformPipeline {
+    stages: Vec<Box<dyn SecretTransform>>,
+}
+
+/// Decode standard base64
+#[derive(Debug, Clone, Default)]
+pub struct Base64Decode;
+
+/// Extract a value from a JSON document using an RFC 6901 pointer
+#[derive(Debug, Clone)]
+pub struct JsonPointer {
+    pointer: String,
+}
+
+/// Render the payload into a template, replacing every `{{value}}`
+#[derive(Debug, Clone)]
+pub struct Template {
+    template: String,
+}
+
+impl TransformPipeline {
+    /// Create an empty pipeline
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a stage to the pipeline
+    pub fn then<S: SecretTransform + 'static>(mut self, stage: S) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+}
+
+impl SecretTransform for TransformPipeline {
+    fn apply(&self, input: SecretBytes) -> Result<SecretBytes> {
+        self.stages.iter().try_fold(input, |payload, stage| stage.apply(payload))
+    }
+}
+
+impl SecretTransform for Base64Decode {
+    fn apply(&self, input: SecretBytes) -> Result<SecretBytes> {
+        let trimmed = String::from_utf8_lossy(&input);
+        base64::engine::general_purpose::STANDARD
+            .decode(trimmed.trim())
+            .map(Zeroizing::new)
+            .map_err(|e| VaultError::InvalidResponse(format!("base64 decode failed: {}", e)))
+    }
+}
+
+impl JsonPointer {
+    /// Create a JSON pointer extraction (e.g. `/data/password`)
+    pub fn new(pointer: impl Into<String>) -> Self {
+        Self { pointer: pointer.into() }
+    }
+}
+
+impl SecretTransform for JsonPointer {
+    fn apply(&self, input: SecretBytes) -> Result<SecretBytes> {
+        let document: serde_json::Value = serde_json::from_slice(&input)
+            .map_err(|e| VaultError::InvalidResponse(format!("payload is not JSON: {}", e)))?;
+
+        let value = document.pointer(&self.pointer)
+            .ok_or_else(|| VaultError::InvalidResponse(
+                format!("JSON pointer {} not found in payload", self.pointer)
+            ))?;
+
+        // Strings are extracted verbatim, anything else as JSON
+        let bytes = match value {
+            serde_json::Value::String(text) => text.as_bytes().to_vec(),
+            other => serde_json::to_vec(other)?,
+        };
+        Ok(Zeroizing::new(bytes))
+    }
+}
+
+impl Template {
+    /// Create a template transform
+    pub fn new(template: impl Into<String>) -> Self {
+        Self { template: template.into() }
+    }
+}
+
+impl SecretTransform for Template {
+    fn apply(&self, input: SecretBytes) -> Result<SecretBytes> {
+        let value = Zeroizing::new(String::from_utf8(input.to_vec())
+            .map_err(|_| VaultError::InvalidResponse("payload is not valid UTF-8".to_string()))?);
+        Ok(Zeroizing::new(self.template.replace("{{value}}", &value).into_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(s: &str) -> SecretBytes {
+        Zeroizing::new(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_base64_decode() {
+        let output = Base64Decode.apply(bytes("c2VjcmV0LXZhbHVl\n")).unwrap();
+        assert_eq!(output.as_slice(), b"secret-value");
+
+        let err = Base64Decode.apply(bytes("not base64!")).unwrap_err();
+        assert!(matches!(err, VaultError::InvalidResponse(_)));
+    }
+
+    #[test]
+    fn test_json_pointer_extract() {
+        let payload = bytes(r#"{"data":{"password":"hunter2","port":5432}}"#);
+        let output = JsonPointer::new("/data/password").apply(payload.clone()).unwrap();
+        assert_eq!(output.as_slice(), b"hunter2");
+
+        let output = JsonPointer::new("/data/port").apply(payload.clone()).unwrap();
+        assert_eq!(output.as_slice(), b"5432");
+
+        let err = JsonPointer::new("/data/missing").apply(payload).unwrap_err();
+        assert!(matches!(err, VaultError::InvalidResponse(_)));
+    }
+
+    #[test]
+    fn test_pipeline_decode_extract_render() {
+        // base64 of {"password":"hunter2"}
+        let pipeline = TransformPipeline::new()
+            .then(Base64Decode)
+            .then(JsonPointer::new("/password"))
+            .then(Template::new("DB_PASSWORD={{value}}"));
+
+        let output = pipeline.apply(bytes("eyJwYXNzd29yZCI6Imh1bnRlcjIifQ==")).unwrap();
+        assert_eq!(output.as_slice(), b"DB_PASSWORD=hunter2");
+    }
+}
@@ -1,3 +1,10 @@
 pub mod transport;
 
-pub use transport::{Transport, HttpTransport, UnixTransport, MtlsTransport};
\ No newline at end of file
+pub use transport::{connect, HttpTransport, MockTransport, SecretStream, Transport, TransportPool};
+pub(crate) use transport::{with_request_id, with_request_timeout};
+#[cfg(feature = "transport-grpc")]
+pub use transport::{GrpcMessage, GrpcTransport};
+#[cfg(feature = "transport-mtls")]
+pub use transport::MtlsTransport;
+#[cfg(feature = "transport-unix")]
+pub use transport::UnixTransport;
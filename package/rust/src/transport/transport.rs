@@ -4,14 +4,23 @@
 //! with async-first design and proper error handling.
 
 use crate::capability::{Capability, CapabilityRequest};
-use crate::error::{Result, TransportError};
+use crate::client::{PolicyDecision, RevocationReceipt, RevocationStatus, RevokeReason, ServerHints};
+use crate::error::{CapabilityError, ConfigError, IdentityError, Result, TransportError, VaultError};
 use crate::identity::Identity;
 use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError};
 use std::time::Duration;
+use zeroize::Zeroizing;
+
+/// Secret payload streamed from Vault
+pub type SecretStream = std::pin::Pin<Box<dyn tokio::io::AsyncRead + Send>>;
 
 /// Transport trait for different communication mechanisms
+///
+/// `Debug` implementations must not print credentials.
 #[async_trait]
-pub trait Transport: Send + Sync {
+pub trait Transport: std::fmt::Debug + Send + Sync {
     /// Request a capability from Vault
     async fn request_capability(
         &self,
@@ -19,13 +28,76 @@ pub trait Transport: Send + Sync {
         request: &CapabilityRequest,
     ) -> Result<Capability>;
 
+    /// Request several capabilities at once, with one result per request
+    ///
+    /// The default implementation issues the requests concurrently.
+    async fn request_capabilities(
+        &self,
+        identity: &Identity,
+        requests: &[CapabilityRequest],
+    ) -> Result<Vec<Result<Capability>>> {
+        Ok(request_each(self, identity, requests).await)
+    }
+
+    /// Ask whether a capability request would be granted, without issuing it
+    ///
+    /// The default implementation reports policy checks as unsupported.
+    async fn check_capability(&self, _identity: &Identity, _request: &CapabilityRequest) -> Result<PolicyDecision> {
+        Err(TransportError::Protocol("policy checks are not supported by this transport".to_string()).into())
+    }
+
     /// Access resource using a capability
+    ///
+    /// The server refusing the capability as revoked or expired is reported
+    /// as `CapabilityError::Revoked` or `CapabilityError::Expired`.
     async fn access_with_capability<T>(&self, capability: &Capability) -> Result<T>
     where
         T: serde::de::DeserializeOwned + Send;
 
+    /// Access resource using a capability, recording a reason for this use
+    async fn access_with_reason<T>(&self, capability: &Capability, reason: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send;
+
+    /// Access resource using a capability, streaming the raw payload
+    ///
+    /// The default implementation reports streaming as unsupported.
+    async fn access_stream(&self, _capability: &Capability) -> Result<SecretStream> {
+        Err(TransportError::Protocol("streaming access is not supported by this transport".to_string()).into())
+    }
+
     /// Revoke a capability
-    async fn revoke_capability(&self, capability_id: uuid::Uuid) -> Result<()>;
+    async fn revoke_capability(&self, capability_id: uuid::Uuid) -> Result<RevocationReceipt>;
+
+    /// Revoke a capability, telling the server why
+    ///
+    /// The default implementation drops the reason and calls
+    /// [`Transport::revoke_capability`].
+    async fn revoke_capability_with_reason(
+        &self,
+        capability_id: uuid::Uuid,
+        _reason: &RevokeReason,
+    ) -> Result<RevocationReceipt> {
+        self.revoke_capability(capability_id).await
+    }
+
+    /// Revoke several capabilities at once, with one result per id
+    ///
+    /// The default implementation revokes them concurrently.
+    async fn revoke_capabilities(&self, capability_ids: &[uuid::Uuid]) -> Result<Vec<Result<RevocationReceipt>>> {
+        Ok(revoke_each(self, capability_ids).await)
+    }
+
+    /// Get the current state of a previously requested revocation
+    async fn revocation_status(&self, capability_id: uuid::Uuid) -> Result<RevocationReceipt>;
+
+    /// Whether the server has revoked a capability, if the transport can tell
+    ///
+    /// Lets watchers notice revocations made by someone other than this
+    /// client. The default implementation reports `None` (unknown).
+    async fn is_revoked(&self, _capability_id: uuid::Uuid) -> Result<Option<bool>> {
+        Ok(None)
+    }
 
     /// Refresh a capability
     async fn refresh_capability(
@@ -41,353 +113,1540 @@ pub trait Transport: Send + Sync {
     /// Health check
     async fn health_check(&self) -> Result<crate::client::HealthStatus>;
 
+    /// Lightweight liveness probe, returning the round-trip latency
+    ///
+    /// The default implementation reports pings as unsupported.
+    async fn ping(&self) -> Result<Duration> {
+        Err(TransportError::Protocol("ping is not supported by this transport".to_string()).into())
+    }
+
+    /// Fetch the server's client configuration hints
+    async fn client_config(&self) -> Result<ServerHints>;
+
+    /// Exchange a one-time bootstrap token for a workload identity
+    async fn bootstrap_identity(&self, one_time_token: &str) -> Result<Identity>;
+
+    /// Use `timeout` as the request timeout from the next call on
+    ///
+    /// Returns `false` when the transport cannot change it in place, in
+    /// which case a new transport has to be connected instead.
+    fn set_request_timeout(&self, _timeout: Duration) -> bool {
+        false
+    }
+
     /// Close transport connection
     async fn close(&self) -> Result<()>;
 }
 
-/// HTTP/HTTPS transport implementation
-pub struct HttpTransport {
-    client: reqwest::Client,
-    endpoint: String,
-    auth_header: Option<String>,
+/// Issue each request separately and concurrently
+async fn request_each<T: Transport + ?Sized>(
+    transport: &T,
+    identity: &Identity,
+    requests: &[CapabilityRequest],
+) -> Vec<Result<Capability>> {
+    futures::future::join_all(
+        requests.iter().map(|request| transport.request_capability(identity, request)),
+    )
+    .await
 }
 
-impl HttpTransport {
-    /// Create new HTTP transport
-    pub async fn new(config: &crate::config::Config) -> Result<Self> {
-        let mut client_builder = reqwest::Client::builder()
-            .timeout(config.timeouts.request)
-            .connect_timeout(config.timeouts.connect);
-
-        // Configure TLS if specified
-        if let Some(tls_config) = &config.tls {
-            // TODO: Configure TLS based on config
-        }
+/// Revoke each capability separately and concurrently
+async fn revoke_each<T: Transport + ?Sized>(
+    transport: &T,
+    capability_ids: &[uuid::Uuid],
+) -> Vec<Result<RevocationReceipt>> {
+    futures::future::join_all(capability_ids.iter().map(|id| transport.revoke_capability(*id))).await
+}
 
-        let client = client_builder.build()
-            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?;
+/// Body of a batch capability request
+#[derive(serde::Serialize)]
+struct BatchRequest<'a> {
+    requests: &'a [CapabilityRequest],
+}
 
-        // Prepare authentication header
-        let auth_header = match &config.auth.method {
-            crate::config::AuthMethod::Token => {
-                if let Some(token_file) = &config.auth.token_file {
-                    let token = std::fs::read_to_string(token_file)
-                        .map_err(|e| TransportError::ConnectionFailed(
-                            format!("Failed to read token file: {}", e)
-                        ))?;
-                    Some(format!("Bearer {}", token.trim()))
-                } else {
-                    None
-                }
-            }
-            _ => None,
-        };
+/// Body of a capability refresh request
+///
+/// The HTTP endpoint takes the id from the path, so it is omitted there.
+#[derive(serde::Serialize)]
+struct RefreshBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<uuid::Uuid>,
+    #[serde(rename = "ttl_seconds", with = "crate::capability::ttl_seconds")]
+    ttl: Duration,
+}
 
-        Ok(Self {
-            client,
-            endpoint: config.endpoint.clone(),
-            auth_header,
-        })
-    }
+/// Body of a batch revocation request
+#[derive(serde::Serialize)]
+struct RevokeBatchRequest<'a> {
+    ids: &'a [uuid::Uuid],
 }
 
-#[async_trait]
-impl Transport for HttpTransport {
-    async fn request_capability(
-        &self,
-        identity: &Identity,
-        request: &CapabilityRequest,
-    ) -> Result<Capability> {
-        let url = format!("{}/v1/capabilities", self.endpoint);
-        
-        let mut req_builder = self.client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .header("X-Vault-Identity", identity.token());
+/// Body of a batch revocation response
+#[derive(serde::Deserialize)]
+struct RevokeBatchResponse {
+    results: Vec<RevokeBatchItem>,
+}
 
-        if let Some(auth) = &self.auth_header {
-            req_builder = req_builder.header("Authorization", auth);
-        }
+/// Outcome of one revocation in a batch
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RevokeBatchItem {
+    Revoked { receipt: RevocationReceipt },
+    Failed { error: BatchItemError },
+}
 
-        let response = req_builder
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| TransportError::Http(e.to_string()))?;
+/// Body of a batch capability response
+#[derive(serde::Deserialize)]
+struct BatchResponse {
+    results: Vec<BatchItem>,
+}
 
-        if response.status().is_success() {
-            let capability: Capability = response.json().await
-                .map_err(|e| TransportError::InvalidResponse(e.to_string()))?;
-            Ok(capability)
-        } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            Err(TransportError::Http(
-                format!("HTTP {}: {}", status, error_text)
-            ).into())
-        }
-    }
+/// Outcome of one request in a batch
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum BatchItem {
+    Issued { capability: Capability },
+    Failed { error: BatchItemError },
+}
 
-    async fn access_with_capability<T>(&self, capability: &Capability) -> Result<T>
-    where
-        T: serde::de::DeserializeOwned + Send,
-    {
-        let url = format!("{}/v1/access", self.endpoint);
-        
-        let mut req_builder = self.client
-            .post(&url)
-            .header("Content-Type", "application/json");
+/// Error reported for one request in a batch
+#[derive(serde::Deserialize)]
+struct BatchItemError {
+    code: String,
+    message: String,
+}
 
-        if let Some(auth) = &self.auth_header {
-            req_builder = req_builder.header("Authorization", auth);
+impl BatchItem {
+    fn into_result(self) -> Result<Capability> {
+        match self {
+            BatchItem::Issued { capability } => Ok(capability),
+            BatchItem::Failed { error } => Err(error.into_error()),
         }
+    }
+}
 
-        let response = req_builder
-            .json(&capability)
-            .send()
-            .await
-            .map_err(|e| TransportError::Http(e.to_string()))?;
-
-        if response.status().is_success() {
-            let result: T = response.json().await
-                .map_err(|e| TransportError::InvalidResponse(e.to_string()))?;
-            Ok(result)
-        } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            Err(TransportError::Http(
-                format!("HTTP {}: {}", status, error_text)
-            ).into())
+impl RevokeBatchItem {
+    fn into_result(self) -> Result<RevocationReceipt> {
+        match self {
+            RevokeBatchItem::Revoked { receipt } => Ok(receipt),
+            RevokeBatchItem::Failed { error } => Err(error.into_error()),
         }
     }
+}
 
-    async fn revoke_capability(&self, capability_id: uuid::Uuid) -> Result<()> {
-        let url = format!("{}/v1/capabilities/{}/revoke", self.endpoint, capability_id);
-        
-        let mut req_builder = self.client
-            .post(&url);
-
-        if let Some(auth) = &self.auth_header {
-            req_builder = req_builder.header("Authorization", auth);
+impl BatchItemError {
+    fn into_error(self) -> VaultError {
+        match self.code.as_str() {
+            "ACCESS_DENIED" => VaultError::AccessDenied(self.message),
+            _ => VaultError::Server(format!("{}: {}", self.code, self.message)),
         }
+    }
+}
 
-        let response = req_builder
-            .send()
-            .await
-            .map_err(|e| TransportError::Http(e.to_string()))?;
+/// Body of an error response carrying a structured error
+#[derive(serde::Deserialize)]
+struct ErrorBody {
+    error: BatchItemError,
+}
 
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            Err(TransportError::Http(
-                format!("HTTP {}: {}", status, error_text)
-            ).into())
-        }
+/// Error for the server refusing `capability` itself with error `code`
+///
+/// `CAPABILITY_REVOKED` becomes `CapabilityError::Revoked` and
+/// `CAPABILITY_EXPIRED` `CapabilityError::Expired`; any other code is not a
+/// refusal of the capability.
+fn capability_refusal(capability: &Capability, code: &str) -> Option<VaultError> {
+    match code {
+        "CAPABILITY_REVOKED" => Some(CapabilityError::Revoked(capability.id).into()),
+        "CAPABILITY_EXPIRED" => Some(CapabilityError::Expired(capability.expires_at).into()),
+        _ => None,
     }
+}
 
-    async fn refresh_capability(
-        &self,
-        identity: &Identity,
-        capability_id: uuid::Uuid,
-        new_ttl: Duration,
-    ) -> Result<Capability> {
-        let url = format!("{}/v1/capabilities/{}/refresh", self.endpoint, capability_id);
-        
-        let mut req_builder = self.client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .header("X-Vault-Identity", identity.token())
-            .json(&serde_json::json!({
-                "ttl_seconds": new_ttl.as_secs()
-            }));
+/// W3C `traceparent` of the current tracing span, if it carries one
+///
+/// Requires the `otel` feature and a `tracing-opentelemetry` layer;
+/// otherwise no trace context is propagated.
+#[cfg(feature = "otel")]
+fn current_traceparent() -> Option<String> {
+    use opentelemetry::trace::TraceContextExt;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    span_context.is_valid().then(|| {
+        format!(
+            "00-{}-{}-{:02x}",
+            span_context.trace_id(),
+            span_context.span_id(),
+            span_context.trace_flags().to_u8()
+        )
+    })
+}
 
-        if let Some(auth) = &self.auth_header {
-            req_builder = req_builder.header("Authorization", auth);
-        }
+#[cfg(not(feature = "otel"))]
+fn current_traceparent() -> Option<String> {
+    None
+}
 
-        let response = req_builder
-            .send()
-            .await
-            .map_err(|e| TransportError::Http(e.to_string()))?;
+tokio::task_local! {
+    /// Request timeout replacing the configured one for the current call
+    static REQUEST_TIMEOUT: Duration;
 
-        if response.status().is_success() {
-            let capability: Capability = response.json().await
-                .map_err(|e| TransportError::InvalidResponse(e.to_string()))?;
-            Ok(capability)
-        } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            Err(TransportError::Http(
-                format!("HTTP {}: {}", status, error_text)
-            ).into())
-        }
-    }
+    /// Request id chosen by the caller for the current operation
+    static REQUEST_ID: uuid::Uuid;
+}
 
-    async fn status(&self) -> Result<crate::client::VaultStatus> {
-        let url = format!("{}/v1/status", self.endpoint);
-        
-        let mut req_builder = self.client.get(&url);
+/// Run `call` with `timeout` replacing the transport's request timeout
+pub(crate) async fn with_request_timeout<F>(timeout: Duration, call: F) -> F::Output
+where
+    F: std::future::Future,
+{
+    REQUEST_TIMEOUT.scope(timeout, call).await
+}
 
-        if let Some(auth) = &self.auth_header {
-            req_builder = req_builder.header("Authorization", auth);
-        }
+/// Request timeout for the current call, falling back to `default`
+fn effective_request_timeout(default: Duration) -> Duration {
+    REQUEST_TIMEOUT.try_with(|timeout| *timeout).unwrap_or(default)
+}
 
-        let response = req_builder
-            .send()
-            .await
-            .map_err(|e| TransportError::Http(e.to_string()))?;
+/// Request timeout a transport's calls share, changeable while they run
+struct RequestTimeout(std::sync::atomic::AtomicU64);
 
-        if response.status().is_success() {
-            let status: crate::client::VaultStatus = response.json().await
-                .map_err(|e| TransportError::InvalidResponse(e.to_string()))?;
-            Ok(status)
-        } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            Err(TransportError::Http(
-                format!("HTTP {}: {}", status, error_text)
-            ).into())
-        }
+impl RequestTimeout {
+    fn new(timeout: Duration) -> Self {
+        Self(std::sync::atomic::AtomicU64::new(Self::millis(timeout)))
     }
 
-    async fn health_check(&self) -> Result<crate::client::HealthStatus> {
-        let url = format!("{}/v1/health", self.endpoint);
-        
-        let mut req_builder = self.client.get(&url);
-
-        if let Some(auth) = &self.auth_header {
-            req_builder = req_builder.header("Authorization", auth);
-        }
+    fn get(&self) -> Duration {
+        Duration::from_millis(self.0.load(std::sync::atomic::Ordering::Relaxed))
+    }
 
-        let response = req_builder
-            .send()
-            .await
-            .map_err(|e| TransportError::Http(e.to_string()))?;
+    fn set(&self, timeout: Duration) {
+        self.0.store(Self::millis(timeout), std::sync::atomic::Ordering::Relaxed);
+    }
 
-        if response.status().is_success() {
-            let health: crate::client::HealthStatus = response.json().await
-                .map_err(|e| TransportError::InvalidResponse(e.to_string()))?;
-            Ok(health)
-        } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            Err(TransportError::Http(
-                format!("HTTP {}: {}", status, error_text)
-            ).into())
-        }
+    fn millis(timeout: Duration) -> u64 {
+        u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX)
     }
+}
 
-    async fn close(&self) -> Result<()> {
-        // HTTP client doesn't need explicit closing
-        Ok(())
+impl std::fmt::Debug for RequestTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.get().fmt(f)
     }
 }
 
-/// Unix socket transport implementation
-pub struct UnixTransport {
-    socket_path: String,
-    _client: tokio::net::UnixStream, // Placeholder for actual implementation
+/// Run `call` sending `request_id` as the request id of its transport calls
+///
+/// Retries inside `call` reuse the id, so one operation is one id.
+pub(crate) async fn with_request_id<F>(request_id: uuid::Uuid, call: F) -> F::Output
+where
+    F: std::future::Future,
+{
+    REQUEST_ID.scope(request_id, call).await
 }
 
-impl UnixTransport {
-    /// Create new Unix socket transport
-    pub async fn new(config: &crate::config::Config) -> Result<Self> {
-        let socket_path = config.endpoint.strip_prefix("unix://")
-            .unwrap_or(&config.endpoint)
-            .to_string();
+/// Request id for the current call, fresh unless the caller chose one
+fn current_request_id() -> uuid::Uuid {
+    REQUEST_ID.try_with(|request_id| *request_id).unwrap_or_else(|_| uuid::Uuid::new_v4())
+}
 
-        // TODO: Implement actual Unix socket connection
-        let _client = tokio::net::UnixStream::connect(&socket_path)
-            .await
-            .map_err(|e| TransportError::ConnectionFailed(
-                format!("Failed to connect to Unix socket: {}", e)
-            ))?;
+/// Create the transport selected by `config.transport`
+///
+/// Fails with a `ConfigError` when the selected transport's feature
+/// (`transport-unix`, `transport-mtls` or `transport-grpc`) is disabled.
+pub async fn connect(config: &crate::config::Config) -> Result<Arc<dyn Transport + Send + Sync>> {
+    Ok(match config.transport {
+        crate::config::TransportType::Http => Arc::new(HttpTransport::new(config).await?),
+        #[cfg(feature = "transport-unix")]
+        crate::config::TransportType::Unix => Arc::new(UnixTransport::new(config).await?),
+        #[cfg(not(feature = "transport-unix"))]
+        crate::config::TransportType::Unix => return Err(disabled_transport("transport-unix")),
+        #[cfg(feature = "transport-mtls")]
+        crate::config::TransportType::Mtls => Arc::new(MtlsTransport::new(config).await?),
+        #[cfg(not(feature = "transport-mtls"))]
+        crate::config::TransportType::Mtls => return Err(disabled_transport("transport-mtls")),
+        #[cfg(feature = "transport-grpc")]
+        crate::config::TransportType::Grpc => Arc::new(GrpcTransport::new(config).await?),
+        #[cfg(not(feature = "transport-grpc"))]
+        crate::config::TransportType::Grpc => return Err(disabled_transport("transport-grpc")),
+    })
+}
 
-        Ok(Self {
-            socket_path,
-            _client,
-        })
-    }
+/// Error for a transport whose cargo feature is disabled
+#[cfg(not(all(feature = "transport-unix", feature = "transport-mtls", feature = "transport-grpc")))]
+fn disabled_transport(feature: &str) -> VaultError {
+    ConfigError::InvalidValue(
+        "transport".to_string(),
+        format!("SDK built without the {} feature", feature),
+    ).into()
 }
 
-#[async_trait]
-impl Transport for UnixTransport {
-    async fn request_capability(
-        &self,
-        _identity: &Identity,
-        _request: &CapabilityRequest,
-    ) -> Result<Capability> {
-        // TODO: Implement Unix socket transport
-        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+/// Transports shared between clients with identical connection settings
+///
+/// Clients created through [`crate::Client::with_shared_transport`] reuse
+/// one transport, and so one connection pool, per combination of transport
+/// type, endpoint, auth, TLS and timeout settings. Transports live as long
+/// as the pool; drop the pool (and its clients) to close them.
+///
+/// A shared transport carries the credentials it was created with, so only
+/// share a pool between clients within the same trust boundary. A rotated
+/// token file is picked up by the shared transport like any other.
+#[derive(Debug, Clone, Default)]
+pub struct TransportPool {
+    transports: Arc<tokio::sync::Mutex<HashMap<PoolKey, Arc<dyn Transport + Send + Sync>>>>,
+}
+
+/// Settings that determine whether two clients may share a transport
+///
+/// Secrets are kept as SHA-256 digests, so the pool holds no copy of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PoolKey {
+    transport: crate::config::TransportType,
+    endpoint: String,
+    namespace: Option<String>,
+    auth_method: crate::config::AuthMethod,
+    token_file: Option<std::path::PathBuf>,
+    token_digest: Option<Vec<u8>>,
+    cert_file: Option<std::path::PathBuf>,
+    key_file: Option<std::path::PathBuf>,
+    ca_file: Option<std::path::PathBuf>,
+    hmac_key_file: Option<std::path::PathBuf>,
+    tls: Option<crate::config::TlsConfig>,
+    timeouts: crate::config::TimeoutConfig,
+    proxy_url: Option<String>,
+    proxy_username: Option<String>,
+    proxy_password_digest: Option<Vec<u8>>,
+    no_proxy: Vec<String>,
+    compression: Option<crate::config::CompressionConfig>,
+    follow_redirects_to_active: bool,
+    cluster_addresses: Vec<String>,
+}
+
+impl PoolKey {
+    fn new(config: &crate::config::Config) -> Self {
+        let digest = |secret: &str| ring::digest::digest(&ring::digest::SHA256, secret.as_bytes()).as_ref().to_vec();
+        let auth = &config.auth;
+        let proxy = config.proxy.as_ref();
+        Self {
+            transport: config.transport.clone(),
+            endpoint: config.endpoint.clone(),
+            namespace: config.namespace.clone(),
+            auth_method: auth.method.clone(),
+            token_file: auth.token_file.clone(),
+            token_digest: auth.token.as_ref().map(|token| digest(token)),
+            cert_file: auth.cert_file.clone(),
+            key_file: auth.key_file.clone(),
+            ca_file: auth.ca_file.clone(),
+            hmac_key_file: auth.hmac_key_file.clone(),
+            tls: config.tls.clone(),
+            timeouts: config.timeouts.clone(),
+            proxy_url: proxy.map(|proxy| proxy.url.clone()),
+            proxy_username: proxy.and_then(|proxy| proxy.username.clone()),
+            proxy_password_digest: proxy.and_then(|proxy| proxy.password.as_deref()).map(digest),
+            no_proxy: proxy.map(|proxy| proxy.no_proxy.clone()).unwrap_or_default(),
+            compression: config.compression.clone(),
+            follow_redirects_to_active: config.follow_redirects_to_active,
+            cluster_addresses: config.cluster_addresses.clone(),
+        }
     }
+}
 
-    async fn access_with_capability<T>(&self, _capability: &Capability) -> Result<T>
-    where
-        T: serde::de::DeserializeOwned + Send,
-    {
-        // TODO: Implement Unix socket transport
-        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+impl TransportPool {
+    /// Create an empty pool
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    async fn revoke_capability(&self, _capability_id: uuid::Uuid) -> Result<()> {
-        // TODO: Implement Unix socket transport
-        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    /// Get the transport for `config`, creating it on first use
+    pub async fn get_or_connect(&self, config: &crate::config::Config) -> Result<Arc<dyn Transport + Send + Sync>> {
+        let key = Self::key(config);
+        let mut transports = self.transports.lock().await;
+        if let Some(transport) = transports.get(&key) {
+            return Ok(Arc::clone(transport));
+        }
+
+        let transport = connect(config).await?;
+        transports.insert(key, Arc::clone(&transport));
+        Ok(transport)
     }
 
-    async fn refresh_capability(
-        &self,
-        _identity: &Identity,
-        _capability_id: uuid::Uuid,
-        _new_ttl: Duration,
-    ) -> Result<Capability> {
-        // TODO: Implement Unix socket transport
-        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    /// Number of distinct transports in the pool
+    pub async fn len(&self) -> usize {
+        self.transports.lock().await.len()
     }
 
-    async fn status(&self) -> Result<crate::client::VaultStatus> {
-        // TODO: Implement Unix socket transport
-        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    /// Whether the pool holds no transports
+    pub async fn is_empty(&self) -> bool {
+        self.transports.lock().await.is_empty()
     }
 
-    async fn health_check(&self) -> Result<crate::client::HealthStatus> {
-        // TODO: Implement Unix socket transport
-        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    /// Drop every pooled transport
+    pub async fn clear(&self) {
+        self.transports.lock().await.clear();
     }
 
-    async fn close(&self) -> Result<()> {
-        // TODO: Implement Unix socket cleanup
-        Ok(())
+    /// Settings that determine whether two clients may share a transport
+    pub(crate) fn key(config: &crate::config::Config) -> PoolKey {
+        PoolKey::new(config)
     }
 }
 
-/// mTLS transport implementation
-pub struct MtlsTransport {
+/// HTTP/HTTPS transport implementation
+pub struct HttpTransport {
     client: reqwest::Client,
     endpoint: String,
+    auth_header: Option<BearerToken>,
+    hmac_key: Option<Zeroizing<Vec<u8>>>,
+    rate_limit_fallback: Duration,
+    request_timeout: RequestTimeout,
+    follow_redirects_to_active: bool,
+    cluster_nodes: Vec<reqwest::Url>,
+    namespace: Option<String>,
+    compression: Option<crate::config::CompressionConfig>,
 }
 
-impl MtlsTransport {
-    /// Create new mTLS transport
-    pub async fn new(config: &crate::config::Config) -> Result<Self> {
-        // TODO: Implement mTLS client configuration
-        let client = reqwest::Client::builder()
-            .timeout(config.timeouts.request)
-            .build()
-            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?;
-
-        Ok(Self {
-            client,
-            endpoint: config.endpoint.clone(),
-        })
+impl std::fmt::Debug for HttpTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpTransport")
+            .field("endpoint", &self.endpoint)
+            .field("auth_header", &self.auth_header.as_ref().map(|_| "***"))
+            .field("hmac_key", &self.hmac_key.as_ref().map(|_| "***"))
+            .field("rate_limit_fallback", &self.rate_limit_fallback)
+            .field("request_timeout", &self.request_timeout)
+            .field("follow_redirects_to_active", &self.follow_redirects_to_active)
+            .field("cluster_nodes", &self.cluster_nodes)
+            .field("namespace", &self.namespace)
+            .field("compression", &self.compression)
+            .finish_non_exhaustive()
     }
 }
 
-#[async_trait]
-impl Transport for MtlsTransport {
-    async fn request_capability(
-        &self,
+/// Bearer token for token auth, from `auth.token_file` or else `auth.token`
+enum BearerToken {
+    File(TokenFile),
+    Inline(Arc<Zeroizing<String>>),
+}
+
+impl BearerToken {
+    /// Token configured for token auth, with the file taking precedence
+    fn from_config(auth: &crate::config::AuthConfig) -> Result<Option<Self>> {
+        if !matches!(auth.method, crate::config::AuthMethod::Token) {
+            return Ok(None);
+        }
+        if let Some(token_file) = &auth.token_file {
+            return Ok(Some(BearerToken::File(TokenFile::load(token_file)?)));
+        }
+        let Some(token) = auth.token.as_ref().map(|token| token.trim()) else {
+            return Ok(None);
+        };
+        crate::config::check_bearer_token(token)
+            .map_err(|reason| TransportError::ConnectionFailed(format!("VAULT_TOKEN {}", reason)))?;
+        Ok(Some(BearerToken::Inline(Arc::new(Zeroizing::new(format!("Bearer {}", token))))))
+    }
+
+    /// Current `Authorization` header value
+    fn header(&self) -> Arc<Zeroizing<String>> {
+        match self {
+            BearerToken::File(token_file) => token_file.header(),
+            BearerToken::Inline(header) => header.clone(),
+        }
+    }
+}
+
+/// How often a token file is stat'ed for changes
+const TOKEN_FILE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Bearer token read from `auth.token_file`, re-read whenever the file changes
+///
+/// The file is stat'ed at most every [`TOKEN_FILE_CHECK_INTERVAL`], so a
+/// token rotated by an orchestrator (a Kubernetes projected token, a Vault
+/// agent sink) is sent shortly after without blocking every request on the
+/// filesystem. A rewrite caught half-way, leaving the file empty or not a
+/// token, keeps the previous token until a complete one is read.
+struct TokenFile {
+    path: std::path::PathBuf,
+    state: std::sync::RwLock<TokenFileState>,
+}
+
+struct TokenFileState {
+    /// Modification time and length the header was read at
+    stamp: Option<(std::time::SystemTime, u64)>,
+    /// When the file was last stat'ed
+    checked_at: std::time::Instant,
+    header: Arc<Zeroizing<String>>,
+}
+
+impl TokenFile {
+    /// Read the token file, failing if it is unreadable or not a token
+    fn load(path: &std::path::Path) -> Result<Self> {
+        let stamp = file_stamp(path);
+        let header = read_bearer(path).map_err(|e| TransportError::ConnectionFailed(
+            format!("Failed to read token file: {}", e)
+        ))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            state: std::sync::RwLock::new(TokenFileState {
+                stamp,
+                checked_at: std::time::Instant::now(),
+                header: Arc::new(header),
+            }),
+        })
+    }
+
+    /// Current `Authorization` header value, re-reading the file if it changed
+    fn header(&self) -> Arc<Zeroizing<String>> {
+        {
+            let state = self.state.read().unwrap_or_else(PoisonError::into_inner);
+            if state.checked_at.elapsed() < TOKEN_FILE_CHECK_INTERVAL {
+                return state.header.clone();
+            }
+        }
+
+        let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+        if state.checked_at.elapsed() < TOKEN_FILE_CHECK_INTERVAL {
+            return state.header.clone();
+        }
+        state.checked_at = std::time::Instant::now();
+        let stamp = file_stamp(&self.path);
+        if stamp.is_none() || state.stamp == stamp {
+            return state.header.clone();
+        }
+        match read_bearer(&self.path) {
+            Ok(header) => {
+                tracing::debug!("Token file {} changed, using the new token", self.path.display());
+                state.stamp = stamp;
+                state.header = Arc::new(header);
+            }
+            Err(e) => tracing::warn!("Keeping previous token, failed to re-read {}: {}", self.path.display(), e),
+        }
+        state.header.clone()
+    }
+}
+
+/// Modification time and length of `path`, if it can be stat'ed
+fn file_stamp(path: &std::path::Path) -> Option<(std::time::SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Read a token file into a bearer header, rejecting anything but a token
+fn read_bearer(path: &std::path::Path) -> std::io::Result<Zeroizing<String>> {
+    let token = std::fs::read_to_string(path).map(Zeroizing::new)?;
+    crate::config::check_bearer_token(token.trim()).map_err(|reason| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, format!("token file contents {}", reason))
+    })?;
+    Ok(Zeroizing::new(format!("Bearer {}", token.trim())))
+}
+
+impl HttpTransport {
+    /// Create new HTTP transport
+    pub async fn new(config: &crate::config::Config) -> Result<Self> {
+        let mut client_builder = reqwest::Client::builder()
+            .timeout(config.timeouts.request)
+            .connect_timeout(config.timeouts.connect)
+            .redirect(redirect_policy(config.follow_redirects_to_active));
+        #[cfg(feature = "compression")]
+        {
+            client_builder = client_builder.gzip(config.compression.is_some());
+        }
+        #[cfg(not(feature = "compression"))]
+        if config.compression.is_some() {
+            return Err(ConfigError::InvalidValue(
+                "compression".to_string(),
+                "SDK built without the compression feature".to_string(),
+            ).into());
+        }
+
+        let mut endpoint = config.endpoint.clone();
+
+        // Configure TLS if specified
+        if let Some(tls_config) = &config.tls {
+            if !tls_config.verify_cert {
+                tracing::warn!("TLS certificate verification disabled for {}", config.endpoint);
+                client_builder = client_builder.danger_accept_invalid_certs(true);
+            }
+
+            if let Some(min_version) = &tls_config.min_version {
+                client_builder = client_builder
+                    .min_tls_version(parse_tls_version("tls.min_version", min_version)?);
+            }
+
+            if let Some(max_version) = &tls_config.max_version {
+                client_builder = client_builder
+                    .max_tls_version(parse_tls_version("tls.max_version", max_version)?);
+            }
+
+            if let Some(server_name) = &tls_config.server_name {
+                // reqwest derives SNI from the URL host, so address the server by the
+                // pinned name and resolve that name to the configured endpoint.
+                let (pinned_endpoint, addr) = pin_server_name(&config.endpoint, server_name).await?;
+                client_builder = client_builder.resolve(server_name, addr);
+                endpoint = pinned_endpoint;
+            }
+
+            if tls_config.cipher_suites.is_some() {
+                tracing::warn!("tls.cipher_suites is not supported by the HTTP transport and will be ignored");
+            }
+        }
+
+        // Trust an additional CA if provided
+        if let Some(ca_file) = &config.auth.ca_file {
+            let pem = std::fs::read(ca_file)
+                .map_err(|e| TransportError::Tls(
+                    format!("Failed to read CA file: {}", e)
+                ))?;
+            let ca_cert = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| TransportError::Tls(e.to_string()))?;
+            client_builder = client_builder.add_root_certificate(ca_cert);
+        }
+
+        // An explicit proxy replaces the HTTP(S)_PROXY environment variables
+        if let Some(proxy_config) = &config.proxy {
+            client_builder = client_builder.no_proxy().proxy(build_proxy(proxy_config)?);
+        }
+
+        let client = client_builder.build()
+            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?;
+
+        // Prepare authentication header
+        let auth_header = BearerToken::from_config(&config.auth)?;
+
+        let hmac_key = match &config.auth.hmac_key_file {
+            Some(key_file) => {
+                let key = std::fs::read_to_string(key_file).map(Zeroizing::new).map_err(|e| TransportError::ConnectionFailed(
+                    format!("Failed to read HMAC key file: {}", e)
+                ))?;
+                Some(Zeroizing::new(key.trim().as_bytes().to_vec()))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            client,
+            endpoint,
+            auth_header,
+            hmac_key,
+            rate_limit_fallback: config.retry.base_delay,
+            request_timeout: RequestTimeout::new(config.timeouts.request),
+            follow_redirects_to_active: config.follow_redirects_to_active,
+            cluster_nodes: config.cluster_addresses.iter().filter_map(|address| reqwest::Url::parse(address).ok()).collect(),
+            namespace: config.namespace.clone(),
+            compression: config.compression.clone(),
+        })
+    }
+
+    /// Send a tagged request, resending it once to the active node if a standby refused it
+    ///
+    /// A standby answers with a 307/308 redirect, or a 503 whose body reads
+    /// `{"standby": true}` and may name the `active_address`; without one,
+    /// the active node is looked up via `leader_address` from `/v1/status`.
+    async fn send(&self, req_builder: reqwest::RequestBuilder, request_id: uuid::Uuid) -> Result<reqwest::Response> {
+        let request = req_builder.build().map_err(|e| self.send_error(e).with_request_id(request_id))?;
+        let resend = request.try_clone().filter(|_| self.follow_redirects_to_active);
+        let response = self.client.execute(request).await
+            .map_err(|e| self.send_error(e).with_request_id(request_id))?;
+
+        let Some(mut resend) = resend else {
+            return Ok(response);
+        };
+        let active_url = match self.active_url(response, resend.url()).await {
+            Standby::Not(response) => return Ok(response),
+            Standby::Failed(e) => return Err(e.with_request_id(request_id)),
+            Standby::Active(None) => {
+                return Err(VaultError::from(TransportError::Protocol(
+                    "standby node did not name the active node".to_string(),
+                )).with_request_id(request_id));
+            }
+            Standby::Active(Some(url)) => url,
+        };
+
+        // The resend carries our credentials, so only to nodes we know
+        if !self.is_cluster_node(&active_url) {
+            return Err(VaultError::from(TransportError::Protocol(format!(
+                "standby node named {} as active, which is neither the endpoint nor in cluster_addresses",
+                active_url.origin().ascii_serialization()
+            ))).with_request_id(request_id));
+        }
+
+        tracing::debug!("Standby node refused request, resending to active node {}", active_url);
+        *resend.url_mut() = active_url;
+        self.client.execute(resend).await
+            .map_err(|e| self.send_error(e).with_request_id(request_id))
+    }
+
+    /// Whether `url` is on the endpoint's origin or a configured cluster node
+    fn is_cluster_node(&self, url: &reqwest::Url) -> bool {
+        let origin = url.origin();
+        reqwest::Url::parse(&self.endpoint).map_or(false, |endpoint| endpoint.origin() == origin)
+            || self.cluster_nodes.iter().any(|node| node.origin() == origin)
+    }
+
+    /// Classify `response` to a request for `url` as a standby refusal or not
+    ///
+    /// For a refusal, this is where the active node serves `url`. A 503
+    /// without a standby body is reported as the error it is.
+    async fn active_url(&self, response: reqwest::Response, url: &reqwest::Url) -> Standby {
+        let status = response.status();
+        if matches!(status, reqwest::StatusCode::TEMPORARY_REDIRECT | reqwest::StatusCode::PERMANENT_REDIRECT) {
+            let location = response.headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|location| url.join(location).ok());
+            return Standby::Active(location);
+        }
+        if status != reqwest::StatusCode::SERVICE_UNAVAILABLE {
+            return Standby::Not(response);
+        }
+
+        #[derive(serde::Deserialize)]
+        struct StandbyBody {
+            #[serde(default)]
+            standby: bool,
+            active_address: Option<String>,
+        }
+
+        let error_text = response.text().await.unwrap_or_default();
+        let body = match serde_json::from_str::<StandbyBody>(&error_text) {
+            Ok(body) if body.standby => body,
+            _ => return Standby::Failed(TransportError::Http {
+                message: format!("HTTP {}: {}", status, error_text),
+                source: None,
+            }.into()),
+        };
+        let active_address = match body.active_address {
+            Some(address) => Some(address),
+            None => self.leader_address().await,
+        };
+        Standby::Active(active_address.and_then(|address| rebase_url(url, &address)))
+    }
+
+    /// Active node address reported by `/v1/status`, if any
+    async fn leader_address(&self) -> Option<String> {
+        let url = format!("{}/v1/status", self.endpoint);
+        let mut req_builder = self.client.get(&url);
+        req_builder = self.authorize(req_builder);
+
+        let response = self.tag(req_builder).0.send().await.ok()?;
+        let status: crate::client::VaultStatus = response.json().await.ok()?;
+        status.leader_address
+    }
+
+    /// Attach the bearer token from the token file, if one is configured
+    fn authorize(&self, req_builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.auth_header {
+            Some(token) => req_builder.header("Authorization", token.header().as_str()),
+            None => req_builder,
+        }
+    }
+
+    /// Attach the request id, the namespace and any trace context to a request
+    fn tag(&self, req_builder: reqwest::RequestBuilder) -> (reqwest::RequestBuilder, uuid::Uuid) {
+        let request_id = current_request_id();
+        let mut req_builder = req_builder
+            .header("X-Request-Id", request_id.to_string())
+            .timeout(effective_request_timeout(self.request_timeout.get()));
+        if let Some(namespace) = &self.namespace {
+            req_builder = req_builder.header("X-Vault-Namespace", namespace);
+        }
+        if let Some(traceparent) = current_traceparent() {
+            req_builder = req_builder.header("traceparent", traceparent);
+        }
+        (req_builder, request_id)
+    }
+
+    /// Attach a JSON body, signed when an HMAC key is configured
+    fn json_body<B: serde::Serialize + ?Sized>(&self, req_builder: reqwest::RequestBuilder, body: &B) -> Result<reqwest::RequestBuilder> {
+        Ok(self.signed_body(req_builder, Zeroizing::new(serde_json::to_vec(body)?)))
+    }
+
+    /// Attach a JSON body, with `X-Vault-Timestamp` and `X-Vault-HMAC`
+    /// headers when an HMAC key is configured
+    ///
+    /// Bodies reaching the compression threshold are sent compressed, and
+    /// the HMAC then covers the compressed bytes as sent. The buffer handed
+    /// to reqwest is `body` itself, wiped when the request drops it.
+    fn signed_body(&self, req_builder: reqwest::RequestBuilder, body: Zeroizing<Vec<u8>>) -> reqwest::RequestBuilder {
+        let req_builder = req_builder.header("Content-Type", "application/json");
+        let (mut req_builder, sent) = self.compressed_body(req_builder, body);
+        if let Some(key) = &self.hmac_key {
+            let timestamp = chrono::Utc::now().timestamp();
+            req_builder = req_builder
+                .header("X-Vault-Timestamp", timestamp.to_string())
+                .header("X-Vault-HMAC", request_hmac(key, timestamp, &sent));
+        }
+        req_builder.body(bytes::Bytes::from_owner(sent))
+    }
+
+    /// `body` as sent, compressed when it reaches the compression threshold
+    #[cfg(feature = "compression")]
+    fn compressed_body(
+        &self,
+        req_builder: reqwest::RequestBuilder,
+        body: Zeroizing<Vec<u8>>,
+    ) -> (reqwest::RequestBuilder, Zeroizing<Vec<u8>>) {
+        if let Some(compression) = self.compression.as_ref().filter(|c| body.len() >= c.min_size) {
+            match compress(compression.algorithm, &body) {
+                Ok(compressed) => {
+                    let req_builder = req_builder.header("Content-Encoding", compression.algorithm.content_encoding());
+                    return (req_builder, Zeroizing::new(compressed));
+                }
+                Err(e) => tracing::warn!("Sending request body uncompressed: {}", e),
+            }
+        }
+        (req_builder, body)
+    }
+
+    /// `body` as sent; without the `compression` feature it never is compressed
+    #[cfg(not(feature = "compression"))]
+    fn compressed_body(
+        &self,
+        req_builder: reqwest::RequestBuilder,
+        body: Zeroizing<Vec<u8>>,
+    ) -> (reqwest::RequestBuilder, Zeroizing<Vec<u8>>) {
+        (req_builder, body)
+    }
+
+    /// Classify a send failure by the phase it occurred in
+    ///
+    /// Timeouts while establishing the connection become
+    /// `TransportError::ConnectionTimeout`; timeouts afterwards become
+    /// `VaultError::Timeout` with the configured request timeout.
+    fn send_error(&self, e: reqwest::Error) -> VaultError {
+        if e.is_timeout() {
+            if e.is_connect() {
+                TransportError::ConnectionTimeout.into()
+            } else {
+                VaultError::Timeout(effective_request_timeout(self.request_timeout.get()))
+            }
+        } else if e.is_connect() {
+            TransportError::ConnectionFailed(e.to_string()).into()
+        } else {
+            TransportError::http(e).into()
+        }
+    }
+
+    /// Convert a non-success response into an error
+    ///
+    /// HTTP 429 becomes `VaultError::RateLimit` carrying the `Retry-After`
+    /// interval, falling back to the retry base delay when the header is
+    /// missing or malformed.
+    async fn error_from_response(&self, response: reqwest::Response) -> VaultError {
+        let status = response.status();
+
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response.headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, chrono::Utc::now()))
+                .unwrap_or(self.rate_limit_fallback);
+            return VaultError::RateLimit(retry_after);
+        }
+
+        let error_text = response.text().await.unwrap_or_default();
+        TransportError::from_status(status.as_u16(), error_text)
+    }
+
+    /// Convert a non-success response to a request using `capability`
+    ///
+    /// A structured `CAPABILITY_REVOKED` or `CAPABILITY_EXPIRED` error code
+    /// becomes the matching `CapabilityError`, as does HTTP 410 without
+    /// one, which is taken as revoked. Anything else is converted as by
+    /// [`HttpTransport::error_from_response`].
+    async fn capability_error_from_response(&self, capability: &Capability, response: reqwest::Response) -> VaultError {
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return self.error_from_response(response).await;
+        }
+
+        let error_text = response.text().await.unwrap_or_default();
+        let refusal = match serde_json::from_str::<ErrorBody>(&error_text) {
+            Ok(body) => capability_refusal(capability, &body.error.code),
+            Err(_) if status == reqwest::StatusCode::GONE => Some(CapabilityError::Revoked(capability.id).into()),
+            Err(_) => None,
+        };
+        refusal.unwrap_or_else(|| TransportError::from_status(status.as_u16(), error_text))
+    }
+
+    /// Send an access request, optionally carrying a per-use reason
+    async fn access<T>(&self, capability: &Capability, reason: Option<&str>) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        let url = format!("{}/v1/access", self.endpoint);
+        
+        let mut req_builder = self.client
+            .post(&url);
+
+        req_builder = self.authorize(req_builder);
+
+        if let Some(reason) = reason {
+            req_builder = req_builder.header("X-Vault-Access-Reason", reason);
+        }
+
+        let (req_builder, request_id) = self.tag(self.json_body(req_builder, &capability)?);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status().is_success() {
+            let result: T = response.json().await
+                .map_err(TransportError::invalid_response)?;
+            Ok(result)
+        } else {
+            Err(self.capability_error_from_response(capability, response).await.with_request_id(request_id))
+        }
+    }
+}
+
+/// Compress a request body with `algorithm`
+#[cfg(feature = "compression")]
+fn compress(algorithm: crate::config::CompressionAlgorithm, body: &[u8]) -> std::io::Result<Vec<u8>> {
+    use std::io::Write;
+
+    match algorithm {
+        crate::config::CompressionAlgorithm::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(body)?;
+            encoder.finish()
+        }
+    }
+}
+
+/// Base64 HMAC-SHA256 over `"<timestamp>.<body>"`, sent as `X-Vault-HMAC`
+///
+/// Covering the timestamp lets the server reject replayed requests.
+fn request_hmac(key: &[u8], timestamp: i64, body: &[u8]) -> String {
+    use base64::Engine;
+
+    let mut message = format!("{}.", timestamp).into_bytes();
+    message.extend_from_slice(body);
+    base64::engine::general_purpose::STANDARD.encode(crate::crypto::hmac_sha256(key, &message))
+}
+
+/// Map a configured TLS version string ("1.2", "1.3", ...) to a reqwest version
+fn parse_tls_version(field: &str, version: &str) -> Result<reqwest::tls::Version> {
+    match version.trim() {
+        "1.0" => Ok(reqwest::tls::Version::TLS_1_0),
+        "1.1" => Ok(reqwest::tls::Version::TLS_1_1),
+        "1.2" => Ok(reqwest::tls::Version::TLS_1_2),
+        "1.3" => Ok(reqwest::tls::Version::TLS_1_3),
+        _ => Err(ConfigError::InvalidValue(
+            field.to_string(),
+            format!("unsupported TLS version '{}'", version),
+        ).into()),
+    }
+}
+
+/// Build the reqwest proxy for an explicit proxy configuration
+fn build_proxy(config: &crate::config::ProxyConfig) -> Result<reqwest::Proxy> {
+    let mut proxy = reqwest::Proxy::all(&config.url).map_err(|e| ConfigError::InvalidValue(
+        "proxy.url".to_string(),
+        e.to_string(),
+    ))?;
+    if let Some(username) = &config.username {
+        proxy = proxy.basic_auth(username, config.password.as_deref().unwrap_or_default());
+    }
+    if !config.no_proxy.is_empty() {
+        proxy = proxy.no_proxy(reqwest::NoProxy::from_string(&config.no_proxy.join(",")));
+    }
+    Ok(proxy)
+}
+
+/// Redirects followed before giving up, as in reqwest's default policy
+const MAX_REDIRECTS: usize = 10;
+
+/// Redirect policy of the HTTP client
+///
+/// With `follow_active`, 307/308 are left to `HttpTransport::send`, which
+/// resends only to cluster nodes. Other redirects are followed as reqwest
+/// would, but only within the origin of the original request: reqwest
+/// strips `Authorization` across hosts, not `X-Vault-Identity`.
+fn redirect_policy(follow_active: bool) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        let status = attempt.status();
+        if follow_active
+            && matches!(status, reqwest::StatusCode::TEMPORARY_REDIRECT | reqwest::StatusCode::PERMANENT_REDIRECT)
+        {
+            return attempt.stop();
+        }
+        let same_origin = attempt.previous()
+            .first()
+            .map_or(false, |first| first.origin() == attempt.url().origin());
+        if !same_origin {
+            attempt.stop()
+        } else if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// Parse a `Retry-After` header value (delta-seconds or HTTP-date)
+fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means the client may retry immediately
+    Some((date.with_timezone(&chrono::Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Run the connection-establishment phase under the connect timeout
+///
+/// Exceeding the timeout yields `TransportError::ConnectionTimeout`.
+pub(crate) async fn connect_phase<T, F>(timeout: Duration, connect: F) -> Result<T>
+where
+    F: std::future::Future<Output = Result<T>>,
+{
+    tokio::time::timeout(timeout, connect)
+        .await
+        .map_err(|_| TransportError::ConnectionTimeout)?
+}
+
+/// Run the request phase under the request timeout
+///
+/// Exceeding the timeout yields `VaultError::Timeout`.
+pub(crate) async fn request_phase<T, F>(timeout: Duration, request: F) -> Result<T>
+where
+    F: std::future::Future<Output = Result<T>>,
+{
+    tokio::time::timeout(timeout, request)
+        .await
+        .map_err(|_| VaultError::Timeout(timeout))?
+}
+
+/// Rewrite the endpoint host to `server_name` and resolve the original host
+async fn pin_server_name(endpoint: &str, server_name: &str) -> Result<(String, std::net::SocketAddr)> {
+    let mut url = reqwest::Url::parse(endpoint)
+        .map_err(|e| TransportError::InvalidEndpoint(e.to_string()))?;
+
+    let host = url.host_str()
+        .ok_or_else(|| TransportError::InvalidEndpoint(endpoint.to_string()))?
+        .to_string();
+    let port = url.port_or_known_default()
+        .ok_or_else(|| TransportError::InvalidEndpoint(endpoint.to_string()))?;
+
+    let addr = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map_err(|e| TransportError::ConnectionFailed(
+            format!("Failed to resolve {}: {}", host, e)
+        ))?
+        .next()
+        .ok_or_else(|| TransportError::ConnectionFailed(
+            format!("No address found for {}", host)
+        ))?;
+
+    url.set_host(Some(server_name))
+        .map_err(|e| ConfigError::InvalidValue("tls.server_name".to_string(), e.to_string()))?;
+
+    Ok((url.as_str().trim_end_matches('/').to_string(), addr))
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn request_capability(
+        &self,
+        identity: &Identity,
+        request: &CapabilityRequest,
+    ) -> Result<Capability> {
+        let url = format!("{}/v1/capabilities", self.endpoint);
+        
+        let mut req_builder = self.client
+            .post(&url)
+            .header("X-Vault-Identity", identity.token());
+
+        req_builder = self.authorize(req_builder);
+
+        if let Some(key) = request.idempotency_key {
+            req_builder = req_builder.header("Idempotency-Key", key.to_string());
+        }
+
+        let (req_builder, request_id) = self.tag(self.json_body(req_builder, &request)?);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status().is_success() {
+            let capability: Capability = response.json().await
+                .map_err(TransportError::invalid_response)?;
+            Ok(capability)
+        } else {
+            Err(self.error_from_response(response).await.with_request_id(request_id))
+        }
+    }
+
+    async fn request_capabilities(
+        &self,
+        identity: &Identity,
+        requests: &[CapabilityRequest],
+    ) -> Result<Vec<Result<Capability>>> {
+        let url = format!("{}/v1/capabilities/batch", self.endpoint);
+
+        let mut req_builder = self.client
+            .post(&url)
+            .header("X-Vault-Identity", identity.token());
+
+        req_builder = self.authorize(req_builder);
+
+        let (req_builder, request_id) = self.tag(self.json_body(req_builder, &BatchRequest { requests })?);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            tracing::debug!("Batch endpoint unavailable, requesting capabilities individually");
+            return Ok(request_each(self, identity, requests).await);
+        }
+
+        if !response.status().is_success() {
+            return Err(self.error_from_response(response).await.with_request_id(request_id));
+        }
+
+        let batch: BatchResponse = response.json().await
+            .map_err(TransportError::invalid_response)?;
+        if batch.results.len() != requests.len() {
+            return Err(TransportError::InvalidResponse {
+                message: format!("batch returned {} results for {} requests", batch.results.len(), requests.len()),
+                source: None,
+            }.into());
+        }
+
+        Ok(batch.results.into_iter().map(BatchItem::into_result).collect())
+    }
+
+    async fn check_capability(&self, identity: &Identity, request: &CapabilityRequest) -> Result<PolicyDecision> {
+        let url = format!("{}/v1/capabilities/check", self.endpoint);
+
+        let mut req_builder = self.client
+            .post(&url)
+            .header("X-Vault-Identity", identity.token());
+
+        req_builder = self.authorize(req_builder);
+
+        let (req_builder, request_id) = self.tag(self.json_body(req_builder, &request)?);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status().is_success() {
+            let decision: PolicyDecision = response.json().await
+                .map_err(TransportError::invalid_response)?;
+            Ok(decision)
+        } else {
+            Err(self.error_from_response(response).await.with_request_id(request_id))
+        }
+    }
+
+    async fn access_with_capability<T>(&self, capability: &Capability) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        self.access(capability, None).await
+    }
+
+    async fn access_with_reason<T>(&self, capability: &Capability, reason: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        self.access(capability, Some(reason)).await
+    }
+
+    async fn access_stream(&self, capability: &Capability) -> Result<SecretStream> {
+        use futures::TryStreamExt;
+
+        let url = format!("{}/v1/access/stream", self.endpoint);
+
+        let mut req_builder = self.client
+            .post(&url)
+            .header("Accept", "application/octet-stream");
+
+        req_builder = self.authorize(req_builder);
+
+        let (req_builder, request_id) = self.tag(self.json_body(req_builder, &capability)?);
+        let response = self.send(req_builder, request_id).await?;
+
+        if !response.status().is_success() {
+            return Err(self.capability_error_from_response(capability, response).await.with_request_id(request_id));
+        }
+
+        let body = response
+            .bytes_stream()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e));
+        Ok(Box::pin(tokio_util::io::StreamReader::new(body)))
+    }
+
+    async fn revoke_capability(&self, capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        self.revoke_capability_with_reason(capability_id, &RevokeReason::Unspecified).await
+    }
+
+    async fn revoke_capability_with_reason(
+        &self,
+        capability_id: uuid::Uuid,
+        reason: &RevokeReason,
+    ) -> Result<RevocationReceipt> {
+        let url = format!("{}/v1/capabilities/{}/revoke", self.endpoint, capability_id);
+        
+        let mut req_builder = self.client
+            .post(&url);
+
+        if *reason != RevokeReason::Unspecified {
+            req_builder = self.json_body(req_builder, &serde_json::json!({ "reason": reason.to_string() }))?;
+        }
+
+        req_builder = self.authorize(req_builder);
+
+        let (req_builder, request_id) = self.tag(req_builder);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status().is_success() {
+            let body = response.bytes().await
+                .map_err(TransportError::http)?;
+
+            // Servers revoking synchronously may return an empty body
+            if body.is_empty() {
+                Ok(RevocationReceipt::completed(capability_id))
+            } else {
+                serde_json::from_slice(&body)
+                    .map_err(|e| TransportError::invalid_response(e).into())
+            }
+        } else {
+            Err(self.error_from_response(response).await.with_request_id(request_id))
+        }
+    }
+
+    async fn revoke_capabilities(&self, capability_ids: &[uuid::Uuid]) -> Result<Vec<Result<RevocationReceipt>>> {
+        let url = format!("{}/v1/capabilities/revoke-batch", self.endpoint);
+
+        let mut req_builder = self.client
+            .post(&url);
+
+        req_builder = self.authorize(req_builder);
+
+        let (req_builder, request_id) = self.tag(self.json_body(req_builder, &RevokeBatchRequest { ids: capability_ids })?);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            tracing::debug!("Batch revocation endpoint unavailable, revoking individually");
+            return Ok(revoke_each(self, capability_ids).await);
+        }
+
+        if !response.status().is_success() {
+            return Err(self.error_from_response(response).await.with_request_id(request_id));
+        }
+
+        let batch: RevokeBatchResponse = response.json().await
+            .map_err(TransportError::invalid_response)?;
+        if batch.results.len() != capability_ids.len() {
+            return Err(TransportError::InvalidResponse {
+                message: format!("batch returned {} results for {} revocations", batch.results.len(), capability_ids.len()),
+                source: None,
+            }.into());
+        }
+
+        Ok(batch.results.into_iter().map(RevokeBatchItem::into_result).collect())
+    }
+
+    async fn revocation_status(&self, capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        let url = format!("{}/v1/capabilities/{}/revocation", self.endpoint, capability_id);
+        
+        let mut req_builder = self.client.get(&url);
+
+        req_builder = self.authorize(req_builder);
+
+        let (req_builder, request_id) = self.tag(req_builder);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status().is_success() {
+            let receipt: RevocationReceipt = response.json().await
+                .map_err(TransportError::invalid_response)?;
+            Ok(receipt)
+        } else {
+            Err(self.error_from_response(response).await.with_request_id(request_id))
+        }
+    }
+
+    async fn refresh_capability(
+        &self,
+        identity: &Identity,
+        capability_id: uuid::Uuid,
+        new_ttl: Duration,
+    ) -> Result<Capability> {
+        let url = format!("{}/v1/capabilities/{}/refresh", self.endpoint, capability_id);
+        
+        let mut req_builder = self.client
+            .post(&url)
+            .header("X-Vault-Identity", identity.token());
+        req_builder = self.json_body(req_builder, &RefreshBody { id: None, ttl: new_ttl })?;
+
+        req_builder = self.authorize(req_builder);
+
+        let (req_builder, request_id) = self.tag(req_builder);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status().is_success() {
+            let capability: Capability = response.json().await
+                .map_err(TransportError::invalid_response)?;
+            Ok(capability)
+        } else {
+            Err(self.error_from_response(response).await.with_request_id(request_id))
+        }
+    }
+
+    async fn status(&self) -> Result<crate::client::VaultStatus> {
+        let url = format!("{}/v1/status", self.endpoint);
+        
+        let mut req_builder = self.client.get(&url);
+
+        req_builder = self.authorize(req_builder);
+
+        let (req_builder, request_id) = self.tag(req_builder);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status().is_success() {
+            let status: crate::client::VaultStatus = response.json().await
+                .map_err(TransportError::invalid_response)?;
+            Ok(status)
+        } else {
+            Err(self.error_from_response(response).await.with_request_id(request_id))
+        }
+    }
+
+    async fn health_check(&self) -> Result<crate::client::HealthStatus> {
+        let url = format!("{}/v1/health", self.endpoint);
+        
+        let mut req_builder = self.client.get(&url);
+
+        req_builder = self.authorize(req_builder);
+
+        let (req_builder, request_id) = self.tag(req_builder);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status().is_success() {
+            let health: crate::client::HealthStatus = response.json().await
+                .map_err(TransportError::invalid_response)?;
+            Ok(health)
+        } else {
+            Err(self.error_from_response(response).await.with_request_id(request_id))
+        }
+    }
+
+    async fn ping(&self) -> Result<Duration> {
+        let url = format!("{}/v1/sys/health", self.endpoint);
+
+        let mut req_builder = self.client.head(&url);
+
+        req_builder = self.authorize(req_builder);
+
+        // Liveness is per node, so a standby's 503 is reported, not followed
+        let (req_builder, request_id) = self.tag(req_builder);
+        let started = std::time::Instant::now();
+        let response = req_builder.send().await
+            .map_err(|e| self.send_error(e).with_request_id(request_id))?;
+        let latency = started.elapsed();
+
+        if response.status().is_success() {
+            Ok(latency)
+        } else if response.status() == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+            Err(VaultError::Server("vault is sealed or standby (HTTP 503)".to_string()).with_request_id(request_id))
+        } else {
+            Err(self.error_from_response(response).await.with_request_id(request_id))
+        }
+    }
+
+    async fn client_config(&self) -> Result<ServerHints> {
+        let url = format!("{}/v1/client-config", self.endpoint);
+
+        let mut req_builder = self.client.get(&url);
+
+        req_builder = self.authorize(req_builder);
+
+        let (req_builder, request_id) = self.tag(req_builder);
+        let response = self.send(req_builder, request_id).await?;
+
+        if response.status().is_success() {
+            let hints: ServerHints = response.json().await
+                .map_err(TransportError::invalid_response)?;
+            Ok(hints)
+        } else {
+            Err(self.error_from_response(response).await.with_request_id(request_id))
+        }
+    }
+
+    async fn bootstrap_identity(&self, one_time_token: &str) -> Result<Identity> {
+        let url = format!("{}/v1/identity/bootstrap", self.endpoint);
+
+        #[derive(serde::Serialize)]
+        struct BootstrapRequest<'a> {
+            one_time_token: &'a str,
+        }
+
+        // Keep the serialized token in a buffer that is wiped once sent
+        let body = zeroize::Zeroizing::new(serde_json::to_vec(&BootstrapRequest { one_time_token })?);
+
+        let (req_builder, request_id) = self.tag(self.signed_body(self.client.post(&url), body));
+        let response = self.send(req_builder, request_id).await?;
+
+        let status = response.status();
+        if status.is_success() {
+            let identity: Identity = response.json().await
+                .map_err(TransportError::invalid_response)?;
+            Ok(identity)
+        } else if matches!(status.as_u16(), 401 | 403 | 409 | 410) {
+            let error_text = response.text().await.unwrap_or_default();
+            Err(VaultError::from(IdentityError::InvalidToken(
+                format!("one-time token rejected (HTTP {}): {}", status, error_text)
+            )).with_request_id(request_id))
+        } else {
+            Err(self.error_from_response(response).await.with_request_id(request_id))
+        }
+    }
+
+    fn set_request_timeout(&self, timeout: Duration) -> bool {
+        self.request_timeout.set(timeout);
+        true
+    }
+
+    async fn close(&self) -> Result<()> {
+        // HTTP client doesn't need explicit closing
+        Ok(())
+    }
+}
+
+#[cfg(feature = "transport-unix")]
+/// Unix socket transport implementation
+#[derive(Debug)]
+pub struct UnixTransport {
+    socket_path: String,
+    _client: tokio::net::UnixStream, // Placeholder for actual implementation
+}
+
+#[cfg(feature = "transport-unix")]
+impl UnixTransport {
+    /// Create new Unix socket transport
+    pub async fn new(config: &crate::config::Config) -> Result<Self> {
+        let socket_path = config.endpoint.strip_prefix("unix://")
+            .unwrap_or(&config.endpoint)
+            .to_string();
+
+        // TODO: Implement actual Unix socket connection
+        let _client = connect_phase(config.timeouts.connect, async {
+            tokio::net::UnixStream::connect(&socket_path)
+                .await
+                .map_err(|e| TransportError::ConnectionFailed(
+                    format!("Failed to connect to Unix socket: {}", e)
+                ).into())
+        }).await?;
+
+        Ok(Self {
+            socket_path,
+            _client,
+        })
+    }
+}
+
+#[cfg(feature = "transport-unix")]
+#[async_trait]
+impl Transport for UnixTransport {
+    async fn request_capability(
+        &self,
+        _identity: &Identity,
+        _request: &CapabilityRequest,
+    ) -> Result<Capability> {
+        // TODO: Implement Unix socket transport
+        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    }
+
+    async fn access_with_capability<T>(&self, _capability: &Capability) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        // TODO: Implement Unix socket transport
+        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    }
+
+    async fn access_with_reason<T>(&self, _capability: &Capability, _reason: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        // TODO: Implement Unix socket transport
+        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    }
+
+    async fn revoke_capability(&self, _capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        // TODO: Implement Unix socket transport
+        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    }
+
+    async fn revocation_status(&self, _capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        // TODO: Implement Unix socket transport
+        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    }
+
+    async fn refresh_capability(
+        &self,
+        _identity: &Identity,
+        _capability_id: uuid::Uuid,
+        _new_ttl: Duration,
+    ) -> Result<Capability> {
+        // TODO: Implement Unix socket transport
+        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    }
+
+    async fn status(&self) -> Result<crate::client::VaultStatus> {
+        // TODO: Implement Unix socket transport
+        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    }
+
+    async fn health_check(&self) -> Result<crate::client::HealthStatus> {
+        // TODO: Implement Unix socket transport
+        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    }
+
+    async fn client_config(&self) -> Result<ServerHints> {
+        // TODO: Implement Unix socket transport
+        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    }
+
+    async fn bootstrap_identity(&self, _one_time_token: &str) -> Result<Identity> {
+        // TODO: Implement Unix socket transport
+        Err(TransportError::Protocol("Unix socket transport not implemented".to_string()).into())
+    }
+
+    async fn close(&self) -> Result<()> {
+        // TODO: Implement Unix socket cleanup
+        Ok(())
+    }
+}
+
+#[cfg(feature = "transport-mtls")]
+/// mTLS transport implementation
+#[derive(Debug)]
+pub struct MtlsTransport {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+#[cfg(feature = "transport-mtls")]
+impl MtlsTransport {
+    /// Create new mTLS transport
+    pub async fn new(config: &crate::config::Config) -> Result<Self> {
+        // TODO: Implement mTLS client configuration
+        let client = reqwest::Client::builder()
+            .timeout(config.timeouts.request)
+            .connect_timeout(config.timeouts.connect)
+            .build()
+            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            endpoint: config.endpoint.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "transport-mtls")]
+#[async_trait]
+impl Transport for MtlsTransport {
+    async fn request_capability(
+        &self,
         _identity: &Identity,
         _request: &CapabilityRequest,
     ) -> Result<Capability> {
@@ -395,54 +1654,632 @@ impl Transport for MtlsTransport {
         Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
     }
 
-    async fn access_with_capability<T>(&self, _capability: &Capability) -> Result<T>
-    where
-        T: serde::de::DeserializeOwned + Send,
-    {
-        // TODO: Implement mTLS transport
-        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    async fn access_with_capability<T>(&self, _capability: &Capability) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        // TODO: Implement mTLS transport
+        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    }
+
+    async fn access_with_reason<T>(&self, _capability: &Capability, _reason: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        // TODO: Implement mTLS transport
+        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    }
+
+    async fn revoke_capability(&self, _capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        // TODO: Implement mTLS transport
+        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    }
+
+    async fn revocation_status(&self, _capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        // TODO: Implement mTLS transport
+        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    }
+
+    async fn refresh_capability(
+        &self,
+        _identity: &Identity,
+        _capability_id: uuid::Uuid,
+        _new_ttl: Duration,
+    ) -> Result<Capability> {
+        // TODO: Implement mTLS transport
+        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    }
+
+    async fn status(&self) -> Result<crate::client::VaultStatus> {
+        // TODO: Implement mTLS transport
+        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    }
+
+    async fn health_check(&self) -> Result<crate::client::HealthStatus> {
+        // TODO: Implement mTLS transport
+        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    }
+
+    async fn client_config(&self) -> Result<ServerHints> {
+        // TODO: Implement mTLS transport
+        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    }
+
+    async fn bootstrap_identity(&self, _one_time_token: &str) -> Result<Identity> {
+        // TODO: Implement mTLS transport
+        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    }
+
+    async fn close(&self) -> Result<()> {
+        // TODO: Implement mTLS cleanup
+        Ok(())
+    }
+}
+
+#[cfg(feature = "transport-grpc")]
+/// Fully-qualified name of the Vault gRPC service
+const GRPC_SERVICE: &str = "aether.vault.v1.Vault";
+
+#[cfg(feature = "transport-grpc")]
+/// Message exchanged by every Vault RPC
+///
+/// The single field carries the JSON encoding of the same bodies the REST
+/// API uses, so both transports share one schema. The body is wiped when
+/// the message is dropped.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GrpcMessage {
+    /// JSON-encoded request or response body
+    #[prost(bytes = "vec", tag = "1")]
+    pub json: Vec<u8>,
+}
+
+#[cfg(feature = "transport-grpc")]
+impl Drop for GrpcMessage {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.json);
+    }
+}
+
+#[cfg(feature = "transport-grpc")]
+/// gRPC transport implementation
+///
+/// Calls the unary RPCs of the `aether.vault.v1.Vault` service. The identity
+/// token travels in `x-vault-identity` metadata and the configured token in
+/// `authorization`.
+pub struct GrpcTransport {
+    channel: tonic::transport::Channel,
+    auth_header: Option<BearerToken>,
+    rate_limit_fallback: Duration,
+    request_timeout: RequestTimeout,
+}
+
+#[cfg(feature = "transport-grpc")]
+impl std::fmt::Debug for GrpcTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GrpcTransport")
+            .field("auth_header", &self.auth_header.as_ref().map(|_| "***"))
+            .field("rate_limit_fallback", &self.rate_limit_fallback)
+            .field("request_timeout", &self.request_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "transport-grpc")]
+impl GrpcTransport {
+    /// Create new gRPC transport
+    ///
+    /// The connection is established lazily on the first call.
+    pub async fn new(config: &crate::config::Config) -> Result<Self> {
+        let channel = tonic::transport::Endpoint::from_shared(config.endpoint.clone())
+            .map_err(|e| TransportError::InvalidEndpoint(e.to_string()))?
+            .connect_timeout(config.timeouts.connect)
+            .connect_lazy();
+
+        let auth_header = BearerToken::from_config(&config.auth)?;
+
+        Ok(Self {
+            channel,
+            auth_header,
+            rate_limit_fallback: config.retry.base_delay,
+            request_timeout: RequestTimeout::new(config.timeouts.request),
+        })
+    }
+
+    /// Call `method` with a JSON body and optional metadata
+    async fn call<B, T>(&self, method: &'static str, body: &B, metadata: &[(&'static str, &str)]) -> Result<T>
+    where
+        B: serde::Serialize + ?Sized,
+        T: serde::de::DeserializeOwned,
+    {
+        let json = self.call_raw(method, Zeroizing::new(serde_json::to_vec(body)?), metadata).await?;
+        serde_json::from_slice(&json).map_err(|e| TransportError::invalid_response(e).into())
+    }
+
+    /// Call `method` and return the raw JSON response body
+    async fn call_raw(
+        &self,
+        method: &'static str,
+        json: Zeroizing<Vec<u8>>,
+        metadata: &[(&'static str, &str)],
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        self.call_using(None, method, json, metadata).await
+    }
+
+    /// Access a resource with `capability`
+    async fn access<T>(&self, capability: &Capability, metadata: &[(&'static str, &str)]) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let body = Zeroizing::new(serde_json::to_vec(capability)?);
+        let json = self.call_using(Some(capability), "Access", body, metadata).await?;
+        serde_json::from_slice(&json).map_err(|e| TransportError::invalid_response(e).into())
+    }
+
+    /// [`GrpcTransport::call_raw`] for a call using `capability`
+    ///
+    /// The server refusing the capability itself becomes a `CapabilityError`
+    /// (see [`GrpcTransport::error_from_status`]).
+    async fn call_using(
+        &self,
+        capability: Option<&Capability>,
+        method: &'static str,
+        json: Zeroizing<Vec<u8>>,
+        metadata: &[(&'static str, &str)],
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        let request_id = current_request_id();
+        self.call_tagged(capability, method, json, metadata, request_id)
+            .await
+            .map_err(|e| e.with_request_id(request_id))
+    }
+
+    /// Call `method` sending `request_id` as `x-request-id`
+    async fn call_tagged(
+        &self,
+        capability: Option<&Capability>,
+        method: &'static str,
+        mut json: Zeroizing<Vec<u8>>,
+        metadata: &[(&'static str, &str)],
+        request_id: uuid::Uuid,
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        let timeout = effective_request_timeout(self.request_timeout.get());
+        let mut request = tonic::Request::new(GrpcMessage { json: std::mem::take(&mut *json) });
+        request.set_timeout(timeout);
+        request.metadata_mut().insert("x-request-id", metadata_value("x-request-id", &request_id.to_string())?);
+        if let Some(traceparent) = current_traceparent() {
+            request.metadata_mut().insert("traceparent", metadata_value("traceparent", &traceparent)?);
+        }
+        if let Some(token) = &self.auth_header {
+            request.metadata_mut().insert("authorization", metadata_value("authorization", &token.header())?);
+        }
+        for (key, value) in metadata {
+            request.metadata_mut().insert(*key, metadata_value(key, value)?);
+        }
+
+        let mut grpc = tonic::client::Grpc::new(self.channel.clone());
+        grpc.ready()
+            .await
+            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?;
+
+        let path = format!("/{}/{}", GRPC_SERVICE, method);
+        let path = tonic::codegen::http::uri::PathAndQuery::try_from(path)
+            .map_err(|e| TransportError::Protocol(e.to_string()))?;
+        let codec = tonic::codec::ProstCodec::<GrpcMessage, GrpcMessage>::default();
+
+        let response = request_phase(timeout, async {
+            grpc.unary(request, path, codec)
+                .await
+                .map_err(|status| self.error_from_status(status, capability))
+        }).await?;
+        Ok(Zeroizing::new(std::mem::take(&mut response.into_inner().json)))
+    }
+
+    /// Translate a gRPC status into the matching `VaultError`
+    ///
+    /// For a call using `capability`, an `x-vault-error-code` of
+    /// `CAPABILITY_REVOKED` or `CAPABILITY_EXPIRED` becomes the matching
+    /// `CapabilityError`, whatever the status code.
+    fn error_from_status(&self, status: tonic::Status, capability: Option<&Capability>) -> VaultError {
+        let code = status.metadata().get("x-vault-error-code").and_then(|value| value.to_str().ok());
+        if let Some(refusal) = capability.zip(code).and_then(|(capability, code)| capability_refusal(capability, code)) {
+            return refusal;
+        }
+
+        let message = status.message().to_string();
+        match status.code() {
+            tonic::Code::Unavailable => TransportError::ConnectionFailed(message).into(),
+            tonic::Code::ResourceExhausted => {
+                let retry_after = status.metadata()
+                    .get("retry-after")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| parse_retry_after(value, chrono::Utc::now()))
+                    .unwrap_or(self.rate_limit_fallback);
+                VaultError::RateLimit(retry_after)
+            }
+            tonic::Code::DeadlineExceeded => VaultError::Timeout(effective_request_timeout(self.request_timeout.get())),
+            tonic::Code::Unauthenticated => VaultError::AuthenticationFailed(message),
+            tonic::Code::PermissionDenied => VaultError::AccessDenied(message),
+            tonic::Code::InvalidArgument => VaultError::Validation(message),
+            code => VaultError::Server(format!("gRPC {:?}: {}", code, message)),
+        }
+    }
+}
+
+/// Build an ASCII metadata value, rejecting anything gRPC can't carry
+#[cfg(feature = "transport-grpc")]
+fn metadata_value(key: &str, value: &str) -> Result<tonic::metadata::AsciiMetadataValue> {
+    value.parse().map_err(|_| {
+        TransportError::Protocol(format!("{} metadata is not valid ASCII", key)).into()
+    })
+}
+
+#[cfg(feature = "transport-grpc")]
+#[async_trait]
+impl Transport for GrpcTransport {
+    async fn request_capability(
+        &self,
+        identity: &Identity,
+        request: &CapabilityRequest,
+    ) -> Result<Capability> {
+        let key = request.idempotency_key.map(|key| key.to_string());
+        let mut metadata = vec![("x-vault-identity", identity.token())];
+        if let Some(key) = &key {
+            metadata.push(("idempotency-key", key.as_str()));
+        }
+        self.call("RequestCapability", request, &metadata).await
+    }
+
+    async fn access_with_capability<T>(&self, capability: &Capability) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        self.access(capability, &[]).await
+    }
+
+    async fn access_with_reason<T>(&self, capability: &Capability, reason: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        self.access(capability, &[("x-vault-access-reason", reason)]).await
+    }
+
+    async fn revoke_capability(&self, capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        self.revoke_capability_with_reason(capability_id, &RevokeReason::Unspecified).await
+    }
+
+    async fn revoke_capability_with_reason(
+        &self,
+        capability_id: uuid::Uuid,
+        reason: &RevokeReason,
+    ) -> Result<RevocationReceipt> {
+        let mut body = serde_json::json!({ "id": capability_id });
+        if *reason != RevokeReason::Unspecified {
+            body["reason"] = serde_json::json!(reason.to_string());
+        }
+        let json = self.call_raw("RevokeCapability", Zeroizing::new(serde_json::to_vec(&body)?), &[]).await?;
+
+        // Servers revoking synchronously may return an empty body
+        if json.is_empty() {
+            Ok(RevocationReceipt::completed(capability_id))
+        } else {
+            serde_json::from_slice(&json).map_err(|e| TransportError::invalid_response(e).into())
+        }
+    }
+
+    async fn revocation_status(&self, capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        self.call("RevocationStatus", &serde_json::json!({ "id": capability_id }), &[]).await
+    }
+
+    async fn refresh_capability(
+        &self,
+        identity: &Identity,
+        capability_id: uuid::Uuid,
+        new_ttl: Duration,
+    ) -> Result<Capability> {
+        let body = RefreshBody { id: Some(capability_id), ttl: new_ttl };
+        self.call("RefreshCapability", &body, &[("x-vault-identity", identity.token())]).await
+    }
+
+    async fn status(&self) -> Result<crate::client::VaultStatus> {
+        self.call("Status", &serde_json::json!({}), &[]).await
+    }
+
+    async fn health_check(&self) -> Result<crate::client::HealthStatus> {
+        self.call("Health", &serde_json::json!({}), &[]).await
+    }
+
+    async fn client_config(&self) -> Result<ServerHints> {
+        self.call("ClientConfig", &serde_json::json!({}), &[]).await
+    }
+
+    async fn bootstrap_identity(&self, one_time_token: &str) -> Result<Identity> {
+        // Keep the serialized token in a buffer that is wiped once sent
+        let body = Zeroizing::new(serde_json::to_vec(&serde_json::json!({ "one_time_token": one_time_token }))?);
+
+        match self.call_raw("BootstrapIdentity", body, &[]).await {
+            Ok(json) => serde_json::from_slice(&json)
+                .map_err(|e| TransportError::invalid_response(e).into()),
+            Err(e) if matches!(e.root(), VaultError::AuthenticationFailed(_) | VaultError::AccessDenied(_)) => {
+                let request_id = e.request_id();
+                let rejected = VaultError::from(IdentityError::InvalidToken(format!("one-time token rejected: {}", e.root())));
+                Err(match request_id {
+                    Some(request_id) => rejected.with_request_id(request_id),
+                    None => rejected,
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn set_request_timeout(&self, timeout: Duration) -> bool {
+        self.request_timeout.set(timeout);
+        true
+    }
+
+    async fn close(&self) -> Result<()> {
+        // Channels close when the last clone is dropped
+        Ok(())
+    }
+}
+
+/// How a response relates to HA standby handling, see [`HttpTransport::send`]
+enum Standby {
+    /// An ordinary response
+    Not(reqwest::Response),
+    /// A standby refusal, with where the active node serves the request if known
+    Active(Option<reqwest::Url>),
+    /// An error response that is not a standby refusal
+    Failed(VaultError),
+}
+
+/// `url` with its scheme, host and port taken from the node at `address`
+fn rebase_url(url: &reqwest::Url, address: &str) -> Option<reqwest::Url> {
+    let active = reqwest::Url::parse(address).ok()?;
+    let mut rebased = url.clone();
+    rebased.set_scheme(active.scheme()).ok()?;
+    rebased.set_host(active.host_str()).ok()?;
+    rebased.set_port(active.port()).ok()?;
+    Some(rebased)
+}
+
+/// Wrap a mock secret in the access envelope, leased for the capability's remaining TTL
+fn mock_access_response(capability: &Capability, data: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "data": data,
+        "lease_duration": capability.remaining_ttl().map_or(0, |ttl| ttl.as_secs()),
+        "renewable": true,
+        "version": 1,
+        "request_id": uuid::Uuid::new_v4(),
+    })
+}
+
+/// Mock transport for testing
+#[derive(Debug)]
+pub struct MockTransport {
+    capabilities: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<uuid::Uuid, Capability>>>,
+    failures: std::sync::Arc<std::sync::atomic::AtomicU32>,
+    bootstrap_tokens: std::sync::Arc<std::sync::Mutex<std::collections::HashSet<String>>>,
+    revocation_delay: std::sync::Arc<std::sync::atomic::AtomicU32>,
+    pending_revocations: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<uuid::Uuid, u32>>>,
+    latency: std::sync::Arc<std::sync::Mutex<(Duration, Duration)>>,
+    timeouts: crate::config::TimeoutConfig,
+    hints: std::sync::Arc<std::sync::Mutex<ServerHints>>,
+    denied_targets: std::sync::Arc<std::sync::Mutex<std::collections::HashSet<String>>>,
+    decisions: std::sync::Arc<std::sync::Mutex<HashMap<String, PolicyDecision>>>,
+    sealed_polls: std::sync::Arc<std::sync::atomic::AtomicU32>,
+    stream_payload: std::sync::Arc<std::sync::Mutex<Vec<u8>>>,
+    accesses: std::sync::Arc<std::sync::atomic::AtomicU32>,
+    health: std::sync::Arc<std::sync::Mutex<Vec<crate::client::HealthDetail>>>,
+    features: std::sync::Arc<std::sync::Mutex<Option<Vec<String>>>>,
+    signing_key: Option<std::sync::Arc<ring::signature::Ed25519KeyPair>>,
+    idempotency_keys: std::sync::Arc<std::sync::Mutex<Vec<uuid::Uuid>>>,
+    issued_by_key: std::sync::Arc<std::sync::Mutex<HashMap<uuid::Uuid, uuid::Uuid>>>,
+    lost_responses: std::sync::Arc<std::sync::atomic::AtomicU32>,
+    revoke_reasons: std::sync::Arc<std::sync::Mutex<Vec<(uuid::Uuid, RevokeReason)>>>,
+    silently_revoked: std::sync::Arc<std::sync::Mutex<std::collections::HashSet<uuid::Uuid>>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self {
+            capabilities: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            failures: std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0)),
+            bootstrap_tokens: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashSet::new())),
+            revocation_delay: std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0)),
+            pending_revocations: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            latency: std::sync::Arc::new(std::sync::Mutex::new((Duration::ZERO, Duration::ZERO))),
+            timeouts: crate::config::TimeoutConfig::default(),
+            hints: std::sync::Arc::new(std::sync::Mutex::new(ServerHints::default())),
+            denied_targets: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashSet::new())),
+            decisions: std::sync::Arc::new(std::sync::Mutex::new(HashMap::new())),
+            sealed_polls: std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0)),
+            stream_payload: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            accesses: std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0)),
+            health: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            features: std::sync::Arc::new(std::sync::Mutex::new(None)),
+            signing_key: None,
+            idempotency_keys: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            issued_by_key: std::sync::Arc::new(std::sync::Mutex::new(HashMap::new())),
+            lost_responses: std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0)),
+            revoke_reasons: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            silently_revoked: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashSet::new())),
+        }
+    }
+
+    /// Advertise `features` from `status`, refusing batch calls when it lacks `batch`
+    pub fn set_features(&self, features: Option<Vec<String>>) {
+        *self.features.lock().unwrap_or_else(PoisonError::into_inner) = features;
+    }
+
+    /// Fail unless the advertised features include `feature`
+    fn check_feature(&self, feature: crate::client::Feature) -> Result<()> {
+        match &*self.features.lock().unwrap_or_else(PoisonError::into_inner) {
+            Some(features) if !features.iter().any(|f| f == feature.name()) => Err(TransportError::Protocol(
+                format!("{} is not supported by this server", feature.name()),
+            ).into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Issue the next `count` capabilities but fail as if their responses were lost
+    pub fn lose_responses(&self, count: u32) {
+        self.lost_responses.store(count, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Idempotency keys received by `request_capability`, in order
+    pub fn idempotency_keys(&self) -> Vec<uuid::Uuid> {
+        self.idempotency_keys.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Revoke a capability server-side without telling the client
+    ///
+    /// Later accesses with it fail with `CapabilityError::Revoked`.
+    pub fn revoke_silently(&self, capability_id: uuid::Uuid) {
+        self.silently_revoked.lock().unwrap_or_else(PoisonError::into_inner).insert(capability_id);
+    }
+
+    /// Capabilities revoked so far with the reason given, in order
+    pub fn revoke_reasons(&self) -> Vec<(uuid::Uuid, RevokeReason)> {
+        self.revoke_reasons.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Number of capabilities issued so far
+    pub fn issued_count(&self) -> usize {
+        self.capabilities.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Report the given component details from `health_check`
+    pub fn set_health(&self, details: Vec<crate::client::HealthDetail>) {
+        *self.health.lock().unwrap_or_else(PoisonError::into_inner) = details;
+    }
+
+    /// Number of access requests served so far
+    pub fn access_count(&self) -> u32 {
+        self.accesses.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Serve `payload` from `access_stream`
+    pub fn set_stream_payload(&self, payload: Vec<u8>) {
+        *self.stream_payload.lock().unwrap_or_else(PoisonError::into_inner) = payload;
+    }
+
+    /// Refuse capability requests for `target`
+    pub fn deny_target(&self, target: &str) {
+        self.denied_targets.lock().unwrap_or_else(PoisonError::into_inner).insert(target.to_string());
+    }
+
+    /// Answer policy checks for `target` with `decision`
+    ///
+    /// Targets without a configured decision are allowed for the requested
+    /// TTL, unless denied with [`MockTransport::deny_target`].
+    pub fn set_policy_decision(&self, target: &str, decision: PolicyDecision) {
+        self.decisions.lock().unwrap_or_else(PoisonError::into_inner).insert(target.to_string(), decision);
+    }
+
+    /// Issue a capability for a request, unless its target is denied
+    fn issue(&self, identity: &Identity, request: &CapabilityRequest) -> Result<Capability> {
+        if self.denied_targets.lock().unwrap_or_else(PoisonError::into_inner).contains(&request.target) {
+            return Err(VaultError::AccessDenied(format!("target {} is denied", request.target)));
+        }
+
+        let mut capability = Capability::new(
+            request.domain.clone(),
+            request.action.clone(),
+            request.target.clone(),
+            request.context.clone(),
+            request.ttl,
+            "mock-vault".to_string(),
+            identity.subject().unwrap_or_else(|| "mock-client".to_string()),
+        );
+        self.sign(&mut capability);
+
+        let mut caps = self.capabilities.lock().unwrap_or_else(PoisonError::into_inner);
+        caps.insert(capability.id, capability.clone());
+
+        Ok(capability)
+    }
+
+    /// Serve the given document from `client_config`
+    pub fn set_server_hints(&self, hints: ServerHints) {
+        *self.hints.lock().unwrap_or_else(PoisonError::into_inner) = hints;
+    }
+
+    /// Sign issued and refreshed capabilities with an Ed25519 key from a 32-byte seed
+    pub fn with_signing_key(mut self, seed: &[u8]) -> Result<Self> {
+        let key_pair = ring::signature::Ed25519KeyPair::from_seed_unchecked(seed)
+            .map_err(|_| crate::error::CryptoError::InvalidKeyFormat("invalid Ed25519 seed".to_string()))?;
+        self.signing_key = Some(std::sync::Arc::new(key_pair));
+        Ok(self)
     }
 
-    async fn revoke_capability(&self, _capability_id: uuid::Uuid) -> Result<()> {
-        // TODO: Implement mTLS transport
-        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    /// Public key verifying the capabilities this mock signs, if it signs them
+    pub fn public_key(&self) -> Option<Vec<u8>> {
+        use ring::signature::KeyPair;
+        self.signing_key.as_ref().map(|key_pair| key_pair.public_key().as_ref().to_vec())
     }
 
-    async fn refresh_capability(
-        &self,
-        _identity: &Identity,
-        _capability_id: uuid::Uuid,
-        _new_ttl: Duration,
-    ) -> Result<Capability> {
-        // TODO: Implement mTLS transport
-        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    /// Sign `capability` when a signing key is configured
+    fn sign(&self, capability: &mut Capability) {
+        if let Some(key_pair) = &self.signing_key {
+            capability.signature = key_pair.sign(&capability.signing_payload()).as_ref().to_vec();
+        }
     }
 
-    async fn status(&self) -> Result<crate::client::VaultStatus> {
-        // TODO: Implement mTLS transport
-        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    /// Use the given connect/request timeouts
+    pub fn with_timeouts(mut self, timeouts: crate::config::TimeoutConfig) -> Self {
+        self.timeouts = timeouts;
+        self
     }
 
-    async fn health_check(&self) -> Result<crate::client::HealthStatus> {
-        // TODO: Implement mTLS transport
-        Err(TransportError::Protocol("mTLS transport not implemented".to_string()).into())
+    /// Simulate time spent connecting and waiting for a response on every call
+    pub fn set_latency(&self, connect: Duration, respond: Duration) {
+        *self.latency.lock().unwrap_or_else(PoisonError::into_inner) = (connect, respond);
     }
 
-    async fn close(&self) -> Result<()> {
-        // TODO: Implement mTLS cleanup
-        Ok(())
+    /// Apply the simulated latency under the configured phase timeouts
+    async fn simulate_latency(&self) -> Result<()> {
+        let (connect, respond) = *self.latency.lock().unwrap_or_else(PoisonError::into_inner);
+
+        connect_phase(self.timeouts.connect, async {
+            tokio::time::sleep(connect).await;
+            Ok(())
+        }).await?;
+
+        request_phase(effective_request_timeout(self.timeouts.request), async {
+            tokio::time::sleep(respond).await;
+            Ok(())
+        }).await
     }
-}
 
-/// Mock transport for testing
-pub struct MockTransport {
-    capabilities: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<uuid::Uuid, Capability>>>,
-}
+    /// Report the vault sealed for the next `polls` status checks
+    pub fn seal_for(&self, polls: u32) {
+        self.sealed_polls.store(polls, std::sync::atomic::Ordering::SeqCst);
+    }
 
-impl MockTransport {
-    pub fn new() -> Self {
-        Self {
-            capabilities: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+    /// Queue revocations so they complete only after `polls` status checks
+    pub fn set_revocation_delay(&self, polls: u32) {
+        self.revocation_delay.store(polls, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Register a one-time bootstrap token the mock will accept once
+    pub fn add_bootstrap_token(&self, token: &str) {
+        self.bootstrap_tokens.lock().unwrap_or_else(PoisonError::into_inner).insert(token.to_string());
+    }
+
+    /// Make the next `count` calls fail with a retryable connection error
+    pub fn fail_next(&self, count: u32) {
+        self.failures.store(count, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    /// Consume one injected failure, if any
+    fn injected_failure(&self) -> Result<()> {
+        let remaining = self.failures.fetch_update(
+            std::sync::atomic::Ordering::SeqCst,
+            std::sync::atomic::Ordering::SeqCst,
+            |n| n.checked_sub(1),
+        );
+        match remaining {
+            Ok(_) => Err(TransportError::ConnectionFailed("injected mock failure".to_string()).into()),
+            Err(_) => Ok(()),
         }
     }
 }
@@ -451,44 +2288,166 @@ impl MockTransport {
 impl Transport for MockTransport {
     async fn request_capability(
         &self,
-        _identity: &Identity,
+        identity: &Identity,
         request: &CapabilityRequest,
     ) -> Result<Capability> {
-        let capability = Capability::new(
-            request.domain.clone(),
-            request.action.clone(),
-            request.target.clone(),
-            request.context.clone(),
-            request.ttl,
-            "mock-vault".to_string(),
-            "mock-client".to_string(),
-        );
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        // Replay the capability already issued under this key
+        if let Some(key) = request.idempotency_key {
+            self.idempotency_keys.lock().unwrap_or_else(PoisonError::into_inner).push(key);
+            let issued = self.issued_by_key.lock().unwrap_or_else(PoisonError::into_inner).get(&key).copied();
+            if let Some(capability) = issued.and_then(|id| self.capabilities.lock().unwrap_or_else(PoisonError::into_inner).get(&id).cloned()) {
+                return Ok(capability);
+            }
+        }
 
-        let mut caps = self.capabilities.lock().unwrap();
-        caps.insert(capability.id, capability.clone());
+        let capability = self.issue(identity, request)?;
+        if let Some(key) = request.idempotency_key {
+            self.issued_by_key.lock().unwrap_or_else(PoisonError::into_inner).insert(key, capability.id);
+        }
 
+        let lost = self.lost_responses.fetch_update(
+            std::sync::atomic::Ordering::SeqCst,
+            std::sync::atomic::Ordering::SeqCst,
+            |n| n.checked_sub(1),
+        );
+        if lost.is_ok() {
+            return Err(TransportError::ConnectionFailed("mock response lost".to_string()).into());
+        }
         Ok(capability)
     }
 
+    async fn request_capabilities(
+        &self,
+        identity: &Identity,
+        requests: &[CapabilityRequest],
+    ) -> Result<Vec<Result<Capability>>> {
+        // One simulated round trip for the whole batch
+        self.check_feature(crate::client::Feature::Batch)?;
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        Ok(requests.iter().map(|request| self.issue(identity, request)).collect())
+    }
+
+    async fn access_stream(&self, _capability: &Capability) -> Result<SecretStream> {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        let payload = self.stream_payload.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        Ok(Box::pin(std::io::Cursor::new(payload)))
+    }
+
+    async fn check_capability(&self, _identity: &Identity, request: &CapabilityRequest) -> Result<PolicyDecision> {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        if let Some(decision) = self.decisions.lock().unwrap_or_else(PoisonError::into_inner).get(&request.target) {
+            return Ok(decision.clone());
+        }
+        if self.denied_targets.lock().unwrap_or_else(PoisonError::into_inner).contains(&request.target) {
+            return Ok(PolicyDecision::deny(format!("target {} is denied", request.target)));
+        }
+        Ok(PolicyDecision::allow(request.ttl))
+    }
+
     async fn access_with_capability<T>(&self, capability: &Capability) -> Result<T>
     where
         T: serde::de::DeserializeOwned + Send,
     {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        if self.silently_revoked.lock().unwrap_or_else(PoisonError::into_inner).contains(&capability.id) {
+            return Err(crate::error::CapabilityError::Revoked(capability.id).into());
+        }
+        self.accesses.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
         // For testing, return a simple success response
-        let response = serde_json::json!({
+        let response = mock_access_response(capability, serde_json::json!({
             "success": true,
             "capability_id": capability.id,
             "message": "Access granted"
-        });
+        }));
+
+        serde_json::from_value(response)
+            .map_err(|e| TransportError::invalid_response(e).into())
+    }
+
+    async fn access_with_reason<T>(&self, capability: &Capability, reason: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Send,
+    {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+        self.accesses.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+        let response = mock_access_response(capability, serde_json::json!({
+            "success": true,
+            "capability_id": capability.id,
+            "reason": reason,
+            "message": "Access granted"
+        }));
 
         serde_json::from_value(response)
-            .map_err(|e| TransportError::InvalidResponse(e.to_string()).into())
+            .map_err(|e| TransportError::invalid_response(e).into())
     }
 
-    async fn revoke_capability(&self, capability_id: uuid::Uuid) -> Result<()> {
-        let mut caps = self.capabilities.lock().unwrap();
+    async fn revoke_capability(&self, capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        self.revoke_capability_with_reason(capability_id, &RevokeReason::Unspecified).await
+    }
+
+    async fn revoke_capability_with_reason(
+        &self,
+        capability_id: uuid::Uuid,
+        reason: &RevokeReason,
+    ) -> Result<RevocationReceipt> {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        self.revoke_reasons.lock().unwrap_or_else(PoisonError::into_inner).push((capability_id, reason.clone()));
+        let mut caps = self.capabilities.lock().unwrap_or_else(PoisonError::into_inner);
         caps.remove(&capability_id);
-        Ok(())
+
+        let polls = self.revocation_delay.load(std::sync::atomic::Ordering::SeqCst);
+        if polls == 0 {
+            return Ok(RevocationReceipt::completed(capability_id));
+        }
+
+        self.pending_revocations.lock().unwrap_or_else(PoisonError::into_inner).insert(capability_id, polls);
+        Ok(RevocationReceipt {
+            id: capability_id,
+            status: RevocationStatus::Pending,
+            estimated_effective_at: Some(chrono::Utc::now() + chrono::Duration::seconds(1)),
+        })
+    }
+
+    async fn revocation_status(&self, capability_id: uuid::Uuid) -> Result<RevocationReceipt> {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        let mut pending = self.pending_revocations.lock().unwrap_or_else(PoisonError::into_inner);
+        match pending.get_mut(&capability_id) {
+            Some(remaining) if *remaining > 1 => {
+                *remaining -= 1;
+                Ok(RevocationReceipt {
+                    id: capability_id,
+                    status: RevocationStatus::Pending,
+                    estimated_effective_at: None,
+                })
+            }
+            _ => {
+                pending.remove(&capability_id);
+                Ok(RevocationReceipt::completed(capability_id))
+            }
+        }
+    }
+
+    async fn is_revoked(&self, capability_id: uuid::Uuid) -> Result<Option<bool>> {
+        self.injected_failure()?;
+        Ok(Some(!self.capabilities.lock().unwrap_or_else(PoisonError::into_inner).contains_key(&capability_id)))
     }
 
     async fn refresh_capability(
@@ -497,9 +2456,13 @@ impl Transport for MockTransport {
         capability_id: uuid::Uuid,
         new_ttl: Duration,
     ) -> Result<Capability> {
-        let mut caps = self.capabilities.lock().unwrap();
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        let mut caps = self.capabilities.lock().unwrap_or_else(PoisonError::into_inner);
         if let Some(cap) = caps.get_mut(&capability_id) {
             cap.expires_at = chrono::Utc::now() + chrono::Duration::from_std(new_ttl).unwrap();
+            self.sign(cap);
             Ok(cap.clone())
         } else {
             Err(TransportError::Protocol("Capability not found".to_string()).into())
@@ -507,27 +2470,1131 @@ impl Transport for MockTransport {
     }
 
     async fn status(&self) -> Result<crate::client::VaultStatus> {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        let sealed = self.sealed_polls.fetch_update(
+            std::sync::atomic::Ordering::SeqCst,
+            std::sync::atomic::Ordering::SeqCst,
+            |n| n.checked_sub(1),
+        ).is_ok();
+
         Ok(crate::client::VaultStatus {
             version: "mock-v1.0.0".to_string(),
             server_time: chrono::Utc::now(),
             initialized: true,
-            sealed: false,
+            sealed,
             standby: false,
             performance_mode: Some("standard".to_string()),
             available_storage: Some(1000000000),
             total_storage: Some(2000000000),
+            leader_address: None,
+            api_version: Some("v1".to_string()),
+            features: self.features.lock().unwrap_or_else(PoisonError::into_inner).clone(),
         })
     }
 
     async fn health_check(&self) -> Result<crate::client::HealthStatus> {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
         Ok(crate::client::HealthStatus {
             healthy: true,
-            details: vec![],
+            status: crate::client::HealthStatusType::Unknown,
+            details: self.health.lock().unwrap_or_else(PoisonError::into_inner).clone(),
             timestamp: chrono::Utc::now(),
         })
     }
 
+    async fn ping(&self) -> Result<Duration> {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        let (connect, respond) = *self.latency.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(connect + respond)
+    }
+
+    async fn client_config(&self) -> Result<ServerHints> {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        Ok(self.hints.lock().unwrap_or_else(PoisonError::into_inner).clone())
+    }
+
+    async fn bootstrap_identity(&self, one_time_token: &str) -> Result<Identity> {
+        self.injected_failure()?;
+        self.simulate_latency().await?;
+
+        // Each token is consumed on first use
+        if self.bootstrap_tokens.lock().unwrap_or_else(PoisonError::into_inner).remove(one_time_token) {
+            Ok(Identity::new(format!("mock-workload-{}", uuid::Uuid::new_v4())))
+        } else {
+            Err(IdentityError::InvalidToken("one-time token already used or unknown".to_string()).into())
+        }
+    }
+
     async fn close(&self) -> Result<()> {
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tls_version() {
+        assert!(matches!(parse_tls_version("tls.min_version", "1.2"), Ok(v) if v == reqwest::tls::Version::TLS_1_2));
+        assert!(matches!(parse_tls_version("tls.max_version", "1.3"), Ok(v) if v == reqwest::tls::Version::TLS_1_3));
+        assert!(matches!(
+            parse_tls_version("tls.min_version", "TLSv1.2"),
+            Err(crate::error::VaultError::Config(ConfigError::InvalidValue(_, _)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_tls_version_rejected_at_construction() {
+        let mut config = crate::config::Config::default();
+        config.tls = Some(crate::config::TlsConfig {
+            verify_cert: true,
+            server_name: None,
+            min_version: Some("1.4".to_string()),
+            max_version: None,
+            cipher_suites: None,
+        });
+
+        let result = HttpTransport::new(&config).await;
+        assert!(matches!(
+            result,
+            Err(crate::error::VaultError::Config(ConfigError::InvalidValue(_, _)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_server_name_pins_endpoint_host() {
+        let (endpoint, addr) = pin_server_name("https://127.0.0.1:8200", "vault.internal")
+            .await
+            .unwrap();
+        assert_eq!(endpoint, "https://vault.internal:8200");
+        assert_eq!(addr, "127.0.0.1:8200".parse().unwrap());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = chrono::DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    fn short_timeouts() -> crate::config::TimeoutConfig {
+        crate::config::TimeoutConfig {
+            connect: Duration::from_millis(20),
+            request: Duration::from_millis(20),
+            capability: Duration::from_secs(300),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_connect_yields_connection_timeout() {
+        let transport = MockTransport::new().with_timeouts(short_timeouts());
+        transport.set_latency(Duration::from_millis(200), Duration::ZERO);
+
+        let result = transport.status().await;
+        assert!(matches!(
+            result,
+            Err(VaultError::Transport(TransportError::ConnectionTimeout))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_slow_response_yields_request_timeout() {
+        let transport = MockTransport::new().with_timeouts(short_timeouts());
+        transport.set_latency(Duration::ZERO, Duration::from_millis(200));
+
+        let result = transport.status().await;
+        assert!(matches!(result, Err(VaultError::Timeout(d)) if d == Duration::from_millis(20)));
+    }
+
+    #[tokio::test]
+    async fn test_http_slow_response_yields_request_timeout() {
+        // Accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = format!("http://{}", addr);
+        config.timeouts = short_timeouts();
+        let transport = HttpTransport::new(&config).await.unwrap();
+
+        let err = transport.status().await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::Timeout(_)));
+    }
+
+    #[tokio::test]
+    async fn test_http_transport_debug_redacts_auth_header() {
+        let mut token_file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut token_file, b"s3cr3t-bearer-token").unwrap();
+
+        let mut config = crate::config::Config::default();
+        config.auth.method = crate::config::AuthMethod::Token;
+        config.auth.token_file = Some(token_file.path().to_path_buf());
+
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let debug = format!("{:?}", transport);
+        assert!(debug.contains("auth_header: Some(\"***\")"));
+        assert!(!debug.contains("s3cr3t-bearer-token"));
+    }
+
+    #[tokio::test]
+    async fn test_http_token_file_rotation() {
+        let mut server = mockito::Server::new_async().await;
+        let old = server
+            .mock("GET", "/v1/status")
+            .match_header("authorization", "Bearer old-token")
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+        let new = server
+            .mock("GET", "/v1/status")
+            .match_header("authorization", "Bearer rotated-token")
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let token_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(token_file.path(), "old-token\n").unwrap();
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        config.follow_redirects_to_active = false;
+        config.auth.method = crate::config::AuthMethod::Token;
+        config.auth.token_file = Some(token_file.path().to_path_buf());
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let _ = transport.status().await;
+        old.assert_async().await;
+
+        // Rotated, with a different length so the change shows even on coarse
+        // mtimes; picked up at the next check
+        std::fs::write(token_file.path(), "rotated-token\n").unwrap();
+        tokio::time::sleep(TOKEN_FILE_CHECK_INTERVAL).await;
+        let _ = transport.status().await;
+
+        // A partial write leaves the file empty; the last good token is kept
+        std::fs::write(token_file.path(), "").unwrap();
+        tokio::time::sleep(TOKEN_FILE_CHECK_INTERVAL).await;
+        let _ = transport.status().await;
+        new.assert_async().await;
+
+        // An empty file, or one holding something other than a token, is rejected up front
+        assert!(HttpTransport::new(&config).await.is_err());
+        std::fs::write(token_file.path(), r#"{"token": "rotated-token"}"#).unwrap();
+        assert!(HttpTransport::new(&config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_http_token_sources() {
+        let mut server = mockito::Server::new_async().await;
+        let from_env = server
+            .mock("GET", "/v1/status")
+            .match_header("authorization", "Bearer s.env-token")
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+        let from_file = server
+            .mock("GET", "/v1/status")
+            .match_header("authorization", "Bearer s.file-token")
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let token_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(token_file.path(), "s.file-token").unwrap();
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        config.follow_redirects_to_active = false;
+        config.auth.method = crate::config::AuthMethod::Token;
+
+        // Env only
+        config.auth.token = Some(Zeroizing::new("s.env-token".to_string()));
+        let transport = HttpTransport::new(&config).await.unwrap();
+        assert!(!format!("{:?}", transport).contains("s.env-token"));
+        let _ = transport.status().await;
+        from_env.assert_async().await;
+
+        // File only
+        config.auth.token = None;
+        config.auth.token_file = Some(token_file.path().to_path_buf());
+        let _ = HttpTransport::new(&config).await.unwrap().status().await;
+
+        // Both present: the file wins
+        config.auth.token = Some(Zeroizing::new("s.env-token".to_string()));
+        let _ = HttpTransport::new(&config).await.unwrap().status().await;
+        from_file.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_transport_pool_shares_by_settings() {
+        let pool = TransportPool::new();
+        let config = crate::config::Config::default();
+
+        let first = pool.get_or_connect(&config).await.unwrap();
+        let second = pool.get_or_connect(&config.clone()).await.unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(pool.len().await, 1);
+
+        // Different credentials never share a transport
+        let mut token_file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut token_file, b"other-token").unwrap();
+        let mut other = config.clone();
+        other.auth.method = crate::config::AuthMethod::Token;
+        other.auth.token_file = Some(token_file.path().to_path_buf());
+        let third = pool.get_or_connect(&other).await.unwrap();
+        assert!(!Arc::ptr_eq(&first, &third));
+        assert_eq!(pool.len().await, 2);
+
+        // Inline tokens are masked in Debug output but still keep transports apart
+        let mut alice = config.clone();
+        alice.auth.method = crate::config::AuthMethod::Token;
+        alice.auth.token = Some(Zeroizing::new("s.alice".to_string()));
+        let mut bob = alice.clone();
+        bob.auth.token = Some(Zeroizing::new("s.bob".to_string()));
+        let alice_transport = pool.get_or_connect(&alice).await.unwrap();
+        let bob_transport = pool.get_or_connect(&bob).await.unwrap();
+        assert!(!Arc::ptr_eq(&alice_transport, &bob_transport));
+        assert!(Arc::ptr_eq(&alice_transport, &pool.get_or_connect(&alice).await.unwrap()));
+        assert_eq!(pool.len().await, 4);
+
+        pool.clear().await;
+        assert!(pool.is_empty().await);
+    }
+
+    fn batch_requests() -> Vec<CapabilityRequest> {
+        ["users", "orders"]
+            .iter()
+            .map(|target| {
+                CapabilityRequest::new(
+                    crate::capability::Domain::Database,
+                    crate::capability::Action::Read,
+                    target.to_string(),
+                    crate::capability::CapabilityContext::default(),
+                    Duration::from_secs(60),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_http_batch_mixed_results() {
+        let mut server = mockito::Server::new_async().await;
+        let issued = Capability::new(
+            crate::capability::Domain::Database,
+            crate::capability::Action::Read,
+            "users".to_string(),
+            crate::capability::CapabilityContext::default(),
+            Duration::from_secs(60),
+            "vault".to_string(),
+            "svc".to_string(),
+        );
+        let body = serde_json::json!({
+            "results": [
+                { "capability": issued },
+                { "error": { "code": "ACCESS_DENIED", "message": "orders is off limits" } },
+            ]
+        });
+        let batch = server
+            .mock("POST", "/v1/capabilities/batch")
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let identity = Identity::new("test-token".to_string());
+
+        let results = transport.request_capabilities(&identity, &batch_requests()).await.unwrap();
+        batch.assert_async().await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().id, issued.id);
+        assert!(matches!(&results[1], Err(VaultError::AccessDenied(msg)) if msg == "orders is off limits"));
+    }
+
+    #[tokio::test]
+    async fn test_http_batch_falls_back_without_endpoint() {
+        let mut server = mockito::Server::new_async().await;
+        let _batch = server
+            .mock("POST", "/v1/capabilities/batch")
+            .with_status(404)
+            .create_async()
+            .await;
+        let issued = Capability::new(
+            crate::capability::Domain::Database,
+            crate::capability::Action::Read,
+            "users".to_string(),
+            crate::capability::CapabilityContext::default(),
+            Duration::from_secs(60),
+            "vault".to_string(),
+            "svc".to_string(),
+        );
+        let single = server
+            .mock("POST", "/v1/capabilities")
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::to_string(&issued).unwrap())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let identity = Identity::new("test-token".to_string());
+
+        let results = transport.request_capabilities(&identity, &batch_requests()).await.unwrap();
+        single.assert_async().await;
+        assert!(results.iter().all(|result| result.is_ok()));
+    }
+
+    #[cfg(feature = "transport-grpc")]
+    /// In-process Vault gRPC service answering from a handler function
+    #[derive(Clone)]
+    struct MockGrpcVault {
+        handler: Arc<dyn Fn(&str, &tonic::metadata::MetadataMap, Vec<u8>) -> std::result::Result<Vec<u8>, tonic::Status> + Send + Sync>,
+    }
+
+    #[cfg(feature = "transport-grpc")]
+    /// One unary call routed to the handler
+    struct MockGrpcCall {
+        method: String,
+        vault: MockGrpcVault,
+    }
+
+    #[cfg(feature = "transport-grpc")]
+    impl tonic::server::UnaryService<GrpcMessage> for MockGrpcCall {
+        type Response = GrpcMessage;
+        type Future = tonic::codegen::BoxFuture<tonic::Response<GrpcMessage>, tonic::Status>;
+
+        fn call(&mut self, request: tonic::Request<GrpcMessage>) -> Self::Future {
+            let result = (self.vault.handler)(&self.method, request.metadata(), request.get_ref().json.clone());
+            Box::pin(async move { result.map(|json| tonic::Response::new(GrpcMessage { json })) })
+        }
+    }
+
+    #[cfg(feature = "transport-grpc")]
+    impl<B> tonic::codegen::Service<tonic::codegen::http::Request<B>> for MockGrpcVault
+    where
+        B: tonic::codegen::Body + Send + 'static,
+        B::Error: Into<tonic::codegen::StdError> + Send + 'static,
+    {
+        type Response = tonic::codegen::http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = tonic::codegen::BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(
+            &mut self,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::result::Result<(), Self::Error>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: tonic::codegen::http::Request<B>) -> Self::Future {
+            let call = MockGrpcCall {
+                method: request.uri().path().rsplit('/').next().unwrap_or_default().to_string(),
+                vault: self.clone(),
+            };
+            Box::pin(async move {
+                let mut grpc = tonic::server::Grpc::new(tonic::codec::ProstCodec::<GrpcMessage, GrpcMessage>::default());
+                Ok(grpc.unary(call, request).await)
+            })
+        }
+    }
+
+    #[cfg(feature = "transport-grpc")]
+    impl tonic::server::NamedService for MockGrpcVault {
+        const NAME: &'static str = GRPC_SERVICE;
+    }
+
+    #[cfg(feature = "transport-grpc")]
+    async fn grpc_transport_for(vault: MockGrpcVault) -> GrpcTransport {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(vault)
+                .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener)),
+        );
+
+        let mut config = crate::config::Config::default();
+        config.transport = crate::config::TransportType::Grpc;
+        config.endpoint = format!("http://{}", addr);
+        GrpcTransport::new(&config).await.unwrap()
+    }
+
+    #[cfg(feature = "transport-grpc")]
+    #[tokio::test]
+    async fn test_grpc_transport_against_mock_server() {
+        let vault = MockGrpcVault {
+            handler: Arc::new(|method, metadata, json| match method {
+                "RequestCapability" => {
+                    assert_eq!(metadata.get("x-vault-identity").unwrap().to_str().unwrap(), "test-token");
+                    let request: CapabilityRequest = serde_json::from_slice(&json).unwrap();
+                    let capability = Capability::new(
+                        request.domain,
+                        request.action,
+                        request.target,
+                        request.context,
+                        request.ttl,
+                        "grpc-vault".to_string(),
+                        "svc".to_string(),
+                    );
+                    Ok(serde_json::to_vec(&capability).unwrap())
+                }
+                "RevokeCapability" => Ok(Vec::new()),
+                "Access" => Err(tonic::Status::permission_denied("target is off limits")),
+                "Status" => Err(tonic::Status::unavailable("draining")),
+                _ => Err(tonic::Status::resource_exhausted("slow down")),
+            }),
+        };
+        let transport = grpc_transport_for(vault).await;
+        let identity = Identity::new("test-token".to_string());
+
+        let request = CapabilityRequest::new(
+            crate::capability::Domain::Database,
+            crate::capability::Action::Read,
+            "users".to_string(),
+            crate::capability::CapabilityContext::default(),
+            Duration::from_secs(60),
+        );
+        let capability = transport.request_capability(&identity, &request).await.unwrap();
+        assert_eq!(capability.target, "users");
+        assert_eq!(capability.issuer, "grpc-vault");
+
+        let receipt = transport.revoke_capability(capability.id).await.unwrap();
+        assert_eq!(receipt.status, RevocationStatus::Completed);
+
+        // Status codes map onto the existing error variants
+        let denied: Result<serde_json::Value> = transport.access_with_capability(&capability).await;
+        assert!(matches!(denied.unwrap_err().root(), VaultError::AccessDenied(_)));
+        let unavailable = transport.status().await.unwrap_err();
+        assert!(matches!(unavailable.root(), VaultError::Transport(TransportError::ConnectionFailed(_))));
+        assert!(unavailable.is_retryable());
+        assert!(unavailable.request_id().is_some());
+        assert!(matches!(transport.health_check().await.unwrap_err().root(), VaultError::RateLimit(_)));
+    }
+
+    #[cfg(feature = "transport-grpc")]
+    #[tokio::test]
+    async fn test_grpc_capability_refusals() {
+        let vault = MockGrpcVault {
+            handler: Arc::new(|_, metadata, _| {
+                let mut status = tonic::Status::failed_precondition("capability refused");
+                let reason = metadata.get("x-vault-access-reason").unwrap().to_str().unwrap();
+                if reason != "unknown" {
+                    status.metadata_mut().insert("x-vault-error-code", reason.parse().unwrap());
+                }
+                Err(status)
+            }),
+        };
+        let transport = grpc_transport_for(vault).await;
+        let capability = Capability::new(
+            crate::capability::Domain::Database,
+            crate::capability::Action::Read,
+            "users".to_string(),
+            crate::capability::CapabilityContext::default(),
+            Duration::from_secs(60),
+            "grpc-vault".to_string(),
+            "svc".to_string(),
+        );
+        let access = |code| transport.access_with_reason::<serde_json::Value>(&capability, code);
+
+        let revoked = access("CAPABILITY_REVOKED").await.unwrap_err();
+        assert!(matches!(revoked.root(), VaultError::Capability(CapabilityError::Revoked(id)) if *id == capability.id));
+        let expired = access("CAPABILITY_EXPIRED").await.unwrap_err();
+        assert!(matches!(expired.root(), VaultError::Capability(CapabilityError::Expired(at)) if *at == capability.expires_at));
+        let other = access("unknown").await.unwrap_err();
+        assert!(matches!(other.root(), VaultError::Server(_)));
+    }
+
+    #[tokio::test]
+    async fn test_http_capability_refusals() {
+        let mut server = mockito::Server::new_async().await;
+        let gone = server
+            .mock("POST", "/v1/access")
+            .match_header("x-vault-access-reason", "gone")
+            .with_status(410)
+            .create_async()
+            .await;
+        let expired = server
+            .mock("POST", "/v1/access")
+            .match_header("x-vault-access-reason", "expired")
+            .with_status(403)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error":{"code":"CAPABILITY_EXPIRED","message":"capability expired"}}"#)
+            .create_async()
+            .await;
+        let revoked_stream = server
+            .mock("POST", "/v1/access/stream")
+            .with_status(403)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error":{"code":"CAPABILITY_REVOKED","message":"capability revoked"}}"#)
+            .create_async()
+            .await;
+        let denied = server
+            .mock("POST", "/v1/access")
+            .match_header("x-vault-access-reason", "denied")
+            .with_status(403)
+            .with_body("target is off limits")
+            .create_async()
+            .await;
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let capability = Capability::new(
+            crate::capability::Domain::Database,
+            crate::capability::Action::Read,
+            "users".to_string(),
+            crate::capability::CapabilityContext::default(),
+            Duration::from_secs(60),
+            "vault".to_string(),
+            "svc".to_string(),
+        );
+        let access = |reason| transport.access_with_reason::<serde_json::Value>(&capability, reason);
+
+        // 410 without an error code is taken as revoked
+        let err = access("gone").await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::Capability(CapabilityError::Revoked(id)) if *id == capability.id));
+        assert!(err.request_id().is_some());
+
+        // A structured error code wins over the status
+        let err = access("expired").await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::Capability(CapabilityError::Expired(at)) if *at == capability.expires_at));
+        let err = transport.access_stream(&capability).await.err().unwrap();
+        assert!(matches!(err.root(), VaultError::Capability(CapabilityError::Revoked(_))));
+
+        // Other refusals keep their usual mapping
+        let err = access("denied").await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::AccessDenied(msg) if msg == "target is off limits"));
+
+        gone.assert_async().await;
+        expired.assert_async().await;
+        revoked_stream.assert_async().await;
+        denied.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_http_request_id_header_and_error() {
+        let mut server = mockito::Server::new_async().await;
+        let status = server
+            .mock("GET", "/v1/status")
+            .match_header(
+                "x-request-id",
+                mockito::Matcher::Regex("^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[0-9a-f]{4}-[0-9a-f]{12}$".to_string()),
+            )
+            .with_status(503)
+            .with_body("maintenance")
+            .create_async()
+            .await;
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        let transport = HttpTransport::new(&config).await.unwrap();
+
+        let err = transport.status().await.unwrap_err();
+        status.assert_async().await;
+        let request_id = err.request_id().expect("error carries the request id");
+        assert!(err.to_string().contains(&request_id.to_string()));
+        assert!(matches!(err.root(), VaultError::Transport(TransportError::Http { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_http_requests_route_through_proxy() {
+        // Minimal proxy recording the first request it receives
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_addr = listener.local_addr().unwrap();
+        let (seen_tx, seen_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut head = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                head.extend_from_slice(&buf[..n]);
+            }
+            let _ = seen_tx.send(String::from_utf8_lossy(&head).to_string());
+            let _ = socket
+                .write_all(b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                .await;
+        });
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = "http://vault.example.invalid:8200".to_string();
+        config.proxy = Some(crate::config::ProxyConfig {
+            url: format!("http://{}", proxy_addr),
+            username: Some("svc".to_string()),
+            password: Some("hunter2".to_string()),
+            no_proxy: Vec::new(),
+        });
+        let transport = HttpTransport::new(&config).await.unwrap();
+        assert!(transport.status().await.is_err());
+
+        let head = seen_rx.await.unwrap();
+        assert!(head.starts_with("GET http://vault.example.invalid:8200/v1/status "));
+        // base64("svc:hunter2")
+        assert!(head.to_ascii_lowercase().contains("proxy-authorization: basic "));
+        assert!(head.contains("c3ZjOmh1bnRlcjI="));
+    }
+
+    #[tokio::test]
+    async fn test_no_proxy_hosts_bypass_proxy() {
+        let mut server = mockito::Server::new_async().await;
+        let health = server
+            .mock("GET", "/v1/health")
+            .with_status(503)
+            .create_async()
+            .await;
+
+        // Nothing listens on the proxy address
+        let unused = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy_url = format!("http://{}", unused.local_addr().unwrap());
+        drop(unused);
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        config.proxy = Some(crate::config::ProxyConfig {
+            url: proxy_url,
+            username: None,
+            password: None,
+            no_proxy: vec!["127.0.0.1".to_string()],
+        });
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let _ = transport.health_check().await;
+        health.assert_async().await;
+
+        config.proxy.as_mut().unwrap().url = "http://exa mple.com".to_string();
+        assert!(matches!(
+            HttpTransport::new(&config).await,
+            Err(VaultError::Config(ConfigError::InvalidValue(..)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_http_decode_failure_keeps_source() {
+        use std::error::Error as _;
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/status")
+            .with_header("content-type", "application/json")
+            .with_body("{not json")
+            .create_async()
+            .await;
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        let transport = HttpTransport::new(&config).await.unwrap();
+
+        let err = transport.status().await.unwrap_err();
+        assert!(err.source().is_some());
+        assert!(matches!(err.root(), VaultError::Transport(TransportError::InvalidResponse { source: Some(_), .. })));
+        let reaches_reqwest = std::iter::successors(Some(&err as &(dyn std::error::Error + 'static)), |e| e.source())
+            .any(|e| e.downcast_ref::<reqwest::Error>().is_some());
+        assert!(reaches_reqwest);
+    }
+
+    #[tokio::test]
+    async fn test_http_hmac_signed_body() {
+        use base64::Engine;
+
+        let key_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(key_file.path(), "shared-secret\n").unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let refresh = server
+            .mock("POST", mockito::Matcher::Regex("^/v1/capabilities/[0-9a-f-]+/refresh$".to_string()))
+            .match_header("x-vault-timestamp", mockito::Matcher::Regex("^[0-9]+$".to_string()))
+            .match_header("x-vault-hmac", mockito::Matcher::Regex("^[A-Za-z0-9+/]{43}=$".to_string()))
+            .match_body(r#"{"ttl_seconds":60}"#)
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        config.auth.hmac_key_file = Some(key_file.path().to_path_buf());
+        let transport = HttpTransport::new(&config).await.unwrap();
+        assert!(!format!("{:?}", transport).contains("shared-secret"));
+
+        let identity = Identity::new("test-token".to_string());
+        let _ = transport.refresh_capability(&identity, uuid::Uuid::new_v4(), Duration::from_secs(60)).await;
+        refresh.assert_async().await;
+
+        // The tag covers the timestamp and body, keyed without the trailing newline
+        let tag = request_hmac(b"shared-secret", 1700000000, br#"{"ttl_seconds":60}"#);
+        let tag = base64::engine::general_purpose::STANDARD.decode(tag).unwrap();
+        assert!(crate::crypto::verify_hmac(b"shared-secret", br#"1700000000.{"ttl_seconds":60}"#, &tag));
+        assert!(!crate::crypto::verify_hmac(b"shared-secret", br#"1700000001.{"ttl_seconds":60}"#, &tag));
+    }
+
+    #[tokio::test]
+    async fn test_http_check_capability() {
+        let mut server = mockito::Server::new_async().await;
+        let check = server
+            .mock("POST", "/v1/capabilities/check")
+            .match_header("x-vault-identity", "test-token")
+            .with_status(200)
+            .with_body(r#"{"allowed":false,"reason":"policy ci-readonly forbids write on tls"}"#)
+            .create_async()
+            .await;
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        let transport = HttpTransport::new(&config).await.unwrap();
+
+        let request = CapabilityRequest::new(
+            crate::capability::Domain::Tls,
+            crate::capability::Action::Write,
+            "api.example.com".to_string(),
+            crate::capability::CapabilityContext::default(),
+            Duration::from_secs(300),
+        );
+        let decision = transport
+            .check_capability(&Identity::new("test-token".to_string()), &request)
+            .await
+            .unwrap();
+        check.assert_async().await;
+
+        assert!(!decision.allowed);
+        assert_eq!(decision.reason.as_deref(), Some("policy ci-readonly forbids write on tls"));
+        assert_eq!(decision.granted_ttl(), None);
+    }
+
+    #[tokio::test]
+    async fn test_http_standby_redirects_to_active() {
+        let capability = Capability::new(
+            crate::capability::Domain::Tls,
+            crate::capability::Action::Write,
+            "api.example.com".to_string(),
+            crate::capability::CapabilityContext::default(),
+            Duration::from_secs(300),
+            "vault".to_string(),
+            "client".to_string(),
+        );
+        let request = CapabilityRequest::new(
+            crate::capability::Domain::Tls,
+            crate::capability::Action::Write,
+            "api.example.com".to_string(),
+            crate::capability::CapabilityContext::default(),
+            Duration::from_secs(300),
+        );
+        let identity = Identity::new("test-token".to_string());
+
+        let mut active = mockito::Server::new_async().await;
+        let issue = active
+            .mock("POST", "/v1/capabilities")
+            .match_header("x-vault-identity", "test-token")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"target": "api.example.com"})))
+            .with_status(200)
+            .with_body(serde_json::to_string(&capability).unwrap())
+            .expect(2)
+            .create_async()
+            .await;
+
+        // Redirect naming the active node
+        let mut standby = mockito::Server::new_async().await;
+        let redirect = standby
+            .mock("POST", "/v1/capabilities")
+            .with_status(307)
+            .with_header("location", &format!("{}/v1/capabilities", active.url()))
+            .expect(2)
+            .create_async()
+            .await;
+        let mut config = crate::config::Config::default();
+        config.endpoint = standby.url();
+
+        // Nodes outside the configured cluster never see the credentials
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let err = transport.request_capability(&identity, &request).await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::Transport(TransportError::Protocol(msg)) if msg.contains("cluster_addresses")));
+
+        config.cluster_addresses = vec![active.url()];
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let issued = transport.request_capability(&identity, &request).await.unwrap();
+        assert_eq!(issued.id, capability.id);
+        redirect.assert_async().await;
+
+        // Standby body, with the active node found through the status endpoint
+        let mut standby = mockito::Server::new_async().await;
+        standby
+            .mock("POST", "/v1/capabilities")
+            .with_status(503)
+            .with_body(r#"{"standby":true}"#)
+            .create_async()
+            .await;
+        standby
+            .mock("GET", "/v1/status")
+            .with_status(200)
+            .with_body(serde_json::json!({
+                "version": "1.0.0",
+                "server_time": chrono::Utc::now(),
+                "initialized": true,
+                "sealed": false,
+                "standby": true,
+                "performance_mode": null,
+                "available_storage": null,
+                "total_storage": null,
+                "leader_address": active.url(),
+            }).to_string())
+            .create_async()
+            .await;
+        config.endpoint = standby.url();
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let issued = transport.request_capability(&identity, &request).await.unwrap();
+        assert_eq!(issued.id, capability.id);
+        issue.assert_async().await;
+
+        // Disabled, the standby's refusal is an error
+        config.follow_redirects_to_active = false;
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let err = transport.request_capability(&identity, &request).await.unwrap_err();
+        assert!(err.to_string().contains("503"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_http_follows_same_origin_redirects() {
+        let status = serde_json::json!({
+            "version": "1.0.0",
+            "server_time": chrono::Utc::now(),
+            "initialized": true,
+            "sealed": false,
+            "standby": false,
+            "performance_mode": null,
+            "available_storage": null,
+            "total_storage": null,
+            "leader_address": null,
+        }).to_string();
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/status")
+            .with_status(302)
+            .with_header("location", "/v2/status")
+            .create_async()
+            .await;
+        let moved = server
+            .mock("GET", "/v2/status")
+            .with_status(200)
+            .with_body(&status)
+            .create_async()
+            .await;
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        assert!(HttpTransport::new(&config).await.unwrap().status().await.is_ok());
+        moved.assert_async().await;
+
+        // Credentials never follow a redirect to another origin
+        let mut elsewhere = mockito::Server::new_async().await;
+        let leaked = elsewhere.mock("GET", "/v1/status").expect(0).create_async().await;
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/status")
+            .with_status(302)
+            .with_header("location", &format!("{}/v1/status", elsewhere.url()))
+            .create_async()
+            .await;
+        config.endpoint = server.url();
+        assert!(HttpTransport::new(&config).await.unwrap().status().await.is_err());
+        leaked.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_http_namespace_header() {
+        let mut server = mockito::Server::new_async().await;
+        let namespaced = server
+            .mock("GET", "/v1/health")
+            .match_header("x-vault-namespace", "tenant-a/prod")
+            .with_status(500)
+            .create_async()
+            .await;
+        let root = server
+            .mock("GET", "/v1/status")
+            .match_header("x-vault-namespace", mockito::Matcher::Missing)
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        config.namespace = Some("tenant-a/prod".to_string());
+        let _ = HttpTransport::new(&config).await.unwrap().health_check().await;
+        namespaced.assert_async().await;
+
+        config.namespace = None;
+        let _ = HttpTransport::new(&config).await.unwrap().status().await;
+        root.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_http_ping() {
+        let mut server = mockito::Server::new_async().await;
+        let live = server
+            .mock("HEAD", "/v1/sys/health")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = server.url();
+        let transport = HttpTransport::new(&config).await.unwrap();
+        transport.ping().await.unwrap();
+        live.assert_async().await;
+
+        let mut sealed = mockito::Server::new_async().await;
+        sealed
+            .mock("HEAD", "/v1/sys/health")
+            .with_status(503)
+            .create_async()
+            .await;
+        config.endpoint = sealed.url();
+        let err = HttpTransport::new(&config).await.unwrap().ping().await.unwrap_err();
+        assert!(matches!(err.root(), VaultError::Server(_)), "{:?}", err);
+        assert!(err.to_string().contains("sealed or standby"), "{}", err);
+    }
+
+    /// Read one HTTP/1.1 request, returning its head and (still encoded) body
+    async fn read_request(socket: &mut tokio::net::TcpStream) -> (String, Vec<u8>) {
+        use tokio::io::AsyncReadExt;
+
+        let mut data = Vec::new();
+        let mut buf = [0u8; 4096];
+        let head_end = loop {
+            if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+            let n = socket.read(&mut buf).await.unwrap();
+            assert!(n > 0, "connection closed before the request head");
+            data.extend_from_slice(&buf[..n]);
+        };
+        let head = String::from_utf8_lossy(&data[..head_end]).to_lowercase();
+        let length: usize = head
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length:"))
+            .map_or(0, |value| value.trim().parse().unwrap());
+        while data.len() < head_end + length {
+            let n = socket.read(&mut buf).await.unwrap();
+            data.extend_from_slice(&buf[..n]);
+        }
+        (head, data[head_end..head_end + length].to_vec())
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_http_compressed_batch_round_trip() {
+        use std::io::{Read, Write};
+        use tokio::io::AsyncWriteExt;
+
+        let requests: Vec<CapabilityRequest> = (0..20)
+            .map(|i| CapabilityRequest::new(
+                crate::capability::Domain::Database,
+                crate::capability::Action::Read,
+                format!("orders-replica-{}", i),
+                crate::capability::CapabilityContext::default(),
+                Duration::from_secs(60),
+            ))
+            .collect();
+        let issued: Vec<Capability> = requests
+            .iter()
+            .map(|request| Capability::new(
+                request.domain.clone(),
+                request.action.clone(),
+                request.target.clone(),
+                request.context.clone(),
+                request.ttl,
+                "vault".to_string(),
+                "svc".to_string(),
+            ))
+            .collect();
+
+        // Vault stand-in that decompresses the batch and answers with a gzip response
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (seen_tx, mut seen_rx) = tokio::sync::mpsc::unbounded_channel();
+        let response = serde_json::json!({
+            "results": issued.iter().map(|capability| serde_json::json!({ "capability": capability })).collect::<Vec<_>>()
+        });
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let (head, body) = read_request(&mut socket).await;
+                let reply = if head.contains("content-encoding: gzip") {
+                    let mut json = Vec::new();
+                    flate2::read::GzDecoder::new(body.as_slice()).read_to_end(&mut json).unwrap();
+                    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                    encoder.write_all(response.to_string().as_bytes()).unwrap();
+                    let gzipped = encoder.finish().unwrap();
+                    let _ = seen_tx.send((head, json));
+
+                    let mut reply = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-encoding: gzip\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                        gzipped.len()
+                    ).into_bytes();
+                    reply.extend_from_slice(&gzipped);
+                    reply
+                } else {
+                    let _ = seen_tx.send((head, body));
+                    b"HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_vec()
+                };
+                let _ = socket.write_all(&reply).await;
+            }
+        });
+
+        let mut config = crate::config::Config::default();
+        config.endpoint = format!("http://{}", addr);
+        config.compression = Some(crate::config::CompressionConfig::default());
+        let transport = HttpTransport::new(&config).await.unwrap();
+        let identity = Identity::new("test-token".to_string());
+
+        let results = transport.request_capabilities(&identity, &requests).await.unwrap();
+        let ids: Vec<uuid::Uuid> = results.into_iter().map(|result| result.unwrap().id).collect();
+        assert_eq!(ids, issued.iter().map(|capability| capability.id).collect::<Vec<_>>());
+
+        let (head, json) = seen_rx.recv().await.unwrap();
+        assert!(head.starts_with("post /v1/capabilities/batch "));
+        assert!(head.contains("accept-encoding: gzip"));
+        let sent: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(sent["requests"].as_array().unwrap().len(), 20);
+        assert_eq!(sent["requests"][19]["target"], "orders-replica-19");
+
+        // Below the threshold the body goes out as plain JSON
+        let _ = transport.refresh_capability(&identity, issued[0].id, Duration::from_secs(60)).await;
+        let (head, body) = seen_rx.recv().await.unwrap();
+        assert!(!head.contains("content-encoding"));
+        assert_eq!(body, br#"{"ttl_seconds":60}"#);
+    }
+}